@@ -0,0 +1,184 @@
+use std::collections::BTreeSet;
+use std::fmt;
+
+use bevy::asset::io::Reader;
+use bevy::asset::{AssetLoader, LoadContext};
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::game::Difficulty;
+
+/// Path of the achievement list, relative to the assets directory
+pub const ACHIEVEMENTS_PATH: &str = "config/main.achievements.ron";
+
+/// Every achievement the game knows about
+#[derive(Asset, TypePath, Debug, Clone, Deserialize)]
+pub struct AchievementList {
+    pub achievements: Vec<Achievement>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct Achievement {
+    /// Stable key saved once unlocked; never rename a shipped one
+    pub id: String,
+    pub name: String,
+    pub description: String,
+    pub goal: Goal,
+}
+
+/// What has to happen to unlock an achievement
+#[derive(Debug, Clone, Copy, PartialEq, Deserialize)]
+pub enum Goal {
+    /// Win any round
+    Win,
+    /// Win a round without the player sneezing
+    WinWithoutSneezing,
+    /// Win a round on this difficulty
+    WinOnDifficulty(Difficulty),
+    /// Win a round in at most this many seconds
+    WinWithin(f32),
+    /// Keep every flower head Blissed for this many seconds in a row
+    AllHeadsBlissed(f32),
+    /// Have the player collect `count` caches within `seconds`
+    CachesWithin { count: usize, seconds: f32 },
+    /// Have the player sneeze this many times in one round
+    Sneezes(u32),
+}
+
+/// How a won round went, for the goals checked at the end of a round
+#[derive(Debug, Clone, Copy)]
+pub struct WinSummary {
+    pub seconds: f32,
+    pub sneezes: u32,
+    pub difficulty: Difficulty,
+}
+
+impl Goal {
+    /// Whether winning a round like `summary` meets this goal
+    pub fn met_by_win(&self, summary: &WinSummary) -> bool {
+        match *self {
+            Goal::Win => true,
+            Goal::WinWithoutSneezing => summary.sneezes == 0,
+            Goal::WinOnDifficulty(difficulty) => summary.difficulty == difficulty,
+            Goal::WinWithin(seconds) => summary.seconds <= seconds,
+            _ => false,
+        }
+    }
+}
+
+/// Ids of the achievements the player has earned, saved between sessions
+#[derive(Resource, Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct UnlockedAchievements {
+    pub ids: BTreeSet<String>,
+}
+
+/// Reasons an achievement file can be rejected
+#[derive(Debug)]
+pub enum AchievementError {
+    Io(std::io::Error),
+    Parse(ron::error::SpannedError),
+    DuplicateId(String),
+}
+
+impl fmt::Display for AchievementError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            AchievementError::Io(err) => write!(f, "could not read achievements: {err}"),
+            AchievementError::Parse(err) => write!(f, "could not parse achievements: {err}"),
+            AchievementError::DuplicateId(id) => {
+                write!(f, "achievement id '{id}' is used more than once")
+            }
+        }
+    }
+}
+
+impl std::error::Error for AchievementError {}
+
+impl From<std::io::Error> for AchievementError {
+    fn from(err: std::io::Error) -> Self {
+        AchievementError::Io(err)
+    }
+}
+
+impl From<ron::error::SpannedError> for AchievementError {
+    fn from(err: ron::error::SpannedError) -> Self {
+        AchievementError::Parse(err)
+    }
+}
+
+impl AchievementList {
+    pub fn from_ron(source: &[u8]) -> Result<Self, AchievementError> {
+        let list: AchievementList = ron::de::from_bytes(source)?;
+        let mut ids = BTreeSet::new();
+        for achievement in &list.achievements {
+            if !ids.insert(achievement.id.as_str()) {
+                return Err(AchievementError::DuplicateId(achievement.id.clone()));
+            }
+        }
+        Ok(list)
+    }
+}
+
+/// Loads `AchievementList` from `.achievements.ron` files
+#[derive(Default)]
+pub struct AchievementLoader;
+
+impl AssetLoader for AchievementLoader {
+    type Asset = AchievementList;
+    type Settings = ();
+    type Error = AchievementError;
+
+    async fn load(
+        &self,
+        reader: &mut dyn Reader,
+        _settings: &(),
+        _load_context: &mut LoadContext<'_>,
+    ) -> Result<Self::Asset, Self::Error> {
+        let mut bytes = Vec::new();
+        reader.read_to_end(&mut bytes).await?;
+        AchievementList::from_ron(&bytes)
+    }
+
+    fn extensions(&self) -> &[&str] {
+        &["achievements.ron"]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn shipped_achievements_parse() {
+        let list =
+            AchievementList::from_ron(include_bytes!("../../assets/config/main.achievements.ron"))
+                .unwrap();
+        assert!(!list.achievements.is_empty());
+    }
+
+    #[test]
+    fn duplicate_ids_are_rejected() {
+        let source = br#"(achievements: [
+            (id: "a", name: "A", description: "", goal: Win),
+            (id: "a", name: "B", description: "", goal: Win),
+        ])"#;
+        assert!(matches!(
+            AchievementList::from_ron(source),
+            Err(AchievementError::DuplicateId(_))
+        ));
+    }
+
+    #[test]
+    fn win_goals_check_the_summary() {
+        let summary = WinSummary {
+            seconds: 90.0,
+            sneezes: 1,
+            difficulty: Difficulty::Cozy,
+        };
+        assert!(Goal::Win.met_by_win(&summary));
+        assert!(!Goal::WinWithoutSneezing.met_by_win(&summary));
+        assert!(Goal::WinOnDifficulty(Difficulty::Cozy).met_by_win(&summary));
+        assert!(!Goal::WinWithin(60.0).met_by_win(&summary));
+        assert!(!Goal::Sneezes(1).met_by_win(&summary));
+    }
+}
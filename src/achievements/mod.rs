@@ -0,0 +1,149 @@
+//! Achievements defined in `assets/config/main.achievements.ron`, unlocked
+//! from gameplay events and round results, and saved with the rest of the
+//! player's progress.
+
+mod definition;
+mod progress;
+
+pub use definition::*;
+pub use progress::*;
+
+use bevy::prelude::*;
+
+use crate::ai::PlayerBee;
+use crate::bee::SneezeCount;
+use crate::effects::SneezeEvent;
+use crate::flower::{FlowerHead, RizzBehavior, TickleEvent};
+use crate::game::{Difficulty, GameState, SessionTimer, SimulationTick};
+use crate::replay::ReplayPlayback;
+
+/// Sent once when an achievement is earned, for the toast
+#[derive(Event, Debug, Clone)]
+pub struct AchievementUnlocked {
+    pub name: String,
+    pub description: String,
+}
+
+#[derive(Resource)]
+pub struct AchievementsHandle(pub Handle<AchievementList>);
+
+pub struct AchievementsPlugin;
+
+impl Plugin for AchievementsPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_asset::<AchievementList>()
+            .init_asset_loader::<AchievementLoader>()
+            .init_resource::<UnlockedAchievements>()
+            .init_resource::<RoundProgress>()
+            .add_event::<AchievementUnlocked>()
+            .add_systems(Startup, load_achievements)
+            .add_systems(OnEnter(GameState::Playing), reset_round_progress)
+            .add_systems(
+                Update,
+                (track_round_progress, check_round_goals)
+                    .chain()
+                    .run_if(in_state(GameState::Playing))
+                    .run_if(not(resource_exists::<ReplayPlayback>)),
+            )
+            .add_systems(
+                OnEnter(GameState::Won),
+                check_win_goals.run_if(not(resource_exists::<ReplayPlayback>)),
+            );
+    }
+}
+
+pub fn load_achievements(mut commands: Commands, asset_server: Res<AssetServer>) {
+    commands.insert_resource(AchievementsHandle(asset_server.load(ACHIEVEMENTS_PATH)));
+}
+
+pub fn reset_round_progress(mut progress: ResMut<RoundProgress>) {
+    *progress = RoundProgress::default();
+}
+
+/// Note the player's cache pickups and sneezes, and how long every flower
+/// head has been Blissed
+pub fn track_round_progress(
+    mut tickles: EventReader<TickleEvent>,
+    mut sneezes: EventReader<SneezeEvent>,
+    players: Query<(), With<PlayerBee>>,
+    heads: Query<Option<&RizzBehavior>, With<FlowerHead>>,
+    tick: Res<SimulationTick>,
+    mut progress: ResMut<RoundProgress>,
+) {
+    for event in tickles.read() {
+        if players.contains(event.bee) {
+            progress.cache_ticks.push(tick.0);
+        }
+    }
+
+    for event in sneezes.read() {
+        if players.contains(event.bee_entity) {
+            progress.sneezes += 1;
+        }
+    }
+
+    let all_blissed = !heads.is_empty()
+        && heads
+            .iter()
+            .all(|behavior| behavior == Some(&RizzBehavior::Blissed));
+    progress.track_bliss(tick.0, all_blissed);
+}
+
+fn unlock(
+    achievement: &Achievement,
+    unlocked: &mut UnlockedAchievements,
+    events: &mut EventWriter<AchievementUnlocked>,
+) {
+    info!("Achievement unlocked: {}", achievement.name);
+    unlocked.ids.insert(achievement.id.clone());
+    events.send(AchievementUnlocked {
+        name: achievement.name.clone(),
+        description: achievement.description.clone(),
+    });
+}
+
+/// Unlock goals met during play, like cache streaks and long Blissed spells
+pub fn check_round_goals(
+    handle: Option<Res<AchievementsHandle>>,
+    lists: Res<Assets<AchievementList>>,
+    progress: Res<RoundProgress>,
+    tick: Res<SimulationTick>,
+    mut unlocked: ResMut<UnlockedAchievements>,
+    mut events: EventWriter<AchievementUnlocked>,
+) {
+    let Some(list) = handle.and_then(|handle| lists.get(&handle.0)) else {
+        return;
+    };
+
+    for achievement in &list.achievements {
+        if !unlocked.ids.contains(&achievement.id) && progress.meets(&achievement.goal, tick.0) {
+            unlock(achievement, &mut unlocked, &mut events);
+        }
+    }
+}
+
+/// Unlock goals that depend on how the round was won
+pub fn check_win_goals(
+    handle: Option<Res<AchievementsHandle>>,
+    lists: Res<Assets<AchievementList>>,
+    timer: Res<SessionTimer>,
+    difficulty: Res<Difficulty>,
+    players: Query<Option<&SneezeCount>, With<PlayerBee>>,
+    mut unlocked: ResMut<UnlockedAchievements>,
+    mut events: EventWriter<AchievementUnlocked>,
+) {
+    let Some(list) = handle.and_then(|handle| lists.get(&handle.0)) else {
+        return;
+    };
+
+    let summary = WinSummary {
+        seconds: timer.elapsed,
+        sneezes: players.iter().flatten().map(|count| count.count).sum(),
+        difficulty: *difficulty,
+    };
+    for achievement in &list.achievements {
+        if !unlocked.ids.contains(&achievement.id) && achievement.goal.met_by_win(&summary) {
+            unlock(achievement, &mut unlocked, &mut events);
+        }
+    }
+}
@@ -0,0 +1,98 @@
+use bevy::prelude::*;
+
+use super::Goal;
+use crate::game::SIMULATION_HZ;
+
+fn seconds_to_ticks(seconds: f32) -> u32 {
+    (seconds as f64 * SIMULATION_HZ).round() as u32
+}
+
+/// What the player has done so far this round, for goals that can be met
+/// before the round ends. Times are simulation ticks, so pausing doesn't count.
+#[derive(Resource, Debug, Default)]
+pub struct RoundProgress {
+    /// Ticks at which the player collected a cache
+    pub cache_ticks: Vec<u32>,
+    /// Tick since which every flower head has been Blissed
+    pub blissed_since: Option<u32>,
+    pub sneezes: u32,
+}
+
+impl RoundProgress {
+    /// Caches collected in the `window` ticks up to `now`
+    pub fn caches_within(&self, now: u32, window: u32) -> usize {
+        let start = now.saturating_sub(window);
+        self.cache_ticks
+            .iter()
+            .filter(|tick| (start..=now).contains(*tick))
+            .count()
+    }
+
+    /// Update the Blissed streak: `all_blissed` is whether every head is
+    /// Blissed at tick `now`. A streak from before the tick count was rewound
+    /// (e.g. the garden respawned mid-round) starts over.
+    pub fn track_bliss(&mut self, now: u32, all_blissed: bool) {
+        self.blissed_since = match (all_blissed, self.blissed_since) {
+            (true, Some(since)) if since <= now => Some(since),
+            (true, _) => Some(now),
+            (false, _) => None,
+        };
+    }
+
+    /// Whether `goal` has been met by tick `now`. Goals that need a win are
+    /// never met mid-round.
+    pub fn meets(&self, goal: &Goal, now: u32) -> bool {
+        match *goal {
+            Goal::AllHeadsBlissed(seconds) => self
+                .blissed_since
+                .is_some_and(|since| now.saturating_sub(since) >= seconds_to_ticks(seconds)),
+            Goal::CachesWithin { count, seconds } => {
+                self.caches_within(now, seconds_to_ticks(seconds)) >= count
+            }
+            Goal::Sneezes(count) => self.sneezes >= count,
+            _ => false,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn caches_only_count_inside_the_window() {
+        let progress = RoundProgress {
+            cache_ticks: vec![0, 200, 400, 500],
+            ..default()
+        };
+        let goal = Goal::CachesWithin {
+            count: 3,
+            seconds: 5.0,
+        };
+        assert!(!progress.meets(&goal, 310));
+        assert!(progress.meets(&goal, 500));
+        assert!(!progress.meets(&goal, 800));
+    }
+
+    #[test]
+    fn bliss_streak_restarts_when_broken() {
+        let goal = Goal::AllHeadsBlissed(1.0);
+        let mut progress = RoundProgress::default();
+        progress.track_bliss(0, true);
+        progress.track_bliss(30, false);
+        progress.track_bliss(40, true);
+        assert!(!progress.meets(&goal, 80));
+        assert!(progress.meets(&goal, 100));
+    }
+
+    #[test]
+    fn bliss_streak_restarts_when_the_tick_count_rewinds() {
+        let goal = Goal::AllHeadsBlissed(1.0);
+        let mut progress = RoundProgress::default();
+        progress.track_bliss(500, true);
+        assert!(!progress.meets(&goal, 10));
+
+        progress.track_bliss(10, true);
+        assert!(progress.meets(&goal, 70));
+    }
+}
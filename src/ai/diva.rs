@@ -1,12 +1,9 @@
 use bevy::prelude::*;
 
-use crate::bee::{AllergyMeter, WiggleCooldown, Wiggling};
-use crate::flower::FlowerHead;
-
-const AI_WIGGLE_THRESHOLD: f32 = 50.0;
-const AI_MOVE_SPEED: f32 = 100.0;
-const SAFE_DISTANCE: f32 = 80.0;
-const OPTIMAL_WIGGLE_RANGE: f32 = 100.0;
+use super::PlayerBee;
+use crate::bee::{AllergyMeter, Bee, Role, WiggleCooldown, Wiggling};
+use crate::flower::{AttentionSnap, FlowerHead};
+use crate::game::{GameConfig, InterpolatedTransform};
 
 /// Marker for AI Diva companion
 #[derive(Component)]
@@ -18,6 +15,7 @@ pub struct AiDivaBundle {
     pub diva: AiDiva,
     pub allergy_meter: AllergyMeter,
     pub wiggle_cooldown: WiggleCooldown,
+    pub interpolated: InterpolatedTransform,
 }
 
 impl Default for AiDiva {
@@ -30,9 +28,11 @@ impl Default for AiDiva {
 pub fn ai_diva_movement(
     mut divas: Query<&mut Transform, (With<AiDiva>, Without<Wiggling>)>,
     heads: Query<&GlobalTransform, With<FlowerHead>>,
+    config: Res<GameConfig>,
     time: Res<Time>,
 ) {
     let delta = time.delta_secs();
+    let diva = &config.companion.diva;
 
     for mut transform in &mut divas {
         let diva_pos = transform.translation.truncate();
@@ -55,11 +55,11 @@ pub fn ai_diva_movement(
         let to_center = center - diva_pos;
         let distance_to_center = to_center.length();
 
-        // Target: stay at OPTIMAL_WIGGLE_RANGE from center
-        let target_pos = if distance_to_center < OPTIMAL_WIGGLE_RANGE {
+        // Target: stay at optimal range from center
+        let target_pos = if distance_to_center < diva.optimal_range {
             // Too close - move away slightly
             diva_pos - to_center.normalize_or_zero() * 20.0
-        } else if distance_to_center > OPTIMAL_WIGGLE_RANGE * 1.5 {
+        } else if distance_to_center > diva.optimal_range * 1.5 {
             // Too far - move closer
             diva_pos + to_center.normalize_or_zero() * 20.0
         } else {
@@ -72,9 +72,9 @@ pub fn ai_diva_movement(
         for head_pos in &head_positions {
             let to_head = *head_pos - diva_pos;
             let dist = to_head.length();
-            if dist < SAFE_DISTANCE && dist > 0.0 {
+            if dist < diva.safe_distance && dist > 0.0 {
                 // Push away from this head
-                avoidance -= to_head.normalize() * (SAFE_DISTANCE - dist);
+                avoidance -= to_head.normalize() * (diva.safe_distance - dist);
             }
         }
 
@@ -83,8 +83,8 @@ pub fn ai_diva_movement(
         let direction = (final_target - diva_pos).normalize_or_zero();
 
         // Move toward target
-        transform.translation.x += direction.x * AI_MOVE_SPEED * delta;
-        transform.translation.y += direction.y * AI_MOVE_SPEED * delta;
+        transform.translation.x += direction.x * diva.move_speed * delta;
+        transform.translation.y += direction.y * diva.move_speed * delta;
     }
 }
 
@@ -102,7 +102,10 @@ pub fn ai_diva_wiggle(
         (With<AiDiva>, Without<Wiggling>),
     >,
     heads: Query<(&GlobalTransform, &FlowerHead)>,
+    config: Res<GameConfig>,
 ) {
+    let diva = &config.companion.diva;
+
     for (entity, global_transform, local_transform, cooldown) in &mut divas {
         // Check cooldown
         if let Some(cd) = cooldown {
@@ -117,14 +120,44 @@ pub fn ai_diva_wiggle(
         let needs_wiggle = heads.iter().any(|(head_gt, head)| {
             let head_pos = head_gt.translation().truncate();
             let distance = diva_pos.distance(head_pos);
-            distance <= 150.0 && head.rizz < AI_WIGGLE_THRESHOLD
+            distance <= diva.wiggle_range && head.rizz < diva.wiggle_threshold
         });
 
         if needs_wiggle {
             // Start wiggling
-            commands
-                .entity(entity)
-                .insert(Wiggling::new(local_transform.translation.x));
+            commands.entity(entity).insert(Wiggling::new(
+                local_transform.translation.x,
+                config.wiggle.duration,
+            ));
+        }
+    }
+}
+
+/// A player playing the Diva turns the heads in wiggle range toward them
+/// with each wiggle, drawing them away from the rest of the team
+#[allow(clippy::type_complexity)]
+pub fn diva_players_attract_heads(
+    mut commands: Commands,
+    divas: Query<(&GlobalTransform, &Bee), (With<PlayerBee>, Added<Wiggling>)>,
+    heads: Query<(Entity, &GlobalTransform), With<FlowerHead>>,
+    config: Res<GameConfig>,
+) {
+    for (diva_transform, bee) in &divas {
+        if bee.role != Role::Diva {
+            continue;
+        }
+        let diva_pos = diva_transform.translation().truncate();
+        for (head, head_transform) in &heads {
+            let distance = head_transform.translation().truncate().distance(diva_pos);
+            if distance <= config.companion.diva.wiggle_range {
+                commands.entity(head).insert(AttentionSnap {
+                    target: diva_pos,
+                    timer: Timer::from_seconds(
+                        config.rizz.attention_snap_duration,
+                        TimerMode::Once,
+                    ),
+                });
+            }
         }
     }
 }
@@ -1,27 +1,14 @@
 use bevy::prelude::*;
 
-use crate::bee::AllergyMeter;
+use crate::bee::{AllergyMeter, Bee, Emote, Emoting, Role};
 use crate::flower::FlowerHead;
-
-const HEAL_THRESHOLD: f32 = 60.0;
-const HEAL_RATE: f32 = 20.0;
-const HEAL_RANGE: f32 = 40.0;
-const AI_MOVE_SPEED: f32 = 120.0;
-const HEALER_ALLERGY_MULTIPLIER: f32 = 2.0;
+use crate::game::{GameConfig, InterpolatedTransform};
 
 /// Marker for AI Healer companion
-#[derive(Component)]
+#[derive(Component, Default)]
 pub struct AiHealer {
-    /// Allergy sensitivity multiplier (2x for healer)
-    pub sensitivity: f32,
-}
-
-impl Default for AiHealer {
-    fn default() -> Self {
-        Self {
-            sensitivity: HEALER_ALLERGY_MULTIPLIER,
-        }
-    }
+    /// On its way to a player whose allergy is high
+    pub helping: bool,
 }
 
 /// Bundle for spawning AI Healer
@@ -29,27 +16,34 @@ impl Default for AiHealer {
 pub struct AiHealerBundle {
     pub healer: AiHealer,
     pub allergy_meter: AllergyMeter,
+    pub interpolated: InterpolatedTransform,
 }
 
 /// Marker for the player bee (to distinguish from AI)
 #[derive(Component)]
 pub struct PlayerBee;
 
-/// AI Healer movement - move toward player when their allergy is high
+/// AI Healer movement - move toward the most allergic player when their
+/// allergy is high
 #[allow(clippy::type_complexity)]
 pub fn ai_healer_movement(
     mut healers: Query<&mut Transform, With<AiHealer>>,
     players: Query<(&Transform, &AllergyMeter), (With<PlayerBee>, Without<AiHealer>)>,
+    config: Res<GameConfig>,
     time: Res<Time>,
 ) {
     let delta = time.delta_secs();
+    let healer = &config.companion.healer;
 
-    let Some((player_transform, player_allergy)) = players.iter().next() else {
+    let Some((player_transform, player_allergy)) = players
+        .iter()
+        .max_by(|(_, a), (_, b)| a.percentage().total_cmp(&b.percentage()))
+    else {
         return;
     };
 
     // Only move toward player if their allergy is high
-    if player_allergy.percentage() < HEAL_THRESHOLD / 100.0 {
+    if player_allergy.percentage() < healer.heal_threshold / 100.0 {
         return;
     }
 
@@ -61,50 +55,94 @@ pub fn ai_healer_movement(
         let distance = to_player.length();
 
         // Already close enough
-        if distance <= HEAL_RANGE {
+        if distance <= healer.heal_range {
             continue;
         }
 
         // Move toward player
         let direction = to_player.normalize_or_zero();
-        healer_transform.translation.x += direction.x * AI_MOVE_SPEED * delta;
-        healer_transform.translation.y += direction.y * AI_MOVE_SPEED * delta;
+        healer_transform.translation.x += direction.x * healer.move_speed * delta;
+        healer_transform.translation.y += direction.y * healer.move_speed * delta;
     }
 }
 
-/// AI Healer healing - reduce player allergy when adjacent
+/// Healing - reduce player allergy next to a healer. AI healers heal every
+/// player; a player playing the Healer heals their teammates.
 #[allow(clippy::type_complexity)]
-pub fn ai_healer_heal(
-    healers: Query<&Transform, With<AiHealer>>,
-    mut players: Query<(&Transform, &mut AllergyMeter), (With<PlayerBee>, Without<AiHealer>)>,
+pub fn heal_players(
+    healers: Query<
+        (Entity, &Transform, Option<&Bee>, Has<AiHealer>),
+        Or<(With<AiHealer>, With<PlayerBee>)>,
+    >,
+    mut players: Query<(Entity, &Transform, &mut AllergyMeter), With<PlayerBee>>,
+    config: Res<GameConfig>,
     time: Res<Time>,
 ) {
     let delta = time.delta_secs();
-
-    for (player_transform, mut player_allergy) in &mut players {
+    let healer = &config.companion.healer;
+    let healers: Vec<(Entity, Vec2)> = healers
+        .iter()
+        .filter(|(_, _, bee, ai)| *ai || bee.is_some_and(|bee| bee.role == Role::Healer))
+        .map(|(entity, transform, _, _)| (entity, transform.translation.truncate()))
+        .collect();
+
+    for (player, player_transform, mut player_allergy) in &mut players {
         let player_pos = player_transform.translation.truncate();
 
-        // Check if any healer is close enough
-        let healer_nearby = healers.iter().any(|healer_transform| {
-            let healer_pos = healer_transform.translation.truncate();
-            player_pos.distance(healer_pos) <= HEAL_RANGE
+        // Check if any healer other than the player is close enough
+        let healer_nearby = healers.iter().any(|(healer_entity, healer_pos)| {
+            *healer_entity != player && player_pos.distance(*healer_pos) <= healer.heal_range
         });
 
         if healer_nearby && player_allergy.value > 0.0 {
-            player_allergy.value = (player_allergy.value - HEAL_RATE * delta).max(0.0);
+            player_allergy.value = (player_allergy.value - healer.heal_rate * delta).max(0.0);
+        }
+    }
+}
+
+/// Say "coming to help" when setting off toward an allergic player, so
+/// they know to hold on rather than flee
+#[allow(clippy::type_complexity)]
+pub fn ai_healer_chatter(
+    mut commands: Commands,
+    mut healers: Query<(Entity, &Transform, &mut AiHealer)>,
+    players: Query<(&Transform, &AllergyMeter), (With<PlayerBee>, Without<AiHealer>)>,
+    config: Res<GameConfig>,
+) {
+    let healer_config = &config.companion.healer;
+    let patient = players
+        .iter()
+        .max_by(|(_, a), (_, b)| a.percentage().total_cmp(&b.percentage()))
+        .filter(|(_, allergy)| allergy.percentage() >= healer_config.heal_threshold / 100.0)
+        .map(|(transform, _)| transform.translation.truncate());
+
+    for (entity, transform, mut healer) in &mut healers {
+        // Same test as `ai_healer_movement`, so the call goes out on the
+        // tick the healer sets off
+        let helping = patient.is_some_and(|patient| {
+            patient.distance(transform.translation.truncate()) > healer_config.heal_range
+        });
+        if helping && !healer.helping {
+            commands
+                .entity(entity)
+                .insert(Emoting::new(Emote::ComingToHelp));
         }
+        healer.helping = helping;
     }
 }
 
-/// Update healer allergy with 2x sensitivity
+/// Update healer allergy with extra sensitivity
 pub fn update_healer_allergy(
-    mut healers: Query<(&Transform, &mut AllergyMeter, &AiHealer)>,
+    mut healers: Query<(&Transform, &mut AllergyMeter), With<AiHealer>>,
     heads: Query<&GlobalTransform, With<FlowerHead>>,
+    config: Res<GameConfig>,
     time: Res<Time>,
 ) {
     let delta = time.delta_secs();
+    let allergy = &config.allergy;
+    let healer = &config.companion.healer;
 
-    for (transform, mut meter, healer) in &mut healers {
+    for (transform, mut meter) in &mut healers {
         let healer_pos = transform.translation.truncate();
 
         // Find nearest head
@@ -114,14 +152,15 @@ pub fn update_healer_allergy(
             .min_by(|a, b| a.partial_cmp(b).unwrap())
             .unwrap_or(f32::MAX);
 
-        // Allergy builds up based on proximity (with 2x sensitivity)
-        let proximity_threshold = 200.0;
-        if nearest_distance < proximity_threshold {
-            let buildup_rate = (1.0 - nearest_distance / proximity_threshold) * 50.0;
-            meter.value = (meter.value + buildup_rate * healer.sensitivity * delta).min(meter.max);
+        // Allergy builds up based on proximity (scaled by healer sensitivity)
+        if nearest_distance < allergy.proximity_threshold {
+            let buildup_rate = (1.0 - nearest_distance / allergy.proximity_threshold)
+                * healer.proximity_multiplier;
+            meter.value =
+                (meter.value + buildup_rate * healer.allergy_sensitivity * delta).min(meter.max);
         } else {
             // Decay when far
-            meter.value = (meter.value - 5.0 * delta).max(0.0);
+            meter.value = (meter.value - allergy.base_decay_rate * delta).max(0.0);
         }
     }
 }
@@ -13,12 +13,13 @@ pub struct AiPlugin;
 impl Plugin for AiPlugin {
     fn build(&self, app: &mut App) {
         app.add_systems(
-            Update,
+            FixedUpdate,
             (
                 ai_diva_movement,
                 ai_diva_wiggle,
-                ai_healer_movement,
-                ai_healer_heal,
+                diva_players_attract_heads,
+                (ai_healer_chatter, ai_healer_movement).chain(),
+                heal_players,
                 update_healer_allergy,
             )
                 .run_if(in_state(GameState::Playing)),
@@ -1,14 +1,11 @@
 use bevy::prelude::*;
 
-use super::Bee;
-use crate::flower::FlowerHead;
+use std::time::Duration;
 
-const WIGGLE_DURATION: f32 = 0.5;
-const WIGGLE_COOLDOWN: f32 = 2.0;
-const WIGGLE_RANGE: f32 = 150.0;
-const WIGGLE_RIZZ_BASE: f32 = 20.0;
-const WIGGLE_FREQUENCY: f32 = 20.0;
-const WIGGLE_AMPLITUDE: f32 = 10.0;
+use super::{Bee, PlayerInput, PlayerInputs, PlayerSlot};
+use crate::controls::{Action, PlayerActions};
+use crate::flower::FlowerHead;
+use crate::game::{GameConfig, WiggleConfig};
 
 /// Component for wiggle state
 #[derive(Component)]
@@ -18,9 +15,9 @@ pub struct Wiggling {
 }
 
 impl Wiggling {
-    pub fn new(original_x: f32) -> Self {
+    pub fn new(original_x: f32, duration: f32) -> Self {
         Self {
-            timer: Timer::from_seconds(WIGGLE_DURATION, TimerMode::Once),
+            timer: Timer::from_seconds(duration, TimerMode::Once),
             original_x,
         }
     }
@@ -33,46 +30,83 @@ pub struct WiggleCooldown {
 }
 
 impl WiggleCooldown {
-    pub fn start(&mut self) {
-        self.timer = Timer::from_seconds(WIGGLE_COOLDOWN, TimerMode::Once);
+    pub fn start(&mut self, duration: f32) {
+        self.timer = Timer::from_seconds(duration, TimerMode::Once);
     }
 
     pub fn is_ready(&self) -> bool {
         self.timer.finished() || self.timer.remaining_secs() == 0.0
     }
+
+    /// How much of the cooldown is left, from 1.0 just after a wiggle down
+    /// to 0.0 when ready
+    pub fn remaining_fraction(&self) -> f32 {
+        if self.is_ready() {
+            0.0
+        } else {
+            self.timer.fraction_remaining()
+        }
+    }
 }
 
-/// System to handle wiggle input
-#[allow(clippy::type_complexity)]
+/// How close to a bee a double-tap has to land to make it wiggle
+pub const DOUBLE_TAP_BEE_RADIUS: f32 = 48.0;
+
+/// System to capture wiggle input for the next simulation tick
 pub fn handle_wiggle_input(
-    mut commands: Commands,
-    keyboard: Res<ButtonInput<KeyCode>>,
-    mouse: Res<ButtonInput<MouseButton>>,
-    mut bees: Query<
-        (Entity, &Transform, Option<&mut WiggleCooldown>),
-        (With<Bee>, Without<Wiggling>),
-    >,
+    actions: Res<PlayerActions>,
+    camera_query: Query<(&Camera, &GlobalTransform)>,
+    bees: Query<(&GlobalTransform, &PlayerSlot), With<Bee>>,
+    mut inputs: ResMut<PlayerInputs>,
 ) {
-    // Check for spacebar or right-click (mobile can use double-tap handled differently)
-    let wiggle_pressed =
-        keyboard.just_pressed(KeyCode::Space) || mouse.just_pressed(MouseButton::Right);
+    for (player, actions) in actions.players.iter().enumerate() {
+        // Bound to spacebar, right-click and the gamepad's bottom face button
+        // by default; touch players double-tap their bee or use the
+        // on-screen button
+        if actions.just_pressed(Action::Wiggle) {
+            inputs.player_mut(player).wiggle = true;
+        }
 
-    if !wiggle_pressed {
-        return;
+        let Some(screen_pos) = actions.double_tap else {
+            continue;
+        };
+        let Ok((camera, camera_transform)) = camera_query.get_single() else {
+            continue;
+        };
+        let Ok(world_pos) = camera.viewport_to_world_2d(camera_transform, screen_pos) else {
+            continue;
+        };
+        let tapped_own_bee = bees.iter().any(|(bee, slot)| {
+            slot.0 == player
+                && bee.translation().truncate().distance(world_pos) <= DOUBLE_TAP_BEE_RADIUS
+        });
+        if tapped_own_bee {
+            inputs.player_mut(player).wiggle = true;
+        }
     }
+}
 
-    for (entity, transform, cooldown) in &mut bees {
-        // Check cooldown
-        if let Some(cd) = cooldown {
-            if !cd.is_ready() {
-                continue;
-            }
-        }
+/// Sideways offset of a bee `elapsed` seconds into a wiggle
+pub fn wiggle_offset(elapsed: f32, wiggle: &WiggleConfig) -> f32 {
+    (elapsed * wiggle.frequency).sin() * wiggle.amplitude
+}
+
+/// Whether `input` starts a wiggle: not while one is under way or the
+/// cooldown is still running
+pub fn starts_wiggle(input: &PlayerInput, wiggling: bool, cooldown_ready: bool) -> bool {
+    input.wiggle && !wiggling && cooldown_ready
+}
 
-        // Start wiggling
-        commands
-            .entity(entity)
-            .insert(Wiggling::new(transform.translation.x));
+/// Where a bee that started wiggling at `origin_x` is `elapsed` into the
+/// wiggle, and whether the wiggle is over. It ends back at `origin_x`.
+pub fn wiggle_step(origin_x: f32, elapsed: Duration, wiggle: &WiggleConfig) -> (f32, bool) {
+    if elapsed >= Duration::from_secs_f32(wiggle.duration) {
+        (origin_x, true)
+    } else {
+        (
+            origin_x + wiggle_offset(elapsed.as_secs_f32(), wiggle),
+            false,
+        )
     }
 }
 
@@ -81,20 +115,23 @@ pub fn update_wiggling(
     mut commands: Commands,
     mut bees: Query<(Entity, &GlobalTransform, &mut Transform, &mut Wiggling), With<Bee>>,
     mut heads: Query<(&GlobalTransform, &mut FlowerHead)>,
+    config: Res<GameConfig>,
     time: Res<Time>,
 ) {
+    let wiggle = &config.wiggle;
+
     for (entity, global_transform, mut transform, mut wiggling) in &mut bees {
         wiggling.timer.tick(time.delta());
+        let (x, finished) = wiggle_step(wiggling.original_x, wiggling.timer.elapsed(), wiggle);
+        transform.translation.x = x;
 
-        if wiggling.timer.finished() {
-            // Reset position and remove wiggling
-            transform.translation.x = wiggling.original_x;
+        if finished {
             commands.entity(entity).remove::<Wiggling>();
 
             // Apply cooldown
-            commands.entity(entity).insert(WiggleCooldown {
-                timer: Timer::from_seconds(WIGGLE_COOLDOWN, TimerMode::Once),
-            });
+            let mut cooldown = WiggleCooldown::default();
+            cooldown.start(wiggle.cooldown);
+            commands.entity(entity).insert(cooldown);
 
             // Apply rizz to nearby heads
             let bee_pos = global_transform.translation().truncate();
@@ -102,18 +139,13 @@ pub fn update_wiggling(
                 let head_pos = head_transform.translation().truncate();
                 let distance = bee_pos.distance(head_pos);
 
-                if distance <= WIGGLE_RANGE {
+                if distance <= wiggle.range {
                     // Rizz scaled by distance (more at close range)
-                    let distance_factor = 1.0 - (distance / WIGGLE_RANGE);
-                    let rizz_gain = WIGGLE_RIZZ_BASE * distance_factor;
-                    head.rizz = (head.rizz + rizz_gain).min(100.0);
+                    let distance_factor = 1.0 - (distance / wiggle.range);
+                    let rizz_gain = wiggle.rizz_base * distance_factor;
+                    head.rizz = (head.rizz + rizz_gain).min(config.rizz.max);
                 }
             }
-        } else {
-            // Oscillate side-to-side
-            let progress = wiggling.timer.elapsed_secs();
-            let offset = (progress * WIGGLE_FREQUENCY).sin() * WIGGLE_AMPLITUDE;
-            transform.translation.x = wiggling.original_x + offset;
         }
     }
 }
@@ -134,7 +166,20 @@ mod tests {
         let mut cooldown = WiggleCooldown::default();
         assert!(cooldown.is_ready()); // Initially ready
 
-        cooldown.start();
+        cooldown.start(2.0);
         assert!(!cooldown.is_ready()); // Not ready after starting
     }
+
+    #[test]
+    fn wiggle_cooldown_fraction_runs_down_to_zero() {
+        let mut cooldown = WiggleCooldown::default();
+        assert_eq!(cooldown.remaining_fraction(), 0.0);
+
+        cooldown.start(2.0);
+        cooldown.timer.tick(std::time::Duration::from_secs_f32(0.5));
+        assert_eq!(cooldown.remaining_fraction(), 0.75);
+
+        cooldown.timer.tick(std::time::Duration::from_secs(2));
+        assert_eq!(cooldown.remaining_fraction(), 0.0);
+    }
 }
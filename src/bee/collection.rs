@@ -3,15 +3,14 @@ use bevy::prelude::*;
 use super::{Bee, CollectedPollen};
 use crate::effects::CollectionEvent;
 use crate::flower::{CacheSpawnPoint, Pollen, TickleEvent};
-
-const COLLECTION_RADIUS: f32 = 25.0;
-const CACHE_COLLECTION_RADIUS: f32 = 30.0;
+use crate::game::GameConfig;
 
 pub fn collect_pollen(
     mut commands: Commands,
     mut bees: Query<(&Transform, &mut CollectedPollen), With<Bee>>,
     pollen: Query<(Entity, &Transform, &Pollen)>,
     mut collection_events: EventWriter<CollectionEvent>,
+    config: Res<GameConfig>,
 ) {
     for (bee_transform, mut collected) in &mut bees {
         let bee_pos = bee_transform.translation.truncate();
@@ -20,7 +19,7 @@ pub fn collect_pollen(
             let pollen_pos = pollen_transform.translation.truncate();
             let distance = bee_pos.distance(pollen_pos);
 
-            if distance <= COLLECTION_RADIUS {
+            if distance <= config.pollen.collection_radius {
                 collected.add(pollen.value);
                 commands.entity(pollen_entity).despawn();
 
@@ -35,12 +34,13 @@ pub fn collect_pollen(
 
 /// Collect from stem caches (larger radius, triggers respawn timer and tickle)
 pub fn collect_caches(
-    mut bees: Query<(&Transform, &mut CollectedPollen), With<Bee>>,
+    mut bees: Query<(Entity, &Transform, &mut CollectedPollen), With<Bee>>,
     mut caches: Query<(&GlobalTransform, &mut CacheSpawnPoint, &mut Visibility)>,
     mut collection_events: EventWriter<CollectionEvent>,
     mut tickle_events: EventWriter<TickleEvent>,
+    config: Res<GameConfig>,
 ) {
-    for (bee_transform, mut collected) in &mut bees {
+    for (bee, bee_transform, mut collected) in &mut bees {
         let bee_pos = bee_transform.translation.truncate();
 
         for (cache_transform, mut cache, mut visibility) in &mut caches {
@@ -51,7 +51,7 @@ pub fn collect_caches(
             let cache_pos = cache_transform.translation().truncate();
             let distance = bee_pos.distance(cache_pos);
 
-            if distance <= CACHE_COLLECTION_RADIUS {
+            if distance <= config.pollen.cache_collection_radius {
                 collected.add(cache.value);
                 cache.is_active = false;
                 cache.respawn_timer.reset();
@@ -65,6 +65,7 @@ pub fn collect_caches(
                 // Send tickle event - alerts nearby flower heads!
                 tickle_events.send(TickleEvent {
                     cache_position: cache_pos,
+                    bee,
                 });
             }
         }
@@ -1,4 +1,7 @@
 use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::game::InterpolatedTransform;
 
 #[derive(Component, Debug, Clone, Reflect)]
 #[reflect(Component)]
@@ -14,7 +17,7 @@ impl Default for Bee {
     }
 }
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Reflect)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Reflect, Serialize, Deserialize)]
 pub enum Role {
     #[default]
     Gatherer,
@@ -22,6 +25,18 @@ pub enum Role {
     Healer,
 }
 
+impl Role {
+    pub const ALL: [Role; 3] = [Role::Gatherer, Role::Diva, Role::Healer];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            Role::Gatherer => "Gatherer",
+            Role::Diva => "Diva",
+            Role::Healer => "Healer",
+        }
+    }
+}
+
 #[derive(Component, Debug, Clone, Reflect)]
 #[reflect(Component)]
 pub struct AllergyMeter {
@@ -74,6 +89,7 @@ pub struct BeeBundle {
     pub collected_pollen: CollectedPollen,
     pub transform: Transform,
     pub global_transform: GlobalTransform,
+    pub interpolated: InterpolatedTransform,
 }
 
 #[cfg(test)]
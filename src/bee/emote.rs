@@ -0,0 +1,109 @@
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+
+/// Seconds an emote's bubble stays up
+pub const EMOTE_SECONDS: f32 = 2.5;
+
+/// Something a bee can say. There's no free text: players pick from these
+/// on the emote wheel, and AI companions use them to say what they're up to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize, Reflect)]
+pub enum Emote {
+    Wave,
+    Heart,
+    Help,
+    Sneezing,
+    // Quick-chat lines
+    ComingToHelp,
+    OverHere,
+    NiceWiggle,
+    Thanks,
+}
+
+impl Emote {
+    /// Around the wheel clockwise from the top
+    pub const WHEEL: [Emote; 8] = [
+        Emote::Wave,
+        Emote::ComingToHelp,
+        Emote::Heart,
+        Emote::OverHere,
+        Emote::Help,
+        Emote::NiceWiggle,
+        Emote::Sneezing,
+        Emote::Thanks,
+    ];
+
+    /// What the bubble over the bee says
+    pub fn text(self) -> &'static str {
+        match self {
+            Emote::Wave => "*waves*",
+            Emote::Heart => "<3",
+            Emote::Help => "Help!",
+            Emote::Sneezing => "Sneezing!",
+            Emote::ComingToHelp => "Coming to help!",
+            Emote::OverHere => "Over here!",
+            Emote::NiceWiggle => "Nice wiggle!",
+            Emote::Thanks => "Thanks!",
+        }
+    }
+
+    /// The wheel entry pointed at by `direction`, if it's pushed far enough
+    pub fn on_wheel(direction: Vec2) -> Option<Emote> {
+        if direction.length() < 0.5 {
+            return None;
+        }
+        // Clockwise from straight up, in eighths of a turn
+        let turn = direction
+            .x
+            .atan2(direction.y)
+            .rem_euclid(std::f32::consts::TAU);
+        let slice = std::f32::consts::TAU / Self::WHEEL.len() as f32;
+        let index = ((turn / slice).round() as usize) % Self::WHEEL.len();
+        Some(Self::WHEEL[index])
+    }
+}
+
+/// A bee (or companion) showing an emote bubble
+#[derive(Component, Debug, Clone)]
+pub struct Emoting {
+    pub emote: Emote,
+    pub timer: Timer,
+}
+
+impl Emoting {
+    pub fn new(emote: Emote) -> Self {
+        Self {
+            emote,
+            timer: Timer::from_seconds(EMOTE_SECONDS, TimerMode::Once),
+        }
+    }
+}
+
+/// Take bubbles down once they've been up long enough
+pub fn expire_emotes(
+    mut commands: Commands,
+    mut emoting: Query<(Entity, &mut Emoting)>,
+    time: Res<Time>,
+) {
+    for (entity, mut emoting) in &mut emoting {
+        // Only a new emote counts as a change, for whatever draws bubbles
+        emoting.bypass_change_detection().timer.tick(time.delta());
+        if emoting.timer.finished() {
+            commands.entity(entity).remove::<Emoting>();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn the_wheel_is_picked_by_direction() {
+        assert_eq!(Emote::on_wheel(Vec2::Y), Some(Emote::Wave));
+        assert_eq!(Emote::on_wheel(Vec2::X), Some(Emote::Heart));
+        assert_eq!(Emote::on_wheel(Vec2::NEG_Y), Some(Emote::Help));
+        assert_eq!(Emote::on_wheel(Vec2::NEG_X), Some(Emote::Sneezing));
+        assert_eq!(Emote::on_wheel(Vec2::new(-0.7, 0.7)), Some(Emote::Thanks));
+        assert_eq!(Emote::on_wheel(Vec2::new(0.1, 0.2)), None);
+    }
+}
@@ -0,0 +1,106 @@
+use bevy::prelude::*;
+
+use super::{
+    starts_wiggle, Bee, Emote, Emoting, MoveTarget, PlayerSlot, Steering, WiggleCooldown, Wiggling,
+};
+use crate::game::GameConfig;
+
+/// One player's commands gathered from input devices during the frame and
+/// consumed by the next fixed simulation tick
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct PlayerInput {
+    pub move_target: Option<Vec2>,
+    pub wiggle: bool,
+    /// Held keyboard or stick direction, length at most 1. Unlike the
+    /// one-shot commands it applies to every tick until input changes it.
+    pub direction: Vec2,
+    /// Picked from the emote wheel
+    pub emote: Option<Emote>,
+}
+
+impl PlayerInput {
+    pub fn is_empty(&self) -> bool {
+        self.move_target.is_none()
+            && !self.wiggle
+            && self.direction == Vec2::ZERO
+            && self.emote.is_none()
+    }
+}
+
+/// Buffered input of every local player, indexed by `PlayerSlot`
+#[derive(Resource, Debug, Clone, Default, PartialEq)]
+pub struct PlayerInputs {
+    pub players: Vec<PlayerInput>,
+}
+
+impl PlayerInputs {
+    pub fn player(&self, slot: usize) -> Option<&PlayerInput> {
+        self.players.get(slot)
+    }
+
+    pub fn player_mut(&mut self, slot: usize) -> &mut PlayerInput {
+        if self.players.len() <= slot {
+            self.players.resize_with(slot + 1, PlayerInput::default);
+        }
+        &mut self.players[slot]
+    }
+}
+
+/// Turn buffered input into movement targets and wiggles at the start of a tick
+#[allow(clippy::type_complexity)]
+pub fn apply_player_input(
+    mut commands: Commands,
+    mut inputs: ResMut<PlayerInputs>,
+    mut bees: Query<
+        (
+            Entity,
+            Option<&PlayerSlot>,
+            &Transform,
+            &mut MoveTarget,
+            Option<&mut Steering>,
+            Option<&WiggleCooldown>,
+            Has<Wiggling>,
+        ),
+        With<Bee>,
+    >,
+    config: Res<GameConfig>,
+) {
+    // Clicks and wiggles happen once; the held direction carries over
+    let inputs: Vec<PlayerInput> = inputs
+        .players
+        .iter_mut()
+        .map(|input| {
+            let held = PlayerInput {
+                direction: input.direction,
+                ..default()
+            };
+            std::mem::replace(input, held)
+        })
+        .collect();
+
+    for (entity, slot, transform, mut target, steering, cooldown, wiggling) in &mut bees {
+        let Some(input) = inputs.get(slot.map_or(0, |slot| slot.0)) else {
+            continue;
+        };
+
+        if let Some(mut steering) = steering {
+            steering.direction = input.direction;
+        }
+
+        if let Some(destination) = input.move_target {
+            target.set(destination);
+        }
+
+        if let Some(emote) = input.emote {
+            commands.entity(entity).insert(Emoting::new(emote));
+        }
+
+        let ready = cooldown.is_none_or(WiggleCooldown::is_ready);
+        if starts_wiggle(input, wiggling, ready) {
+            commands.entity(entity).insert(Wiggling::new(
+                transform.translation.x,
+                config.wiggle.duration,
+            ));
+        }
+    }
+}
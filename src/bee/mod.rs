@@ -2,19 +2,27 @@ mod actions;
 mod allergy;
 mod collection;
 mod components;
+mod emote;
+mod input;
 mod movement;
+mod player;
 mod sneeze;
 
 pub use actions::*;
 pub use allergy::*;
 pub use collection::*;
 pub use components::*;
+pub use emote::*;
+pub use input::*;
 pub use movement::*;
+pub use player::*;
 pub use sneeze::*;
 
 use bevy::prelude::*;
+use bevy::transform::systems::sync_simple_transforms;
 
-use crate::game::GameState;
+use crate::effects::CollectionEvent;
+use crate::game::{AppState, GameState, PauseState};
 
 pub struct BeePlugin;
 
@@ -22,25 +30,48 @@ impl Plugin for BeePlugin {
     fn build(&self, app: &mut App) {
         app.register_type::<Bee>()
             .register_type::<Role>()
+            .register_type::<Emote>()
             .register_type::<AllergyMeter>()
             .register_type::<CollectedPollen>()
             .register_type::<MoveTarget>()
+            .register_type::<Steering>()
+            .register_type::<PlayerSlot>()
+            .init_resource::<PlayerInputs>()
+            .add_event::<CollectionEvent>()
             .add_systems(
                 Update,
                 (
                     handle_click_input,
+                    handle_direction_input,
                     handle_wiggle_input,
+                )
+                    // Online, the input goes to the server instead
+                    .run_if(in_state(PauseState::Running).or(in_state(AppState::Online))),
+            )
+            .add_systems(
+                FixedUpdate,
+                (
+                    apply_player_input,
                     update_wiggle_cooldown,
                     update_wiggling,
                     move_toward_target,
+                    apply_steering,
                     collect_pollen,
                     collect_caches,
                     update_allergy_from_proximity,
                     trigger_sneeze,
                     update_sneezing,
+                    expire_emotes,
                 )
                     .chain()
                     .run_if(in_state(GameState::Playing)),
+            )
+            // After every tick's movement, companions' included
+            .add_systems(
+                FixedPostUpdate,
+                keep_bees_in_play_area
+                    .before(sync_simple_transforms)
+                    .run_if(in_state(GameState::Playing)),
             );
     }
 }
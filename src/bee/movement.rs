@@ -1,8 +1,8 @@
 use bevy::prelude::*;
-use bevy::window::PrimaryWindow;
 
-use super::Bee;
-use crate::game::GameConfig;
+use super::{Bee, PlayerInputs, Sneezing};
+use crate::controls::{Action, PlayerActions};
+use crate::game::{GameConfig, MovementConfig, PlayArea};
 
 #[derive(Component, Debug, Clone, Default, Reflect)]
 #[reflect(Component)]
@@ -20,49 +20,137 @@ impl MoveTarget {
     }
 }
 
+/// Direct keyboard/stick control of a bee, alongside click-to-move
+#[derive(Component, Debug, Clone, Default, Reflect)]
+#[reflect(Component)]
+pub struct Steering {
+    /// Requested direction, length at most 1
+    pub direction: Vec2,
+    pub velocity: Vec2,
+}
+
+impl Steering {
+    /// Accelerate toward `direction` at full speed, or coast to a stop when
+    /// there is no direction, and return the resulting velocity
+    pub fn update(&mut self, max_speed: f32, acceleration: f32, friction: f32, dt: f32) -> Vec2 {
+        let desired = self.direction.clamp_length_max(1.0) * max_speed;
+        let rate = if self.direction == Vec2::ZERO {
+            friction
+        } else {
+            acceleration
+        };
+
+        let change = desired - self.velocity;
+        let step = rate * dt;
+        self.velocity = if change.length() <= step {
+            desired
+        } else {
+            self.velocity + change.normalize() * step
+        };
+        self.velocity
+    }
+}
+
+/// The move actions and the left stick of any gamepad steer each player's bee
+pub fn handle_direction_input(actions: Res<PlayerActions>, mut inputs: ResMut<PlayerInputs>) {
+    for (player, actions) in actions.players.iter().enumerate() {
+        let direction = actions.move_axis();
+        if inputs.player(player).map(|input| input.direction) != Some(direction) {
+            inputs.player_mut(player).direction = direction;
+        }
+    }
+}
+
 pub fn handle_click_input(
-    mouse_button: Res<ButtonInput<MouseButton>>,
-    touches: Res<Touches>,
-    windows: Query<&Window, With<PrimaryWindow>>,
+    actions: Res<PlayerActions>,
     camera_query: Query<(&Camera, &GlobalTransform)>,
-    mut bees: Query<&mut MoveTarget, With<Bee>>,
+    buttons: Query<&Interaction, With<Button>>,
+    mut inputs: ResMut<PlayerInputs>,
 ) {
-    let Some(world_pos) =
-        get_click_world_position(&mouse_button, &touches, &windows, &camera_query)
-    else {
+    // Presses on HUD buttons (e.g. pause) aren't movement orders
+    if buttons
+        .iter()
+        .any(|interaction| *interaction != Interaction::None)
+    {
+        return;
+    }
+    let Ok((camera, camera_transform)) = camera_query.get_single() else {
         return;
     };
 
-    for mut target in &mut bees {
-        target.set(world_pos);
+    for (player, actions) in actions.players.iter().enumerate() {
+        if !actions.just_pressed(Action::MoveToPointer) {
+            continue;
+        }
+        let Some(screen_pos) = actions.pointer else {
+            continue;
+        };
+
+        if let Ok(world_pos) = camera.viewport_to_world_2d(camera_transform, screen_pos) {
+            inputs.player_mut(player).move_target = Some(world_pos);
+        }
     }
 }
 
-fn get_click_world_position(
-    mouse_button: &ButtonInput<MouseButton>,
-    touches: &Touches,
-    windows: &Query<&Window, With<PrimaryWindow>>,
-    camera_query: &Query<(&Camera, &GlobalTransform)>,
-) -> Option<Vec2> {
-    let Ok(window) = windows.get_single() else {
-        return None;
-    };
+/// Move steered bees
+pub fn apply_steering(
+    mut bees: Query<
+        (
+            &mut Transform,
+            &mut Steering,
+            &mut MoveTarget,
+            Has<Sneezing>,
+        ),
+        With<Bee>,
+    >,
+    config: Res<GameConfig>,
+    time: Res<Time>,
+) {
+    for (mut transform, mut steering, mut target, sneezing) in &mut bees {
+        let movement = steer(
+            &mut steering,
+            &mut target.destination,
+            sneezing,
+            &config.movement,
+            time.delta_secs(),
+        );
+        transform.translation += movement.extend(0.0);
+    }
+}
 
-    let Ok((camera, camera_transform)) = camera_query.get_single() else {
-        return None;
-    };
+/// How far `steering` moves a bee in `dt` seconds. Steering takes over from
+/// a click `destination`, and a sneezing bee can't steer.
+pub fn steer(
+    steering: &mut Steering,
+    destination: &mut Option<Vec2>,
+    sneezing: bool,
+    movement: &MovementConfig,
+    dt: f32,
+) -> Vec2 {
+    if sneezing {
+        steering.direction = Vec2::ZERO;
+    } else if steering.direction != Vec2::ZERO {
+        *destination = None;
+    }
 
-    // Check for mouse click or touch
-    let cursor_pos = if mouse_button.just_pressed(MouseButton::Left) {
-        window.cursor_position()
-    } else {
-        touches
-            .iter_just_pressed()
-            .next()
-            .map(|touch| touch.position())
-    };
+    let velocity = steering.update(
+        movement.bee_speed,
+        movement.bee_acceleration,
+        movement.bee_friction,
+        dt,
+    );
+    velocity * dt
+}
 
-    cursor_pos.and_then(|pos| camera.viewport_to_world_2d(camera_transform, pos).ok())
+/// Where a bee at `position` ends up after moving at most `distance` toward
+/// `destination`, and whether it got there
+pub fn step_toward(position: Vec2, destination: Vec2, distance: f32) -> (Vec2, bool) {
+    let offset = destination - position;
+    if offset.length() <= distance {
+        (destination, true)
+    } else {
+        (position + offset.normalize() * distance, false)
+    }
 }
 
 pub fn move_toward_target(
@@ -71,27 +159,51 @@ pub fn move_toward_target(
     time: Res<Time>,
 ) {
     for (mut transform, mut target) in &mut bees {
-        let Some(destination) = target.destination else {
+        if target.destination.is_none() {
             continue;
-        };
+        }
 
-        let current_pos = transform.translation.truncate();
-        let direction = destination - current_pos;
-        let distance = direction.length();
+        let position = follow_destination(
+            transform.translation.truncate(),
+            &mut target.destination,
+            &config.movement,
+            time.delta_secs(),
+        );
+        transform.translation.x = position.x;
+        transform.translation.y = position.y;
+    }
+}
 
-        let move_distance = config.movement.bee_speed * time.delta_secs();
+/// Where a bee at `position` is after flying toward `destination` for `dt`
+/// seconds. The destination is cleared once the bee gets there.
+pub fn follow_destination(
+    position: Vec2,
+    destination: &mut Option<Vec2>,
+    movement: &MovementConfig,
+    dt: f32,
+) -> Vec2 {
+    let Some(target) = *destination else {
+        return position;
+    };
+    let (position, arrived) = step_toward(position, target, movement.bee_speed * dt);
+    if arrived {
+        *destination = None;
+    }
+    position
+}
 
-        if distance <= move_distance {
-            // Arrived at destination
-            transform.translation.x = destination.x;
-            transform.translation.y = destination.y;
-            target.clear();
-        } else {
-            // Move toward destination
-            let normalized = direction.normalize();
-            transform.translation.x += normalized.x * move_distance;
-            transform.translation.y += normalized.y * move_distance;
-        }
+/// Stop bees, companions included, at the edge of the garden
+pub fn keep_bees_in_play_area(
+    mut bees: Query<&mut Transform, With<Bee>>,
+    area: Option<Res<PlayArea>>,
+) {
+    let Some(area) = area else {
+        return;
+    };
+    for mut transform in &mut bees {
+        let position = area.clamp(transform.translation.truncate());
+        transform.translation.x = position.x;
+        transform.translation.y = position.y;
     }
 }
 
@@ -110,4 +222,27 @@ mod tests {
         target.clear();
         assert!(target.destination.is_none());
     }
+
+    #[test]
+    fn steering_accelerates_then_coasts_to_a_stop() {
+        let mut steering = Steering {
+            direction: Vec2::X,
+            ..default()
+        };
+        let speed = steering.update(150.0, 900.0, 600.0, 0.1).length();
+        assert!((speed - 90.0).abs() < 1e-3);
+
+        for _ in 0..10 {
+            steering.update(150.0, 900.0, 600.0, 0.1);
+        }
+        assert_eq!(steering.velocity, Vec2::new(150.0, 0.0));
+
+        steering.direction = Vec2::ZERO;
+        steering.update(150.0, 900.0, 600.0, 0.1);
+        assert!((steering.velocity.x - 90.0).abs() < 1e-3);
+        for _ in 0..10 {
+            steering.update(150.0, 900.0, 600.0, 0.1);
+        }
+        assert_eq!(steering.velocity, Vec2::ZERO);
+    }
 }
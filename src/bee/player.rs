@@ -0,0 +1,26 @@
+use bevy::prelude::*;
+
+use crate::controls::MAX_PLAYERS;
+
+/// Bee colour of each local player, also used for their HUD panel
+const PLAYER_COLORS: [Color; MAX_PLAYERS] = [
+    Color::srgb(1.0, 0.9, 0.2),
+    Color::srgb(0.4, 0.75, 1.0),
+    Color::srgb(1.0, 0.6, 0.2),
+    Color::srgb(0.95, 0.95, 0.95),
+];
+
+/// Which local player controls a bee, counting from 0
+#[derive(Component, Debug, Clone, Copy, PartialEq, Eq, Default, Reflect)]
+#[reflect(Component)]
+pub struct PlayerSlot(pub usize);
+
+impl PlayerSlot {
+    pub fn color(&self) -> Color {
+        PLAYER_COLORS[self.0 % MAX_PLAYERS]
+    }
+
+    pub fn label(&self) -> String {
+        format!("P{}", self.0 + 1)
+    }
+}
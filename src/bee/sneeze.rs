@@ -1,23 +1,30 @@
 use bevy::prelude::*;
+use rand::Rng;
 
 use super::{AllergyMeter, Bee, CollectedPollen, MoveTarget};
 use crate::effects::ScatteringPollen;
-use crate::flower::PollenBundle;
-use crate::game::GameConfig;
+use crate::flower::{Pollen, PollenBundle};
+use crate::game::{GameConfig, GameRng, InterpolatedTransform};
 
 #[derive(Component, Debug)]
 pub struct Sneezing {
     pub timer: Timer,
 }
 
-impl Default for Sneezing {
-    fn default() -> Self {
+impl Sneezing {
+    pub fn new(duration: f32) -> Self {
         Self {
-            timer: Timer::from_seconds(0.5, TimerMode::Once),
+            timer: Timer::from_seconds(duration, TimerMode::Once),
         }
     }
 }
 
+impl Default for Sneezing {
+    fn default() -> Self {
+        Self::new(0.5)
+    }
+}
+
 #[derive(Component, Debug, Default)]
 pub struct SneezeCount {
     pub count: u32,
@@ -37,6 +44,7 @@ pub fn trigger_sneeze(
         (With<Bee>, Without<Sneezing>),
     >,
     config: Res<GameConfig>,
+    mut rng: ResMut<GameRng>,
 ) {
     for (entity, transform, mut meter, mut collected, sneeze_count) in &mut bees {
         if meter.should_sneeze(config.sneeze.threshold) {
@@ -46,16 +54,20 @@ pub fn trigger_sneeze(
             // Spawn dropped pollen around the bee with scatter velocity
             let bee_pos = transform.translation.truncate();
             for i in 0..dropped_count {
-                let angle = (i as f32 / dropped_count as f32) * std::f32::consts::TAU;
+                let spread = rng.gameplay().gen_range(-0.3..0.3);
+                let angle = (i as f32 / dropped_count as f32) * std::f32::consts::TAU + spread;
                 let direction = Vec2::new(angle.cos(), angle.sin());
                 let offset = direction * 30.0;
                 let pos = bee_pos + offset;
 
                 // Scatter outward with some speed variation
-                let scatter_speed = 100.0 + (i as f32 * 15.0);
+                let scatter_speed = rng.gameplay().gen_range(100.0..160.0);
 
                 commands.spawn((
                     PollenBundle {
+                        pollen: Pollen {
+                            value: config.pollen.base_value,
+                        },
                         transform: Transform::from_xyz(pos.x, pos.y, 0.5),
                         ..default()
                     },
@@ -68,6 +80,7 @@ pub fn trigger_sneeze(
                         velocity: direction * scatter_speed,
                         friction: 0.85,
                     },
+                    InterpolatedTransform::default(),
                 ));
             }
 
@@ -75,7 +88,9 @@ pub fn trigger_sneeze(
             meter.value = config.sneeze.post_sneeze_value;
 
             // Add sneezing state (stagger)
-            commands.entity(entity).insert(Sneezing::default());
+            commands
+                .entity(entity)
+                .insert(Sneezing::new(config.sneeze.stagger_duration));
 
             // Increment sneeze count
             if let Some(mut count) = sneeze_count {
@@ -0,0 +1,561 @@
+//! Balance simulator: plays many seeded rounds headlessly with a bot and
+//! reports aggregate statistics, so config changes can be compared with
+//! numbers instead of feel.
+//!
+//! ```text
+//! cargo run --release --bin balance -- --games 2000 --difficulty hay-fever --format json
+//! ```
+
+use std::fmt::Write as _;
+use std::process::ExitCode;
+
+use allerbees::headless::HeadlessGame;
+use allerbees::prelude::*;
+use bevy::prelude::*;
+use rand::Rng;
+use rand_chacha::rand_core::SeedableRng;
+use rand_chacha::ChaCha8Rng;
+use serde::Serialize;
+
+const USAGE: &str = "\
+Usage: balance [options]
+
+Options:
+  --games <n>          Rounds to simulate (default 1000)
+  --seed <n>           Seed of the first round; round i uses seed + i (default 1)
+  --level <path>       Level file (default assets/levels/meadow.level.ron)
+  --config <path>      Game config file (default assets/config/game.ron)
+  --difficulty <name>  cozy, normal or hay-fever (default normal)
+  --bot <name>         greedy or wander (default greedy)
+  --max-time <secs>    Rounds still running after this count as timeouts (default 300)
+  --format <name>      csv (one row per round) or json (summary and rounds) (default csv)
+  --out <path>         Write results here instead of stdout
+";
+
+/// Ticks between bot decisions, roughly a human's reaction time
+const DECISION_INTERVAL: u32 = 10;
+/// Ticks between rizz samples
+const RIZZ_SAMPLE_INTERVAL: u32 = 60;
+const RIZZ_BUCKETS: usize = 10;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Bot {
+    /// Chases the nearest pollen, wiggles at nearby heads and backs off
+    /// when the allergy meter gets high
+    Greedy,
+    /// Wanders to random points and wiggles now and then
+    Wander,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Format {
+    Csv,
+    Json,
+}
+
+struct Options {
+    games: u32,
+    seed: u64,
+    level: String,
+    config: String,
+    difficulty: Difficulty,
+    bot: Bot,
+    max_time: f32,
+    format: Format,
+    out: Option<String>,
+}
+
+impl Default for Options {
+    fn default() -> Self {
+        Self {
+            games: 1000,
+            seed: 1,
+            level: "assets/levels/meadow.level.ron".to_string(),
+            config: "assets/config/game.ron".to_string(),
+            difficulty: Difficulty::Normal,
+            bot: Bot::Greedy,
+            max_time: 300.0,
+            format: Format::Csv,
+            out: None,
+        }
+    }
+}
+
+fn parse_options(mut args: impl Iterator<Item = String>) -> Result<Options, String> {
+    let mut options = Options::default();
+    while let Some(flag) = args.next() {
+        if flag == "--help" || flag == "-h" {
+            return Err(String::new());
+        }
+        let value = args.next().ok_or_else(|| format!("{flag} needs a value"))?;
+        let invalid = || format!("invalid value for {flag}: {value}");
+
+        match flag.as_str() {
+            "--games" => options.games = value.parse().map_err(|_| invalid())?,
+            "--seed" => options.seed = value.parse().map_err(|_| invalid())?,
+            "--level" => options.level = value,
+            "--config" => options.config = value,
+            "--difficulty" => {
+                options.difficulty = match value.as_str() {
+                    "cozy" => Difficulty::Cozy,
+                    "normal" => Difficulty::Normal,
+                    "hay-fever" | "hay_fever" => Difficulty::HayFever,
+                    _ => return Err(invalid()),
+                }
+            }
+            "--bot" => {
+                options.bot = match value.as_str() {
+                    "greedy" => Bot::Greedy,
+                    "wander" => Bot::Wander,
+                    _ => return Err(invalid()),
+                }
+            }
+            "--max-time" => options.max_time = value.parse().map_err(|_| invalid())?,
+            "--format" => {
+                options.format = match value.as_str() {
+                    "csv" => Format::Csv,
+                    "json" => Format::Json,
+                    _ => return Err(invalid()),
+                }
+            }
+            "--out" => options.out = Some(value),
+            _ => return Err(format!("unknown option {flag}")),
+        }
+    }
+
+    Ok(options)
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+enum Outcome {
+    Won,
+    Lost,
+    Timeout,
+}
+
+#[derive(Debug, Clone, Serialize)]
+struct RoundStats {
+    seed: u64,
+    outcome: Outcome,
+    seconds: f32,
+    sneezes: u32,
+    pollen: u32,
+    pollen_per_minute: f32,
+    mean_rizz: f32,
+    #[serde(skip)]
+    rizz_samples: Vec<f32>,
+}
+
+#[derive(Debug, Serialize)]
+struct Summary {
+    games: usize,
+    wins: usize,
+    losses: usize,
+    timeouts: usize,
+    win_rate: f32,
+    mean_time_to_win: Option<f32>,
+    median_time_to_win: Option<f32>,
+    mean_sneezes: f32,
+    mean_pollen_per_minute: f32,
+    mean_rizz: f32,
+    /// Share of sampled head rizz in each tenth of the rizz range
+    rizz_histogram: [f32; RIZZ_BUCKETS],
+}
+
+#[derive(Serialize)]
+struct Report<'a> {
+    summary: &'a Summary,
+    rounds: &'a [RoundStats],
+}
+
+/// Snapshot of what the bot can see
+struct View {
+    player: Vec2,
+    allergy: f32,
+    wiggle_ready: bool,
+    heads: Vec<Vec2>,
+    pollen: Vec<Vec2>,
+}
+
+fn look(game: &mut HeadlessGame) -> View {
+    let player = game.player();
+    let world = game.world_mut();
+
+    let position = world.get::<GlobalTransform>(player).unwrap().translation();
+    let allergy = world.get::<AllergyMeter>(player).unwrap().value;
+    let wiggle_ready = world.get::<Wiggling>(player).is_none()
+        && world
+            .get::<WiggleCooldown>(player)
+            .is_none_or(|cooldown| cooldown.is_ready());
+
+    let heads = world
+        .query_filtered::<&GlobalTransform, With<FlowerHead>>()
+        .iter(world)
+        .map(|transform| transform.translation().truncate())
+        .collect();
+    let mut pollen: Vec<Vec2> = world
+        .query_filtered::<&Transform, With<Pollen>>()
+        .iter(world)
+        .map(|transform| transform.translation.truncate())
+        .collect();
+    pollen.extend(
+        world
+            .query::<(&GlobalTransform, &CacheSpawnPoint)>()
+            .iter(world)
+            .filter(|(_, cache)| cache.is_active)
+            .map(|(transform, _)| transform.translation().truncate()),
+    );
+
+    View {
+        player: position.truncate(),
+        allergy,
+        wiggle_ready,
+        heads,
+        pollen,
+    }
+}
+
+fn nearest(from: Vec2, points: &[Vec2]) -> Option<Vec2> {
+    points.iter().copied().min_by(|a, b| {
+        from.distance_squared(*a)
+            .total_cmp(&from.distance_squared(*b))
+    })
+}
+
+fn decide(
+    bot: Bot,
+    view: &View,
+    config: &GameConfig,
+    play_area: Vec2,
+    rng: &mut ChaCha8Rng,
+) -> PlayerInput {
+    let half = play_area / 2.0;
+    match bot {
+        Bot::Greedy => {
+            let nearest_head = nearest(view.player, &view.heads);
+            let head_distance = nearest_head.map_or(f32::MAX, |head| view.player.distance(head));
+
+            let move_target = if view.allergy > config.sneeze.threshold * 0.6 {
+                // Back away from the closest head to let the meter decay
+                nearest_head.map(|head| {
+                    let away = (view.player - head).normalize_or(Vec2::X);
+                    (view.player + away * 120.0).clamp(-half, half)
+                })
+            } else {
+                nearest(view.player, &view.pollen)
+            };
+
+            PlayerInput {
+                move_target,
+                wiggle: view.wiggle_ready && head_distance < config.wiggle.range * 0.7,
+                ..default()
+            }
+        }
+        Bot::Wander => PlayerInput {
+            move_target: rng.gen_bool(0.15).then(|| {
+                Vec2::new(
+                    rng.gen_range(-half.x..half.x),
+                    rng.gen_range(-half.y..half.y),
+                )
+            }),
+            wiggle: view.wiggle_ready && rng.gen_bool(0.1),
+            ..default()
+        },
+    }
+}
+
+fn play_round(
+    options: &Options,
+    config: &GameConfig,
+    level: &LevelDefinition,
+    seed: u64,
+) -> RoundStats {
+    let mut game = HeadlessGame::new(config.clone());
+    game.set_seed(seed);
+    game.set_difficulty(options.difficulty);
+    game.load_level(level);
+
+    let config = game.world().resource::<GameConfig>().clone();
+    let max_ticks = (options.max_time / game.timestep().as_secs_f32()).ceil() as u32;
+    let mut bot_rng = ChaCha8Rng::seed_from_u64(seed);
+    let mut rizz_samples = Vec::new();
+
+    let outcome = loop {
+        match game.state() {
+            GameState::Won => break Outcome::Won,
+            GameState::Lost => break Outcome::Lost,
+            _ if game.tick() >= max_ticks => break Outcome::Timeout,
+            _ => {}
+        }
+
+        let tick = game.tick();
+        if tick.is_multiple_of(RIZZ_SAMPLE_INTERVAL) {
+            let world = game.world_mut();
+            rizz_samples.extend(world.query::<&FlowerHead>().iter(world).map(|h| h.rizz));
+        }
+
+        if tick.is_multiple_of(DECISION_INTERVAL) {
+            let view = look(&mut game);
+            let input = decide(options.bot, &view, &config, level.play_area, &mut bot_rng);
+            game.step_with_input(input);
+        } else {
+            game.step(1);
+        }
+    };
+
+    let player = game.player();
+    let world = game.world();
+    let seconds = world.resource::<SessionTimer>().elapsed;
+    let pollen = world.get::<CollectedPollen>(player).unwrap().count;
+    let sneezes = world
+        .get::<SneezeCount>(player)
+        .map_or(0, |count| count.count);
+    let mean_rizz = mean(&rizz_samples);
+
+    RoundStats {
+        seed,
+        outcome,
+        seconds,
+        sneezes,
+        pollen,
+        pollen_per_minute: if seconds > 0.0 {
+            pollen as f32 * 60.0 / seconds
+        } else {
+            0.0
+        },
+        mean_rizz,
+        rizz_samples,
+    }
+}
+
+fn mean(values: &[f32]) -> f32 {
+    if values.is_empty() {
+        0.0
+    } else {
+        values.iter().sum::<f32>() / values.len() as f32
+    }
+}
+
+fn summarize(rounds: &[RoundStats], rizz_max: f32) -> Summary {
+    let count = |outcome| rounds.iter().filter(|r| r.outcome == outcome).count();
+    let mut win_times: Vec<f32> = rounds
+        .iter()
+        .filter(|r| r.outcome == Outcome::Won)
+        .map(|r| r.seconds)
+        .collect();
+    win_times.sort_by(f32::total_cmp);
+
+    let samples: Vec<f32> = rounds
+        .iter()
+        .flat_map(|r| r.rizz_samples.iter().copied())
+        .collect();
+    let mut rizz_histogram = [0.0; RIZZ_BUCKETS];
+    for rizz in &samples {
+        let bucket = ((rizz / rizz_max) * RIZZ_BUCKETS as f32) as usize;
+        rizz_histogram[bucket.min(RIZZ_BUCKETS - 1)] += 1.0;
+    }
+    if !samples.is_empty() {
+        for share in &mut rizz_histogram {
+            *share /= samples.len() as f32;
+        }
+    }
+
+    let sneezes: Vec<f32> = rounds.iter().map(|r| r.sneezes as f32).collect();
+    let pollen_rates: Vec<f32> = rounds.iter().map(|r| r.pollen_per_minute).collect();
+    let wins = count(Outcome::Won);
+
+    Summary {
+        games: rounds.len(),
+        wins,
+        losses: count(Outcome::Lost),
+        timeouts: count(Outcome::Timeout),
+        win_rate: if rounds.is_empty() {
+            0.0
+        } else {
+            wins as f32 / rounds.len() as f32
+        },
+        mean_time_to_win: (!win_times.is_empty()).then(|| mean(&win_times)),
+        median_time_to_win: win_times.get(win_times.len() / 2).copied(),
+        mean_sneezes: mean(&sneezes),
+        mean_pollen_per_minute: mean(&pollen_rates),
+        mean_rizz: mean(&samples),
+        rizz_histogram,
+    }
+}
+
+fn to_csv(rounds: &[RoundStats]) -> String {
+    let mut csv = String::from("seed,outcome,seconds,sneezes,pollen,pollen_per_minute,mean_rizz\n");
+    for r in rounds {
+        let outcome = match r.outcome {
+            Outcome::Won => "won",
+            Outcome::Lost => "lost",
+            Outcome::Timeout => "timeout",
+        };
+        let _ = writeln!(
+            csv,
+            "{},{},{:.2},{},{},{:.2},{:.2}",
+            r.seed, outcome, r.seconds, r.sneezes, r.pollen, r.pollen_per_minute, r.mean_rizz
+        );
+    }
+    csv
+}
+
+fn print_summary(summary: &Summary) {
+    let time = |t: Option<f32>| t.map_or("-".to_string(), format_time);
+    eprintln!(
+        "{} games: {} won, {} lost, {} timed out ({:.1}% win rate)",
+        summary.games,
+        summary.wins,
+        summary.losses,
+        summary.timeouts,
+        summary.win_rate * 100.0
+    );
+    eprintln!(
+        "time to win: mean {}, median {}",
+        time(summary.mean_time_to_win),
+        time(summary.median_time_to_win)
+    );
+    eprintln!(
+        "sneezes/game {:.2}, pollen/minute {:.1}, mean rizz {:.1}",
+        summary.mean_sneezes, summary.mean_pollen_per_minute, summary.mean_rizz
+    );
+    let histogram: Vec<String> = summary
+        .rizz_histogram
+        .iter()
+        .map(|share| format!("{:.0}%", share * 100.0))
+        .collect();
+    eprintln!("rizz by tenth of range: {}", histogram.join(" "));
+}
+
+fn run(options: &Options) -> Result<(), String> {
+    let config = std::fs::read(&options.config)
+        .map_err(|err| err.to_string())
+        .and_then(|bytes| GameConfig::from_ron(&bytes).map_err(|err| err.to_string()))
+        .map_err(|err| format!("{}: {err}", options.config))?;
+    let level = std::fs::read(&options.level)
+        .map_err(|err| err.to_string())
+        .and_then(|bytes| LevelDefinition::from_ron(&bytes).map_err(|err| err.to_string()))
+        .map_err(|err| format!("{}: {err}", options.level))?;
+
+    eprintln!(
+        "Simulating {} rounds of '{}' on {} with the {:?} bot",
+        options.games,
+        level.name,
+        options.difficulty.label(),
+        options.bot
+    );
+    let rounds: Vec<RoundStats> = (0..options.games as u64)
+        .map(|i| play_round(options, &config, &level, options.seed.wrapping_add(i)))
+        .collect();
+    let summary = summarize(&rounds, config.rizz.max);
+    print_summary(&summary);
+
+    let output = match options.format {
+        Format::Csv => to_csv(&rounds),
+        Format::Json => serde_json::to_string_pretty(&Report {
+            summary: &summary,
+            rounds: &rounds,
+        })
+        .map_err(|err| err.to_string())?,
+    };
+    match &options.out {
+        Some(path) => std::fs::write(path, output).map_err(|err| format!("{path}: {err}")),
+        None => {
+            print!("{output}");
+            Ok(())
+        }
+    }
+}
+
+fn main() -> ExitCode {
+    let options = match parse_options(std::env::args().skip(1)) {
+        Ok(options) => options,
+        // An empty message means --help was asked for
+        Err(message) if message.is_empty() => {
+            eprint!("{USAGE}");
+            return ExitCode::SUCCESS;
+        }
+        Err(message) => {
+            eprintln!("{message}\n\n{USAGE}");
+            return ExitCode::FAILURE;
+        }
+    };
+
+    match run(&options) {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(message) => {
+            eprintln!("{message}");
+            ExitCode::FAILURE
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn args(list: &[&str]) -> impl Iterator<Item = String> {
+        list.iter()
+            .map(|arg| arg.to_string())
+            .collect::<Vec<_>>()
+            .into_iter()
+    }
+
+    fn round(outcome: Outcome, seconds: f32, rizz_samples: Vec<f32>) -> RoundStats {
+        RoundStats {
+            seed: 1,
+            outcome,
+            seconds,
+            sneezes: 0,
+            pollen: 0,
+            pollen_per_minute: 0.0,
+            mean_rizz: mean(&rizz_samples),
+            rizz_samples,
+        }
+    }
+
+    #[test]
+    fn reads_options() {
+        let options = parse_options(args(&["--games", "20", "--format", "json"])).unwrap();
+        assert_eq!(options.games, 20);
+        assert_eq!(options.format, Format::Json);
+    }
+
+    #[test]
+    fn bad_game_count_is_rejected() {
+        let err = parse_options(args(&["--games", "lots"])).err().unwrap();
+        assert_eq!(err, "invalid value for --games: lots");
+    }
+
+    #[test]
+    fn unknown_format_is_rejected() {
+        let err = parse_options(args(&["--format", "xml"])).err().unwrap();
+        assert_eq!(err, "invalid value for --format: xml");
+    }
+
+    #[test]
+    fn no_rounds_summarize_to_zero() {
+        let summary = summarize(&[], 100.0);
+        assert_eq!(summary.games, 0);
+        assert_eq!(summary.win_rate, 0.0);
+        assert_eq!(summary.mean_time_to_win, None);
+        assert_eq!(summary.median_time_to_win, None);
+        assert_eq!(summary.rizz_histogram, [0.0; RIZZ_BUCKETS]);
+    }
+
+    #[test]
+    fn full_rizz_lands_in_the_last_bucket() {
+        let rounds = [
+            round(Outcome::Won, 30.0, vec![0.0, 100.0]),
+            round(Outcome::Lost, 50.0, vec![100.0, 55.0]),
+        ];
+        let summary = summarize(&rounds, 100.0);
+        assert_eq!(summary.wins, 1);
+        assert_eq!(summary.win_rate, 0.5);
+        assert_eq!(summary.median_time_to_win, Some(30.0));
+        assert_eq!(summary.rizz_histogram[0], 0.25);
+        assert_eq!(summary.rizz_histogram[5], 0.25);
+        assert_eq!(summary.rizz_histogram[RIZZ_BUCKETS - 1], 0.5);
+    }
+}
@@ -0,0 +1,116 @@
+//! Lobby server: players open rooms or join them by code, pick roles and
+//! ready up, and each room gets its own garden once everyone is ready.
+//!
+//! ```text
+//! cargo run --bin lobby -- --addr 127.0.0.1:7878 --players 4
+//! cargo run -- --server ws://127.0.0.1:7878   # then pick "Online"
+//! cargo run -- --room ABCD   # fill in a friend's room code
+//! ```
+
+use std::process::ExitCode;
+use std::sync::atomic::AtomicBool;
+
+use allerbees::net::{LobbyServer, DEFAULT_PORT, MAX_ONLINE_PLAYERS};
+use allerbees::prelude::*;
+
+const USAGE: &str = "\
+Usage: lobby [options]
+
+Options:
+  --addr <host:port>  Address to listen on (default 0.0.0.0:7878)
+  --level <path>      Level file (default assets/levels/meadow.level.ron)
+  --config <path>     Game config file (default assets/config/game.ron)
+  --players <n>       Bees per room, AI companions filling empty slots
+                      (default 4, at most 8)
+";
+
+struct Options {
+    addr: String,
+    level: String,
+    config: String,
+    players: usize,
+}
+
+impl Default for Options {
+    fn default() -> Self {
+        Self {
+            addr: format!("0.0.0.0:{DEFAULT_PORT}"),
+            level: "assets/levels/meadow.level.ron".to_string(),
+            config: "assets/config/game.ron".to_string(),
+            players: 4,
+        }
+    }
+}
+
+fn parse_options(mut args: impl Iterator<Item = String>) -> Result<Options, String> {
+    let mut options = Options::default();
+    while let Some(flag) = args.next() {
+        if flag == "--help" || flag == "-h" {
+            return Err(String::new());
+        }
+        let value = args.next().ok_or_else(|| format!("{flag} needs a value"))?;
+
+        match flag.as_str() {
+            "--addr" => options.addr = value,
+            "--level" => options.level = value,
+            "--config" => options.config = value,
+            "--players" => {
+                options.players = value
+                    .parse()
+                    .ok()
+                    .filter(|players| (1..=MAX_ONLINE_PLAYERS).contains(players))
+                    .ok_or_else(|| {
+                        format!("--players must be from 1 to {MAX_ONLINE_PLAYERS}, not {value}")
+                    })?;
+            }
+            _ => return Err(format!("unknown option {flag}")),
+        }
+    }
+
+    Ok(options)
+}
+
+fn run(options: &Options) -> Result<(), String> {
+    let config = std::fs::read(&options.config)
+        .map_err(|err| err.to_string())
+        .and_then(|bytes| GameConfig::from_ron(&bytes).map_err(|err| err.to_string()))
+        .map_err(|err| format!("{}: {err}", options.config))?;
+    let level = std::fs::read(&options.level)
+        .map_err(|err| err.to_string())
+        .and_then(|bytes| LevelDefinition::from_ron(&bytes).map_err(|err| err.to_string()))
+        .map_err(|err| format!("{}: {err}", options.level))?;
+
+    let name = level.name.clone();
+    let mut lobby = LobbyServer::bind(&options.addr, config, level, options.players)
+        .map_err(|err| format!("{}: {err}", options.addr))?;
+    eprintln!(
+        "Lobby for '{name}' rooms of {} on ws://{}",
+        lobby.cap(),
+        lobby.local_addr()
+    );
+    lobby.run(&AtomicBool::new(false));
+    Ok(())
+}
+
+fn main() -> ExitCode {
+    let options = match parse_options(std::env::args().skip(1)) {
+        Ok(options) => options,
+        // An empty message means --help was asked for
+        Err(message) if message.is_empty() => {
+            eprint!("{USAGE}");
+            return ExitCode::SUCCESS;
+        }
+        Err(message) => {
+            eprintln!("{message}\n\n{USAGE}");
+            return ExitCode::FAILURE;
+        }
+    };
+
+    match run(&options) {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(message) => {
+            eprintln!("{message}");
+            ExitCode::FAILURE
+        }
+    }
+}
@@ -0,0 +1,119 @@
+//! Garden server: runs the simulation for everyone playing online.
+//!
+//! ```text
+//! cargo run --bin server -- --addr 127.0.0.1:7878
+//! cargo run --bin server -- --world meadow.world.ron   # keep the garden
+//! cargo run -- --server ws://127.0.0.1:7878   # then pick "Online"
+//! cargo run -- --latency 120 --jitter 30 --loss 5   # fake a bad link
+//! ```
+//!
+//! Ctrl-C or SIGTERM stops the server, saving a `--world` garden first.
+
+use std::process::ExitCode;
+use std::sync::atomic::{AtomicBool, Ordering};
+
+use allerbees::net::{GardenServer, DEFAULT_PORT};
+use allerbees::prelude::*;
+
+const USAGE: &str = "\
+Usage: server [options]
+
+Options:
+  --addr <host:port>  Address to listen on (default 0.0.0.0:7878)
+  --level <path>      Level file (default assets/levels/meadow.level.ron)
+  --config <path>     Game config file (default assets/config/game.ron)
+  --world <path>      Keep the garden in this file between sessions
+";
+
+/// Set on Ctrl-C or SIGTERM to stop serving
+static STOP: AtomicBool = AtomicBool::new(false);
+
+struct Options {
+    addr: String,
+    level: String,
+    config: String,
+    world: Option<String>,
+}
+
+impl Default for Options {
+    fn default() -> Self {
+        Self {
+            addr: format!("0.0.0.0:{DEFAULT_PORT}"),
+            level: "assets/levels/meadow.level.ron".to_string(),
+            config: "assets/config/game.ron".to_string(),
+            world: None,
+        }
+    }
+}
+
+fn parse_options(mut args: impl Iterator<Item = String>) -> Result<Options, String> {
+    let mut options = Options::default();
+    while let Some(flag) = args.next() {
+        if flag == "--help" || flag == "-h" {
+            return Err(String::new());
+        }
+        let value = args.next().ok_or_else(|| format!("{flag} needs a value"))?;
+
+        match flag.as_str() {
+            "--addr" => options.addr = value,
+            "--level" => options.level = value,
+            "--config" => options.config = value,
+            "--world" => options.world = Some(value),
+            _ => return Err(format!("unknown option {flag}")),
+        }
+    }
+
+    Ok(options)
+}
+
+fn run(options: &Options, stop: &AtomicBool) -> Result<(), String> {
+    let config = std::fs::read(&options.config)
+        .map_err(|err| err.to_string())
+        .and_then(|bytes| GameConfig::from_ron(&bytes).map_err(|err| err.to_string()))
+        .map_err(|err| format!("{}: {err}", options.config))?;
+    let level = std::fs::read(&options.level)
+        .map_err(|err| err.to_string())
+        .and_then(|bytes| LevelDefinition::from_ron(&bytes).map_err(|err| err.to_string()))
+        .map_err(|err| format!("{}: {err}", options.level))?;
+
+    let name = level.name.clone();
+    let mut server = GardenServer::bind(&options.addr, config, level)
+        .map_err(|err| format!("{}: {err}", options.addr))?;
+    if let Some(world) = &options.world {
+        server
+            .persist_to(world)
+            .map_err(|err| format!("{world}: {err}"))?;
+        eprintln!("Keeping the garden in {world}");
+    }
+    eprintln!("Serving '{name}' on ws://{}", server.local_addr());
+    server.run(stop);
+    eprintln!("Stopped");
+    Ok(())
+}
+
+fn main() -> ExitCode {
+    let options = match parse_options(std::env::args().skip(1)) {
+        Ok(options) => options,
+        // An empty message means --help was asked for
+        Err(message) if message.is_empty() => {
+            eprint!("{USAGE}");
+            return ExitCode::SUCCESS;
+        }
+        Err(message) => {
+            eprintln!("{message}\n\n{USAGE}");
+            return ExitCode::FAILURE;
+        }
+    };
+
+    if let Err(err) = ctrlc::set_handler(|| STOP.store(true, Ordering::Relaxed)) {
+        eprintln!("Can't stop cleanly on Ctrl-C: {err}");
+    }
+
+    match run(&options, &STOP) {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(message) => {
+            eprintln!("{message}");
+            ExitCode::FAILURE
+        }
+    }
+}
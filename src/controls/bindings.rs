@@ -0,0 +1,237 @@
+use std::collections::BTreeMap;
+use std::fmt;
+
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+
+/// Something the player can do, independent of the device used to do it
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+pub enum Action {
+    MoveUp,
+    MoveDown,
+    MoveLeft,
+    MoveRight,
+    /// Fly to the pointer (click or tap to move)
+    MoveToPointer,
+    Wiggle,
+    /// Open the emote wheel, and pick from it
+    Emote,
+    Pause,
+    /// Restart from the end screen
+    Confirm,
+    /// Pick a difficulty between rounds and in menus
+    DifficultyCozy,
+    DifficultyNormal,
+    DifficultyHayFever,
+}
+
+impl Action {
+    pub const ALL: [Action; 12] = [
+        Action::MoveUp,
+        Action::MoveDown,
+        Action::MoveLeft,
+        Action::MoveRight,
+        Action::MoveToPointer,
+        Action::Wiggle,
+        Action::Emote,
+        Action::Pause,
+        Action::Confirm,
+        Action::DifficultyCozy,
+        Action::DifficultyNormal,
+        Action::DifficultyHayFever,
+    ];
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            Action::MoveUp => "Move up",
+            Action::MoveDown => "Move down",
+            Action::MoveLeft => "Move left",
+            Action::MoveRight => "Move right",
+            Action::MoveToPointer => "Move to pointer",
+            Action::Wiggle => "Wiggle",
+            Action::Emote => "Emote",
+            Action::Pause => "Pause",
+            Action::Confirm => "Confirm",
+            Action::DifficultyCozy => "Cozy difficulty",
+            Action::DifficultyNormal => "Normal difficulty",
+            Action::DifficultyHayFever => "Hay Fever difficulty",
+        }
+    }
+
+    /// Used while a round is being played, rather than between rounds.
+    /// Actions from different halves can share an input.
+    pub fn in_round(&self) -> bool {
+        !matches!(
+            self,
+            Action::Confirm
+                | Action::DifficultyCozy
+                | Action::DifficultyNormal
+                | Action::DifficultyHayFever
+        )
+    }
+}
+
+/// One physical input an action can be bound to
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Binding {
+    Key(KeyCode),
+    Mouse(MouseButton),
+    Gamepad(GamepadButton),
+    /// Any touch on the screen
+    Touch,
+}
+
+impl Binding {
+    /// Bindings of the same kind replace each other when rebinding, so a
+    /// new key doesn't remove the gamepad button
+    pub fn same_device(&self, other: &Binding) -> bool {
+        std::mem::discriminant(self) == std::mem::discriminant(other)
+    }
+}
+
+impl fmt::Display for Binding {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Binding::Key(key) => write!(f, "{key:?}"),
+            Binding::Mouse(button) => write!(f, "Mouse {button:?}"),
+            Binding::Gamepad(button) => write!(f, "Pad {button:?}"),
+            Binding::Touch => write!(f, "Touch"),
+        }
+    }
+}
+
+/// Which inputs trigger each action; saved with the player's settings
+#[derive(Resource, Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Bindings {
+    pub actions: BTreeMap<Action, Vec<Binding>>,
+}
+
+impl Default for Bindings {
+    fn default() -> Self {
+        use Binding::*;
+
+        let actions = Action::ALL
+            .into_iter()
+            .map(|action| {
+                let bindings = match action {
+                    Action::MoveUp => vec![Key(KeyCode::KeyW), Key(KeyCode::ArrowUp)],
+                    Action::MoveDown => vec![Key(KeyCode::KeyS), Key(KeyCode::ArrowDown)],
+                    Action::MoveLeft => vec![Key(KeyCode::KeyA), Key(KeyCode::ArrowLeft)],
+                    Action::MoveRight => vec![Key(KeyCode::KeyD), Key(KeyCode::ArrowRight)],
+                    Action::MoveToPointer => vec![Mouse(MouseButton::Left), Touch],
+                    Action::Wiggle => vec![
+                        Key(KeyCode::Space),
+                        Mouse(MouseButton::Right),
+                        Gamepad(GamepadButton::South),
+                    ],
+                    Action::Emote => vec![Key(KeyCode::KeyE), Gamepad(GamepadButton::North)],
+                    Action::Pause => vec![Key(KeyCode::Escape), Gamepad(GamepadButton::Start)],
+                    Action::Confirm => vec![
+                        Key(KeyCode::Enter),
+                        Mouse(MouseButton::Left),
+                        Touch,
+                        Gamepad(GamepadButton::South),
+                    ],
+                    Action::DifficultyCozy => vec![Key(KeyCode::Digit1)],
+                    Action::DifficultyNormal => vec![Key(KeyCode::Digit2)],
+                    Action::DifficultyHayFever => vec![Key(KeyCode::Digit3)],
+                };
+                (action, bindings)
+            })
+            .collect();
+        Self { actions }
+    }
+}
+
+impl Bindings {
+    pub fn get(&self, action: Action) -> &[Binding] {
+        self.actions.get(&action).map_or(&[], Vec::as_slice)
+    }
+
+    /// Bind `action` to `binding`, replacing its other bindings on the same
+    /// kind of device. Other actions used at the same time lose `binding`, so
+    /// one press never does two things.
+    pub fn rebind(&mut self, action: Action, binding: Binding) {
+        for (other, bindings) in &mut self.actions {
+            if other.in_round() == action.in_round() {
+                bindings.retain(|existing| *existing != binding);
+            }
+        }
+        let bindings = self.actions.entry(action).or_default();
+        bindings.retain(|existing| !existing.same_device(&binding));
+        bindings.push(binding);
+    }
+
+    /// Give actions added since these bindings were saved their defaults
+    pub fn with_missing_defaults(mut self) -> Self {
+        for (action, defaults) in Self::default().actions {
+            self.actions.entry(action).or_insert(defaults);
+        }
+        self
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn every_action_has_a_default_binding() {
+        let bindings = Bindings::default();
+        for action in Action::ALL {
+            assert!(!bindings.get(action).is_empty(), "{action:?} is unbound");
+        }
+    }
+
+    #[test]
+    fn rebinding_replaces_only_the_same_device() {
+        let mut bindings = Bindings::default();
+        bindings.rebind(Action::Wiggle, Binding::Key(KeyCode::KeyQ));
+        assert_eq!(
+            bindings.get(Action::Wiggle),
+            [
+                Binding::Mouse(MouseButton::Right),
+                Binding::Gamepad(GamepadButton::South),
+                Binding::Key(KeyCode::KeyQ),
+            ]
+        );
+    }
+
+    #[test]
+    fn rebinding_takes_the_input_off_other_round_actions() {
+        let mut bindings = Bindings::default();
+        bindings.rebind(Action::Wiggle, Binding::Key(KeyCode::KeyE));
+        assert_eq!(
+            bindings.get(Action::Emote),
+            [Binding::Gamepad(GamepadButton::North)]
+        );
+
+        bindings.rebind(Action::Pause, Binding::Mouse(MouseButton::Left));
+        assert_eq!(bindings.get(Action::MoveToPointer), [Binding::Touch]);
+        // The end screen isn't up while playing, so Confirm keeps the click
+        assert!(bindings
+            .get(Action::Confirm)
+            .contains(&Binding::Mouse(MouseButton::Left)));
+    }
+
+    #[test]
+    fn saved_bindings_round_trip_and_gain_new_actions() {
+        let mut bindings = Bindings::default();
+        bindings.rebind(Action::Pause, Binding::Key(KeyCode::KeyP));
+        bindings.actions.remove(&Action::Confirm);
+
+        let source = ron::ser::to_string(&bindings).unwrap();
+        let loaded: Bindings = ron::de::from_str(&source).unwrap();
+        assert_eq!(loaded, bindings);
+
+        let filled = loaded.with_missing_defaults();
+        assert_eq!(
+            filled.get(Action::Confirm),
+            Bindings::default().get(Action::Confirm)
+        );
+        assert!(filled
+            .get(Action::Pause)
+            .contains(&Binding::Key(KeyCode::KeyP)));
+    }
+}
@@ -0,0 +1,41 @@
+//! Input actions and their rebindable bindings. Gameplay reads
+//! `PlayerActions` (menus read `ActionState`) rather than the keyboard,
+//! mouse, touch screen or gamepads directly, and local co-op players each
+//! own one device through the `Roster`.
+
+mod bindings;
+mod roster;
+mod state;
+mod touch;
+
+pub use bindings::*;
+pub use roster::*;
+pub use state::*;
+pub use touch::*;
+
+use bevy::input::InputSystem;
+use bevy::prelude::*;
+
+pub struct ControlsPlugin;
+
+impl Plugin for ControlsPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<Bindings>()
+            .init_resource::<ActionState>()
+            .init_resource::<DeviceActions>()
+            .init_resource::<PlayerActions>()
+            .init_resource::<Roster>()
+            .init_resource::<TouchGestures>()
+            .init_resource::<VirtualJoystick>()
+            .add_systems(
+                PreUpdate,
+                (
+                    update_virtual_joystick,
+                    reclaim_reconnected_gamepads,
+                    update_action_state,
+                )
+                    .chain()
+                    .after(InputSystem),
+            );
+    }
+}
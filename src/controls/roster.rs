@@ -0,0 +1,194 @@
+use std::fmt;
+
+use bevy::input::gamepad::{GamepadConnection, GamepadConnectionEvent};
+use bevy::prelude::*;
+
+/// Most bees that can share one screen
+pub const MAX_PLAYERS: usize = 4;
+
+/// One source of input a local player can own
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum InputDevice {
+    Keyboard,
+    /// Mouse and touch screen
+    Pointer,
+    /// A gamepad, with its model so the pad can be recognised if it comes
+    /// back as a new entity after reconnecting
+    Gamepad(Entity, GamepadModel),
+}
+
+/// USB vendor and product a gamepad reported, where the platform knows them
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub struct GamepadModel {
+    pub vendor_id: Option<u16>,
+    pub product_id: Option<u16>,
+}
+
+impl GamepadModel {
+    pub fn of(gamepad: &Gamepad) -> Self {
+        Self {
+            vendor_id: gamepad.vendor_id(),
+            product_id: gamepad.product_id(),
+        }
+    }
+}
+
+impl fmt::Display for InputDevice {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            InputDevice::Keyboard => write!(f, "Keyboard"),
+            InputDevice::Pointer => write!(f, "Mouse/touch"),
+            InputDevice::Gamepad(..) => write!(f, "Gamepad"),
+        }
+    }
+}
+
+/// Local players in joining order and the device each one owns. Until
+/// anyone joins, a single player uses every device.
+#[derive(Resource, Debug, Clone, Default, PartialEq)]
+pub struct Roster {
+    pub devices: Vec<InputDevice>,
+}
+
+impl Roster {
+    pub fn players(&self) -> usize {
+        self.devices.len().max(1)
+    }
+
+    /// Give `device` to a new player, returning their index, unless it
+    /// already has an owner or the screen is full
+    pub fn join(&mut self, device: InputDevice) -> Option<usize> {
+        if self.devices.contains(&device) || self.devices.len() >= MAX_PLAYERS {
+            return None;
+        }
+        self.devices.push(device);
+        Some(self.devices.len() - 1)
+    }
+
+    /// Index of the player who owns `device`, if anyone does
+    pub fn player_of(&self, device: InputDevice) -> Option<usize> {
+        if self.devices.is_empty() {
+            return Some(0);
+        }
+        self.devices.iter().position(|owned| *owned == device)
+    }
+
+    /// Hand a gamepad that just connected as `entity` to the player whose
+    /// pad of the same model is no longer `connected`, returning their index
+    pub fn reconnect(
+        &mut self,
+        entity: Entity,
+        model: GamepadModel,
+        connected: impl Fn(Entity) -> bool,
+    ) -> Option<usize> {
+        if self
+            .devices
+            .iter()
+            .any(|device| matches!(device, InputDevice::Gamepad(owned, _) if *owned == entity))
+        {
+            return None;
+        }
+        let player = self.devices.iter().position(|device| {
+            matches!(device, InputDevice::Gamepad(owned, owned_model)
+                if *owned_model == model && !connected(*owned))
+        })?;
+        self.devices[player] = InputDevice::Gamepad(entity, model);
+        Some(player)
+    }
+}
+
+/// Give a reconnected gamepad back to the player who had it. Bevy may bring
+/// the pad back as a new entity, so it is matched by model instead.
+pub fn reclaim_reconnected_gamepads(
+    mut events: EventReader<GamepadConnectionEvent>,
+    gamepads: Query<(), With<Gamepad>>,
+    mut roster: ResMut<Roster>,
+) {
+    for event in events.read() {
+        let GamepadConnection::Connected {
+            vendor_id,
+            product_id,
+            ..
+        } = &event.connection
+        else {
+            continue;
+        };
+        let model = GamepadModel {
+            vendor_id: *vendor_id,
+            product_id: *product_id,
+        };
+        if let Some(player) =
+            roster.reconnect(event.gamepad, model, |entity| gamepads.contains(entity))
+        {
+            info!("Gamepad reconnected for player {}", player + 1);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn solo_player_owns_every_device() {
+        let roster = Roster::default();
+        assert_eq!(roster.players(), 1);
+        assert_eq!(roster.player_of(InputDevice::Keyboard), Some(0));
+        assert_eq!(roster.player_of(InputDevice::Pointer), Some(0));
+    }
+
+    #[test]
+    fn each_device_joins_once() {
+        let mut roster = Roster::default();
+        assert_eq!(roster.join(InputDevice::Keyboard), Some(0));
+        assert_eq!(roster.join(InputDevice::Pointer), Some(1));
+        assert_eq!(roster.join(InputDevice::Keyboard), None);
+
+        assert_eq!(roster.players(), 2);
+        assert_eq!(roster.player_of(InputDevice::Pointer), Some(1));
+        assert_eq!(
+            roster.player_of(InputDevice::Gamepad(
+                Entity::PLACEHOLDER,
+                GamepadModel::default()
+            )),
+            None
+        );
+    }
+
+    #[test]
+    fn roster_is_capped() {
+        let mut roster = Roster::default();
+        roster.join(InputDevice::Keyboard);
+        roster.join(InputDevice::Pointer);
+        for index in 0..3 {
+            roster.join(InputDevice::Gamepad(
+                Entity::from_raw(index),
+                GamepadModel::default(),
+            ));
+        }
+        assert_eq!(roster.players(), MAX_PLAYERS);
+    }
+
+    #[test]
+    fn a_reconnected_gamepad_returns_to_its_player() {
+        let pad = GamepadModel {
+            vendor_id: Some(0x054c),
+            product_id: Some(0x09cc),
+        };
+        let mut roster = Roster::default();
+        roster.join(InputDevice::Keyboard);
+        roster.join(InputDevice::Gamepad(Entity::from_raw(1), pad));
+
+        // Still connected, so a second pad of the same model is someone else's
+        assert_eq!(roster.reconnect(Entity::from_raw(2), pad, |_| true), None);
+
+        assert_eq!(
+            roster.reconnect(Entity::from_raw(2), pad, |_| false),
+            Some(1)
+        );
+        assert_eq!(
+            roster.player_of(InputDevice::Gamepad(Entity::from_raw(2), pad)),
+            Some(1)
+        );
+    }
+}
@@ -0,0 +1,237 @@
+use bevy::input::touch::Touch;
+use bevy::prelude::*;
+use bevy::utils::HashSet;
+use bevy::window::PrimaryWindow;
+
+use super::{
+    Action, Binding, Bindings, GamepadModel, InputDevice, Roster, TouchGestures, VirtualJoystick,
+};
+
+/// This frame's actions, read by gameplay instead of the raw devices
+#[derive(Resource, Debug, Clone, Default)]
+pub struct ActionState {
+    pressed: HashSet<Action>,
+    just_pressed: HashSet<Action>,
+    /// Screen position of the mouse or touch press that triggered
+    /// `MoveToPointer` this frame
+    pub pointer: Option<Vec2>,
+    /// Screen position of a double-tap completed this frame
+    pub double_tap: Option<Vec2>,
+    /// Left stick of a gamepad, or the virtual joystick, pushed furthest
+    pub stick: Vec2,
+}
+
+impl ActionState {
+    pub fn pressed(&self, action: Action) -> bool {
+        self.pressed.contains(&action)
+    }
+
+    pub fn just_pressed(&self, action: Action) -> bool {
+        self.just_pressed.contains(&action)
+    }
+
+    /// Mark `action` as held, and as newly pressed if `just` is set
+    pub fn press(&mut self, action: Action, just: bool) {
+        self.pressed.insert(action);
+        if just {
+            self.just_pressed.insert(action);
+        }
+    }
+
+    /// Fold in what another device did, keeping the first pointer press and
+    /// the stick pushed furthest
+    pub fn merge(&mut self, other: &ActionState) {
+        self.pressed.extend(other.pressed.iter().copied());
+        self.just_pressed.extend(other.just_pressed.iter().copied());
+        self.pointer = self.pointer.or(other.pointer);
+        self.double_tap = self.double_tap.or(other.double_tap);
+        if other.stick.length() > self.stick.length() {
+            self.stick = other.stick;
+        }
+    }
+
+    /// Movement direction from the move actions or the stick, whichever is
+    /// pushed further; length at most 1
+    pub fn move_axis(&self) -> Vec2 {
+        let axis = |positive, negative| {
+            (self.pressed(positive) as i32 - self.pressed(negative) as i32) as f32
+        };
+        let keys = Vec2::new(
+            axis(Action::MoveRight, Action::MoveLeft),
+            axis(Action::MoveUp, Action::MoveDown),
+        )
+        .normalize_or_zero();
+
+        let stick = self.stick.clamp_length_max(1.0);
+        if stick.length() > keys.length() {
+            stick
+        } else {
+            keys
+        }
+    }
+}
+
+/// What each device did this frame, for letting devices join as players
+#[derive(Resource, Debug, Default)]
+pub struct DeviceActions {
+    pub devices: Vec<(InputDevice, ActionState)>,
+}
+
+impl DeviceActions {
+    pub fn get(&self, device: InputDevice) -> Option<&ActionState> {
+        self.devices
+            .iter()
+            .find(|(owned, _)| *owned == device)
+            .map(|(_, state)| state)
+    }
+}
+
+/// This frame's actions of each local player, from the devices they own
+#[derive(Resource, Debug, Default)]
+pub struct PlayerActions {
+    pub players: Vec<ActionState>,
+}
+
+/// Resolve every binding against the devices once per frame
+#[allow(clippy::too_many_arguments)]
+pub fn update_action_state(
+    keyboard: Res<ButtonInput<KeyCode>>,
+    mouse: Res<ButtonInput<MouseButton>>,
+    touches: Res<Touches>,
+    gamepads: Query<(Entity, &Gamepad)>,
+    windows: Query<&Window, With<PrimaryWindow>>,
+    bindings: Res<Bindings>,
+    roster: Res<Roster>,
+    joystick: Res<VirtualJoystick>,
+    time: Res<Time<Real>>,
+    mut gestures: ResMut<TouchGestures>,
+    mut device_actions: ResMut<DeviceActions>,
+    mut player_actions: ResMut<PlayerActions>,
+    mut state: ResMut<ActionState>,
+) {
+    // The joystick's touch is a drag, not a tap
+    let tapping = |touch: &&Touch| Some(touch.id()) != joystick.touch;
+    let cursor = windows.get_single().ok().and_then(Window::cursor_position);
+    let touch = touches
+        .iter_just_pressed()
+        .find(tapping)
+        .map(|touch| touch.position());
+    let touching = touches.iter().any(|touch| tapping(&touch));
+
+    let devices = [InputDevice::Keyboard, InputDevice::Pointer]
+        .into_iter()
+        .chain(
+            gamepads
+                .iter()
+                .map(|(entity, pad)| InputDevice::Gamepad(entity, GamepadModel::of(pad))),
+        );
+
+    device_actions.devices.clear();
+    for device in devices {
+        let mut device_state = ActionState::default();
+        let gamepad = match device {
+            InputDevice::Gamepad(entity, _) => gamepads.get(entity).ok().map(|(_, pad)| pad),
+            _ => None,
+        };
+        match device {
+            InputDevice::Pointer => {
+                device_state.stick = joystick.axis();
+                if let Some(position) = touch {
+                    device_state.double_tap = gestures.tap(time.elapsed_secs_f64(), position);
+                }
+            }
+            InputDevice::Gamepad(..) => {
+                device_state.stick = gamepad.map_or(Vec2::ZERO, Gamepad::left_stick);
+            }
+            InputDevice::Keyboard => {}
+        }
+
+        for action in Action::ALL {
+            for binding in bindings.get(action) {
+                let (pressed, just_pressed, pointer) = match (*binding, device) {
+                    (Binding::Key(key), InputDevice::Keyboard) => {
+                        (keyboard.pressed(key), keyboard.just_pressed(key), None)
+                    }
+                    (Binding::Mouse(button), InputDevice::Pointer) => {
+                        (mouse.pressed(button), mouse.just_pressed(button), cursor)
+                    }
+                    (Binding::Touch, InputDevice::Pointer) => (touching, touch.is_some(), touch),
+                    (Binding::Gamepad(button), InputDevice::Gamepad(..)) => (
+                        gamepad.is_some_and(|pad| pad.pressed(button)),
+                        gamepad.is_some_and(|pad| pad.just_pressed(button)),
+                        None,
+                    ),
+                    _ => continue,
+                };
+
+                if !pressed && !just_pressed {
+                    continue;
+                }
+                device_state.press(action, just_pressed);
+                if just_pressed && action == Action::MoveToPointer && device_state.pointer.is_none()
+                {
+                    device_state.pointer = pointer;
+                }
+            }
+        }
+        device_actions.devices.push((device, device_state));
+    }
+
+    // Menus answer to every device
+    *state = ActionState::default();
+    for (_, device_state) in &device_actions.devices {
+        state.merge(device_state);
+    }
+
+    player_actions.players = if roster.devices.is_empty() {
+        vec![state.clone()]
+    } else {
+        roster
+            .devices
+            .iter()
+            .map(|device| device_actions.get(*device).cloned().unwrap_or_default())
+            .collect()
+    };
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn opposite_moves_cancel_out() {
+        let mut state = ActionState::default();
+        state.press(Action::MoveLeft, false);
+        state.press(Action::MoveRight, false);
+        state.press(Action::MoveUp, true);
+        assert_eq!(state.move_axis(), Vec2::Y);
+    }
+
+    #[test]
+    fn stick_wins_when_pushed_further() {
+        let mut state = ActionState {
+            stick: Vec2::new(0.0, -0.5),
+            ..default()
+        };
+        assert_eq!(state.move_axis(), Vec2::new(0.0, -0.5));
+
+        state.press(Action::MoveRight, true);
+        assert_eq!(state.move_axis(), Vec2::X);
+    }
+
+    #[test]
+    fn merged_devices_press_everything_either_pressed() {
+        let mut keyboard = ActionState::default();
+        keyboard.press(Action::MoveUp, false);
+        let mut pad = ActionState {
+            stick: Vec2::new(0.5, 0.0),
+            ..default()
+        };
+        pad.press(Action::Wiggle, true);
+
+        keyboard.merge(&pad);
+        assert!(keyboard.pressed(Action::MoveUp));
+        assert!(keyboard.just_pressed(Action::Wiggle));
+        assert_eq!(keyboard.stick, Vec2::new(0.5, 0.0));
+    }
+}
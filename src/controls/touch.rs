@@ -0,0 +1,135 @@
+use bevy::prelude::*;
+use bevy::window::PrimaryWindow;
+
+use crate::game::PauseState;
+
+/// Longest gap between the two taps of a double-tap, in seconds
+pub const DOUBLE_TAP_WINDOW: f64 = 0.3;
+
+/// Furthest apart the two taps of a double-tap can land, in logical pixels
+pub const DOUBLE_TAP_DISTANCE: f32 = 40.0;
+
+/// How far a drag has to go to push the virtual joystick all the way
+pub const JOYSTICK_RADIUS: f32 = 60.0;
+
+/// Recognises double-taps from the touch presses that reach gameplay
+#[derive(Resource, Debug, Default)]
+pub struct TouchGestures {
+    last_tap: Option<(f64, Vec2)>,
+    /// Set once the player has touched the screen, to show touch-only HUD
+    pub seen_touch: bool,
+}
+
+impl TouchGestures {
+    /// Record a tap at `now` seconds, returning where a double-tap landed if
+    /// this tap completes one
+    pub fn tap(&mut self, now: f64, position: Vec2) -> Option<Vec2> {
+        self.seen_touch = true;
+        let double = self.last_tap.is_some_and(|(time, first)| {
+            now - time <= DOUBLE_TAP_WINDOW && first.distance(position) <= DOUBLE_TAP_DISTANCE
+        });
+
+        // A third tap starts a new gesture rather than chaining another double
+        self.last_tap = if double { None } else { Some((now, position)) };
+        double.then_some(position)
+    }
+}
+
+/// Optional on-screen stick: a touch that starts on the left half of the
+/// screen drags it, and that touch no longer counts as a tap
+#[derive(Resource, Debug, Default)]
+pub struct VirtualJoystick {
+    pub enabled: bool,
+    /// Id of the touch driving the stick
+    pub touch: Option<u64>,
+    /// Where that touch started, in logical pixels
+    pub origin: Vec2,
+    /// Where that touch is now, in logical pixels
+    pub position: Vec2,
+}
+
+impl VirtualJoystick {
+    /// Stick direction with +y up, length at most 1
+    pub fn axis(&self) -> Vec2 {
+        if self.touch.is_none() {
+            return Vec2::ZERO;
+        }
+        let drag = (self.position - self.origin) / JOYSTICK_RADIUS;
+        Vec2::new(drag.x, -drag.y).clamp_length_max(1.0)
+    }
+}
+
+/// Claim, follow and release the touch driving the virtual joystick. Only
+/// touches during unpaused play are claimed, so menu buttons on the left half
+/// of the screen still get their taps.
+pub fn update_virtual_joystick(
+    touches: Res<Touches>,
+    windows: Query<&Window, With<PrimaryWindow>>,
+    pause_state: Option<Res<State<PauseState>>>,
+    mut joystick: ResMut<VirtualJoystick>,
+) {
+    let playing = pause_state.is_some_and(|state| *state.get() == PauseState::Running);
+    if !joystick.enabled || !playing {
+        if joystick.touch.is_some() {
+            joystick.touch = None;
+        }
+        return;
+    }
+
+    if let Some(id) = joystick.touch {
+        match touches.get_pressed(id) {
+            Some(touch) => joystick.position = touch.position(),
+            None => joystick.touch = None,
+        }
+        return;
+    }
+
+    let Ok(window) = windows.get_single() else {
+        return;
+    };
+    let half = window.width() / 2.0;
+    if let Some(touch) = touches
+        .iter_just_pressed()
+        .find(|touch| touch.position().x < half)
+    {
+        joystick.touch = Some(touch.id());
+        joystick.origin = touch.position();
+        joystick.position = touch.position();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn two_quick_taps_in_one_place_are_a_double_tap() {
+        let mut gestures = TouchGestures::default();
+        assert_eq!(gestures.tap(1.0, Vec2::new(100.0, 100.0)), None);
+        assert_eq!(
+            gestures.tap(1.2, Vec2::new(110.0, 95.0)),
+            Some(Vec2::new(110.0, 95.0))
+        );
+        // The next tap starts over
+        assert_eq!(gestures.tap(1.3, Vec2::new(110.0, 95.0)), None);
+    }
+
+    #[test]
+    fn slow_or_distant_taps_are_not() {
+        let mut gestures = TouchGestures::default();
+        gestures.tap(1.0, Vec2::ZERO);
+        assert_eq!(gestures.tap(1.5, Vec2::ZERO), None);
+        assert_eq!(gestures.tap(1.6, Vec2::new(200.0, 0.0)), None);
+    }
+
+    #[test]
+    fn joystick_axis_points_up_when_dragged_up_the_screen() {
+        let joystick = VirtualJoystick {
+            enabled: true,
+            touch: Some(0),
+            origin: Vec2::new(100.0, 300.0),
+            position: Vec2::new(100.0, 180.0),
+        };
+        assert_eq!(joystick.axis(), Vec2::Y);
+    }
+}
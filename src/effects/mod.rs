@@ -22,9 +22,9 @@ impl Plugin for EffectsPlugin {
                     update_sneeze_animation,
                     update_screen_shake,
                     update_achoo_text,
-                    update_scattering_pollen,
                 ),
-            );
+            )
+            .add_systems(FixedUpdate, update_scattering_pollen);
     }
 }
 
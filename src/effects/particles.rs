@@ -1,6 +1,8 @@
 use bevy::prelude::*;
+use rand::Rng;
 
 use super::CollectionEvent;
+use crate::game::{GameRng, RoundEntity};
 
 #[derive(Component)]
 pub struct Particle {
@@ -11,15 +13,17 @@ pub struct Particle {
 pub fn spawn_collection_particles(
     mut commands: Commands,
     mut events: EventReader<CollectionEvent>,
+    mut rng: ResMut<GameRng>,
 ) {
     for event in events.read() {
         // Spawn 5 particles in random directions
-        for i in 0..5 {
-            let angle = (i as f32 / 5.0) * std::f32::consts::TAU + 0.2 * (i as f32);
-            let speed = 80.0 + (i as f32 * 10.0);
+        for _ in 0..5 {
+            let angle = rng.effects().gen_range(0.0..std::f32::consts::TAU);
+            let speed = rng.effects().gen_range(80.0..130.0);
             let velocity = Vec2::new(angle.cos(), angle.sin()) * speed;
 
             commands.spawn((
+                RoundEntity,
                 Particle {
                     velocity,
                     lifetime: Timer::from_seconds(0.3, TimerMode::Once),
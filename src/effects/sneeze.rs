@@ -1,6 +1,9 @@
 use bevy::prelude::*;
+use rand::Rng;
 
 use crate::bee::{Bee, Sneezing};
+use crate::game::{GameRng, RoundEntity};
+use crate::save::Settings;
 
 /// Event sent when a sneeze happens
 #[derive(Event)]
@@ -85,7 +88,10 @@ pub fn handle_sneeze_effects(
     mut events: EventReader<SneezeEvent>,
     bees: Query<&Transform, With<Bee>>,
     camera: Query<(Entity, &Transform), With<Camera2d>>,
+    settings: Option<Res<Settings>>,
 ) {
+    let reduce_motion = settings.is_some_and(|settings| settings.reduce_motion);
+
     for event in events.read() {
         // Add expansion animation to bee
         if let Ok(transform) = bees.get(event.bee_entity) {
@@ -94,8 +100,10 @@ pub fn handle_sneeze_effects(
                 .insert(SneezeAnimation::new(transform.scale));
         }
 
-        // Add screen shake to camera
-        if let Ok((camera_entity, camera_transform)) = camera.get_single() {
+        // Add screen shake to camera, unless the player asked for less motion
+        if let Some((camera_entity, camera_transform)) =
+            camera.get_single().ok().filter(|_| !reduce_motion)
+        {
             commands.entity(camera_entity).insert(ScreenShake::new(
                 0.2,
                 8.0,
@@ -105,6 +113,7 @@ pub fn handle_sneeze_effects(
 
         // Spawn ACHOO text
         commands.spawn((
+            RoundEntity,
             AchooText::default(),
             Text2d::new("ACHOO!"),
             TextFont {
@@ -151,6 +160,7 @@ pub fn update_screen_shake(
     mut commands: Commands,
     mut camera: Query<(Entity, &mut Transform, &mut ScreenShake), With<Camera2d>>,
     time: Res<Time>,
+    mut rng: ResMut<GameRng>,
 ) {
     for (entity, mut transform, mut shake) in &mut camera {
         shake.timer.tick(time.delta());
@@ -161,8 +171,8 @@ pub fn update_screen_shake(
         } else {
             // Random offset based on intensity, decreasing over time
             let remaining = 1.0 - shake.timer.fraction();
-            let offset_x = (time.elapsed_secs() * 100.0).sin() * shake.intensity * remaining;
-            let offset_y = (time.elapsed_secs() * 130.0).cos() * shake.intensity * remaining;
+            let offset_x = rng.effects().gen_range(-1.0..1.0) * shake.intensity * remaining;
+            let offset_y = rng.effects().gen_range(-1.0..1.0) * shake.intensity * remaining;
 
             transform.translation = shake.original_translation + Vec3::new(offset_x, offset_y, 0.0);
         }
@@ -1,5 +1,7 @@
 use bevy::prelude::*;
 
+use crate::game::{InterpolatedTransform, RoundEntity};
+
 #[derive(Component, Debug, Clone, Default, Reflect)]
 #[reflect(Component)]
 pub struct Flower;
@@ -10,6 +12,8 @@ pub struct FlowerHead {
     pub movement_pattern: MovementPattern,
     pub pollen_drop_timer: Timer,
     pub rizz: f32,
+    /// Point, relative to its flower, that the head's pattern is centred on
+    pub centre: Vec2,
 }
 
 impl Default for FlowerHead {
@@ -18,6 +22,7 @@ impl Default for FlowerHead {
             movement_pattern: MovementPattern::default(),
             pollen_drop_timer: Timer::from_seconds(2.0, TimerMode::Repeating),
             rizz: 0.0,
+            centre: Vec2::new(0.0, 120.0),
         }
     }
 }
@@ -133,6 +138,7 @@ pub struct FlowerHeadBundle {
     pub head: FlowerHead,
     pub transform: Transform,
     pub global_transform: GlobalTransform,
+    pub interpolated: InterpolatedTransform,
 }
 
 #[derive(Bundle, Default)]
@@ -140,4 +146,5 @@ pub struct PollenBundle {
     pub pollen: Pollen,
     pub transform: Transform,
     pub global_transform: GlobalTransform,
+    pub round: RoundEntity,
 }
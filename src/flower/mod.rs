@@ -9,8 +9,9 @@ pub use pollen::*;
 pub use rizz::*;
 
 use bevy::prelude::*;
+use bevy::transform::systems::sync_simple_transforms;
 
-use crate::game::GameState;
+use crate::game::{AppState, GameState};
 
 pub struct FlowerPlugin;
 
@@ -23,7 +24,7 @@ impl Plugin for FlowerPlugin {
             .register_type::<PollenCache>()
             .add_event::<TickleEvent>()
             .add_systems(
-                Update,
+                FixedUpdate,
                 (
                     decay_rizz,
                     update_rizz_behavior,
@@ -33,11 +34,21 @@ impl Plugin for FlowerPlugin {
                     pursue_bee,
                     spawn_pollen_from_heads,
                     respawn_caches,
-                    setup_rizz_meters,
-                    update_rizz_meters,
                 )
                     .chain()
                     .run_if(in_state(GameState::Playing)),
+            )
+            .add_systems(
+                FixedPostUpdate,
+                keep_heads_in_play_area
+                    .before(sync_simple_transforms)
+                    .run_if(in_state(GameState::Playing)),
+            )
+            .add_systems(
+                Update,
+                (setup_rizz_meters, update_rizz_meters)
+                    .chain()
+                    .run_if(in_state(GameState::Playing).or(in_state(AppState::Online))),
             );
     }
 }
@@ -1,17 +1,15 @@
 use bevy::prelude::*;
 
-use super::{FlowerHead, MovementPattern, RizzBehavior};
-
-/// Lazy movement parameters for blissed heads
-const BLISS_RADIUS: f32 = 20.0;
-const BLISS_SPEED: f32 = 0.3;
+use super::{Flower, FlowerHead, MovementPattern, RizzBehavior};
+use crate::game::{GameConfig, PlayArea, RizzConfig};
 
 pub fn update_flower_head_movement(
     mut heads: Query<(&mut Transform, &mut FlowerHead, Option<&RizzBehavior>)>,
+    config: Res<GameConfig>,
     time: Res<Time>,
 ) {
     for (mut transform, mut head, behavior) in &mut heads {
-        let delta = time.delta_secs();
+        let delta = time.delta_secs() * config.movement.flower_head_speed_scale;
 
         match behavior {
             Some(RizzBehavior::Pursuing) => {
@@ -21,22 +19,49 @@ pub fn update_flower_head_movement(
             }
             Some(RizzBehavior::Blissed) => {
                 // Lazy, predictable circular movement
-                let offset = calculate_blissed_movement(&mut head.movement_pattern, delta);
-                transform.translation.x = offset.x;
-                transform.translation.y = offset.y;
+                let offset =
+                    calculate_blissed_movement(&mut head.movement_pattern, &config.rizz, delta);
+                transform.translation.x = offset.x + head.centre.x;
+                transform.translation.y = offset.y + head.centre.y;
             }
             _ => {
                 // Normal movement pattern
                 let offset = calculate_pattern_offset(&mut head.movement_pattern, delta);
-                transform.translation.x = offset.x;
-                transform.translation.y = offset.y;
+                transform.translation.x = offset.x + head.centre.x;
+                transform.translation.y = offset.y + head.centre.y;
             }
         }
     }
 }
 
-/// Slow, predictable circular movement for blissed heads
-fn calculate_blissed_movement(pattern: &mut MovementPattern, delta: f32) -> Vec2 {
+/// Stop flower heads, which move relative to their flower, at the edge of
+/// the garden
+pub fn keep_heads_in_play_area(
+    mut heads: Query<(&mut Transform, &Parent), With<FlowerHead>>,
+    flowers: Query<&Transform, (With<Flower>, Without<FlowerHead>)>,
+    area: Option<Res<PlayArea>>,
+) {
+    let Some(area) = area else {
+        return;
+    };
+    for (mut transform, parent) in &mut heads {
+        let Ok(flower) = flowers.get(parent.get()) else {
+            continue;
+        };
+        let origin = flower.translation.truncate();
+        let position = area.clamp(origin + transform.translation.truncate()) - origin;
+        transform.translation.x = position.x;
+        transform.translation.y = position.y;
+    }
+}
+
+/// Slow, predictable circular movement for blissed heads, as an offset from
+/// the head's centre
+fn calculate_blissed_movement(
+    pattern: &mut MovementPattern,
+    rizz: &RizzConfig,
+    delta: f32,
+) -> Vec2 {
     // Extract current angle or use pattern's internal state
     let angle = match pattern {
         MovementPattern::Circular { angle, .. } => angle,
@@ -44,18 +69,19 @@ fn calculate_blissed_movement(pattern: &mut MovementPattern, delta: f32) -> Vec2
         MovementPattern::Sway { offset, .. } => offset,
     };
 
-    *angle += BLISS_SPEED * delta;
+    *angle += rizz.bliss_speed * delta;
     if *angle > std::f32::consts::TAU {
         *angle -= std::f32::consts::TAU;
     }
 
-    // Simple lazy circle at base height
+    // Simple lazy circle around the base height
     Vec2::new(
-        angle.cos() * BLISS_RADIUS,
-        angle.sin() * BLISS_RADIUS + 120.0,
+        angle.cos() * rizz.bliss_radius,
+        angle.sin() * rizz.bliss_radius,
     )
 }
 
+/// Where a head's pattern puts it, relative to its base height
 fn calculate_pattern_offset(pattern: &mut MovementPattern, delta: f32) -> Vec2 {
     match pattern {
         MovementPattern::Circular {
@@ -67,7 +93,7 @@ fn calculate_pattern_offset(pattern: &mut MovementPattern, delta: f32) -> Vec2 {
             if *angle > std::f32::consts::TAU {
                 *angle -= std::f32::consts::TAU;
             }
-            Vec2::new(angle.cos() * *radius, angle.sin() * *radius + 120.0)
+            Vec2::new(angle.cos() * *radius, angle.sin() * *radius)
         }
         MovementPattern::Figure8 {
             width,
@@ -80,7 +106,7 @@ fn calculate_pattern_offset(pattern: &mut MovementPattern, delta: f32) -> Vec2 {
                 *t -= std::f32::consts::TAU;
             }
             // Lissajous curve for figure-8
-            Vec2::new(t.sin() * *width, (2.0 * *t).sin() * *height + 120.0)
+            Vec2::new(t.sin() * *width, (2.0 * *t).sin() * *height)
         }
         MovementPattern::Sway {
             amplitude,
@@ -91,7 +117,7 @@ fn calculate_pattern_offset(pattern: &mut MovementPattern, delta: f32) -> Vec2 {
             if *offset > std::f32::consts::TAU {
                 *offset -= std::f32::consts::TAU;
             }
-            Vec2::new(offset.sin() * *amplitude, 120.0)
+            Vec2::new(offset.sin() * *amplitude, 0.0)
         }
     }
 }
@@ -108,10 +134,10 @@ mod tests {
             angle: 0.0,
         };
 
-        // At angle 0, should be at (radius, base_height)
+        // At angle 0, should be at (radius, 0) from the base height
         let offset = calculate_pattern_offset(&mut pattern, 0.0);
         assert!((offset.x - 50.0).abs() < 0.01);
-        assert!((offset.y - 120.0).abs() < 0.01);
+        assert!(offset.y.abs() < 0.01);
     }
 
     #[test]
@@ -1,19 +1,20 @@
 use bevy::prelude::*;
 
 use super::{CacheSpawnPoint, FlowerHead, Pollen, PollenBundle};
+use crate::game::GameConfig;
 
-const MAX_POLLEN_COUNT: usize = 100;
 const POLLEN_SIZE: f32 = 10.0;
 
 pub fn spawn_pollen_from_heads(
     mut commands: Commands,
     mut heads: Query<(&GlobalTransform, &mut FlowerHead)>,
     pollen_query: Query<&Pollen>,
+    config: Res<GameConfig>,
     time: Res<Time>,
 ) {
     // Check pollen cap
     let current_pollen_count = pollen_query.iter().count();
-    if current_pollen_count >= MAX_POLLEN_COUNT {
+    if current_pollen_count >= config.pollen.max_on_ground {
         return;
     }
 
@@ -26,7 +27,9 @@ pub fn spawn_pollen_from_heads(
 
             commands.spawn((
                 PollenBundle {
-                    pollen: Pollen::default(),
+                    pollen: Pollen {
+                        value: config.pollen.base_value,
+                    },
                     transform: Transform::from_xyz(pos.x, pos.y, 0.5),
                     ..default()
                 },
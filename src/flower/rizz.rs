@@ -2,19 +2,14 @@ use bevy::prelude::*;
 
 use super::FlowerHead;
 use crate::bee::Bee;
-
-const RIZZ_DECAY_RATE: f32 = 5.0;
-const LOW_RIZZ_THRESHOLD: f32 = 30.0;
-const HIGH_RIZZ_THRESHOLD: f32 = 70.0;
-const PURSUIT_SPEED: f32 = 80.0;
-const TICKLE_RIZZ_DROP: f32 = 30.0;
-const ATTENTION_SNAP_DURATION: f32 = 1.0;
-const ATTENTION_SNAP_SPEED: f32 = 150.0;
+use crate::game::GameConfig;
 
 /// Event sent when a cache is collected (tickle)
 #[derive(Event)]
 pub struct TickleEvent {
     pub cache_position: Vec2,
+    /// The bee that collected the cache
+    pub bee: Entity,
 }
 
 /// Component for attention snap - head moves to tickle location
@@ -46,11 +41,11 @@ pub struct RizzMeterFill {
 }
 
 /// System to decay rizz over time
-pub fn decay_rizz(mut heads: Query<&mut FlowerHead>, time: Res<Time>) {
+pub fn decay_rizz(mut heads: Query<&mut FlowerHead>, config: Res<GameConfig>, time: Res<Time>) {
     let delta = time.delta_secs();
 
     for mut head in &mut heads {
-        head.rizz = (head.rizz - RIZZ_DECAY_RATE * delta).max(0.0);
+        head.rizz = (head.rizz - config.rizz.decay_rate * delta).max(0.0);
     }
 }
 
@@ -58,11 +53,12 @@ pub fn decay_rizz(mut heads: Query<&mut FlowerHead>, time: Res<Time>) {
 pub fn update_rizz_behavior(
     mut commands: Commands,
     heads: Query<(Entity, &FlowerHead, Option<&RizzBehavior>)>,
+    config: Res<GameConfig>,
 ) {
     for (entity, head, current_behavior) in &heads {
-        let new_behavior = if head.rizz < LOW_RIZZ_THRESHOLD {
+        let new_behavior = if head.rizz < config.rizz.low_threshold {
             RizzBehavior::Pursuing
-        } else if head.rizz > HIGH_RIZZ_THRESHOLD {
+        } else if head.rizz > config.rizz.high_threshold {
             RizzBehavior::Blissed
         } else {
             RizzBehavior::Normal
@@ -79,6 +75,7 @@ pub fn update_rizz_behavior(
 pub fn pursue_bee(
     mut heads: Query<(&GlobalTransform, &mut Transform, &RizzBehavior, &Parent), With<FlowerHead>>,
     bees: Query<&GlobalTransform, With<Bee>>,
+    config: Res<GameConfig>,
     time: Res<Time>,
 ) {
     let delta = time.delta_secs();
@@ -102,7 +99,7 @@ pub fn pursue_bee(
         if let Some(bee_pos) = nearest_bee {
             // Calculate direction to bee (in local space relative to parent)
             let direction = (bee_pos - head_pos).normalize_or_zero();
-            let movement = direction * PURSUIT_SPEED * delta;
+            let movement = direction * config.rizz.pursuit_speed * delta;
 
             local_transform.translation.x += movement.x;
             local_transform.translation.y += movement.y;
@@ -147,17 +144,20 @@ pub fn setup_rizz_meters(mut commands: Commands, heads: Query<Entity, Added<Flow
 pub fn update_rizz_meters(
     heads: Query<&FlowerHead>,
     mut fills: Query<(&RizzMeterFill, &mut Sprite)>,
+    config: Res<GameConfig>,
 ) {
+    let rizz = &config.rizz;
+
     for (fill, mut sprite) in &mut fills {
         if let Ok(head) = heads.get(fill.head_entity) {
-            let percentage = head.rizz / 100.0;
+            let percentage = head.rizz / rizz.max;
             let width = 38.0 * percentage;
             sprite.custom_size = Some(Vec2::new(width, 4.0));
 
             // Color based on level
-            let color = if percentage < 0.3 {
+            let color = if head.rizz < rizz.low_threshold {
                 Color::srgb(0.9, 0.2, 0.2) // Red - pursuing
-            } else if percentage > 0.7 {
+            } else if head.rizz > rizz.high_threshold {
                 Color::srgb(0.2, 0.9, 0.4) // Green - blissed
             } else {
                 Color::srgb(0.9, 0.6, 0.2) // Orange - normal
@@ -172,6 +172,7 @@ pub fn handle_tickle_event(
     mut commands: Commands,
     mut events: EventReader<TickleEvent>,
     mut heads: Query<(Entity, &GlobalTransform, &mut FlowerHead)>,
+    config: Res<GameConfig>,
 ) {
     for event in events.read() {
         // Find nearest head to cache
@@ -185,12 +186,12 @@ pub fn handle_tickle_event(
 
         if let Some((entity, _dist, mut head)) = nearest_head {
             // Drop rizz
-            head.rizz = (head.rizz - TICKLE_RIZZ_DROP).max(0.0);
+            head.rizz = (head.rizz - config.rizz.tickle_drop).max(0.0);
 
             // Add attention snap
             commands.entity(entity).insert(AttentionSnap {
                 target: event.cache_position,
-                timer: Timer::from_seconds(ATTENTION_SNAP_DURATION, TimerMode::Once),
+                timer: Timer::from_seconds(config.rizz.attention_snap_duration, TimerMode::Once),
             });
         }
     }
@@ -203,6 +204,7 @@ pub fn update_attention_snap(
         (Entity, &GlobalTransform, &mut Transform, &mut AttentionSnap),
         With<FlowerHead>,
     >,
+    config: Res<GameConfig>,
     time: Res<Time>,
 ) {
     let delta = time.delta_secs();
@@ -218,7 +220,7 @@ pub fn update_attention_snap(
         // Move toward target location
         let current_pos = global_transform.translation().truncate();
         let direction = (snap.target - current_pos).normalize_or_zero();
-        let movement = direction * ATTENTION_SNAP_SPEED * delta;
+        let movement = direction * config.rizz.attention_snap_speed * delta;
 
         local_transform.translation.x += movement.x;
         local_transform.translation.y += movement.y;
@@ -1,13 +1,12 @@
 use bevy::prelude::*;
 
-use super::GameState;
+use super::{GameConfig, GameState, SessionTimer};
 use crate::bee::{AllergyMeter, Bee, CollectedPollen, SneezeCount};
-
-const WIN_POLLEN_THRESHOLD: u32 = 20;
-const MAX_SNEEZES: u32 = 3;
+use crate::controls::{Action, ActionState};
 
 pub fn check_win_condition(
     bees: Query<&CollectedPollen, With<Bee>>,
+    config: Res<GameConfig>,
     current_state: Res<State<GameState>>,
     mut next_state: ResMut<NextState<GameState>>,
 ) {
@@ -15,52 +14,69 @@ pub fn check_win_condition(
         return;
     }
 
-    for collected in &bees {
-        if collected.count >= WIN_POLLEN_THRESHOLD {
-            next_state.set(GameState::Won);
-            return;
-        }
+    // Co-op bees pool their pollen
+    let team_pollen: u32 = bees.iter().map(|collected| collected.count).sum();
+    if !bees.is_empty() && team_pollen >= config.win_lose.win_pollen {
+        next_state.set(GameState::Won);
     }
 }
 
 pub fn check_lose_condition(
     bees: Query<(&AllergyMeter, Option<&SneezeCount>), With<Bee>>,
+    config: Res<GameConfig>,
+    timer: Res<SessionTimer>,
     current_state: Res<State<GameState>>,
     mut next_state: ResMut<NextState<GameState>>,
 ) {
-    if *current_state.get() != GameState::Playing {
+    if *current_state.get() != GameState::Playing || config.win_lose.no_fail {
         return;
     }
 
-    for (meter, sneeze_count) in &bees {
-        // Lose if allergy hits max
-        if meter.value >= meter.max {
+    // Lose if the garden is timed and time ran out
+    if let Some(limit) = config.win_lose.time_limit {
+        if timer.elapsed >= limit {
             next_state.set(GameState::Lost);
             return;
         }
+    }
 
-        // Lose if sneezed too many times
-        if let Some(count) = sneeze_count {
-            if count.count >= MAX_SNEEZES {
-                next_state.set(GameState::Lost);
-                return;
-            }
-        }
+    // Lose if any bee's allergy hits max
+    if bees.iter().any(|(meter, _)| meter.value >= meter.max) {
+        next_state.set(GameState::Lost);
+        return;
+    }
+
+    // Lose if the team sneezed too many times; each bee adds to the budget
+    let team_sneezes: u32 = bees
+        .iter()
+        .flat_map(|(_, count)| count)
+        .map(|count| count.count)
+        .sum();
+    let budget = config.win_lose.max_sneezes * bees.iter().count() as u32;
+    if !bees.is_empty() && team_sneezes >= budget {
+        next_state.set(GameState::Lost);
     }
 }
 
 pub fn handle_restart_input(
-    mouse_button: Res<ButtonInput<MouseButton>>,
-    touches: Res<Touches>,
+    actions: Res<ActionState>,
+    buttons: Query<&Interaction, With<Button>>,
     current_state: Res<State<GameState>>,
     mut next_state: ResMut<NextState<GameState>>,
 ) {
-    if *current_state.get() == GameState::Playing {
+    if !matches!(current_state.get(), GameState::Won | GameState::Lost) {
         return;
     }
 
-    if mouse_button.just_pressed(MouseButton::Left) || touches.iter_just_pressed().next().is_some()
+    // Clicks on overlay buttons (e.g. difficulty picker) don't restart
+    if buttons
+        .iter()
+        .any(|interaction| *interaction != Interaction::None)
     {
+        return;
+    }
+
+    if actions.just_pressed(Action::Confirm) {
         next_state.set(GameState::Playing);
     }
 }
@@ -1,14 +1,31 @@
+use std::fmt;
+
+use bevy::asset::io::Reader;
+use bevy::asset::{AssetLoadFailedEvent, AssetLoader, LoadContext};
 use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
 
-#[derive(Resource, Default)]
+use super::Difficulty;
+
+/// Path of the tunable config file, relative to the assets directory
+pub const GAME_CONFIG_PATH: &str = "config/game.ron";
+
+#[derive(Resource, Asset, TypePath, Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
 pub struct GameConfig {
     pub allergy: AllergyConfig,
     pub sneeze: SneezeConfig,
     pub pollen: PollenConfig,
     pub movement: MovementConfig,
+    pub wiggle: WiggleConfig,
+    pub rizz: RizzConfig,
+    pub companion: CompanionConfig,
+    pub win_lose: WinLoseConfig,
+    pub difficulty: DifficultyConfig,
 }
 
-#[derive(Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
 pub struct AllergyConfig {
     pub max_value: f32,
     pub base_decay_rate: f32,
@@ -27,11 +44,14 @@ impl Default for AllergyConfig {
     }
 }
 
-#[derive(Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
 pub struct SneezeConfig {
     pub threshold: f32,
     pub drop_percent: f32,
     pub post_sneeze_value: f32,
+    /// How long the bee is stunned after a sneeze
+    pub stagger_duration: f32,
 }
 
 impl Default for SneezeConfig {
@@ -40,15 +60,20 @@ impl Default for SneezeConfig {
             threshold: 80.0,
             drop_percent: 0.25,
             post_sneeze_value: 20.0,
+            stagger_duration: 0.5,
         }
     }
 }
 
-#[derive(Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
 pub struct PollenConfig {
     pub base_value: u32,
     pub cache_value: u32,
-    pub win_threshold: u32,
+    pub collection_radius: f32,
+    pub cache_collection_radius: f32,
+    /// Flower heads stop dropping pollen once this many are on the ground
+    pub max_on_ground: usize,
 }
 
 impl Default for PollenConfig {
@@ -56,22 +81,645 @@ impl Default for PollenConfig {
         Self {
             base_value: 1,
             cache_value: 5,
-            win_threshold: 50,
+            collection_radius: 25.0,
+            cache_collection_radius: 30.0,
+            max_on_ground: 100,
         }
     }
 }
 
-#[derive(Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
 pub struct MovementConfig {
     pub bee_speed: f32,
-    pub flower_head_speed: f32,
+    /// How fast keyboard and stick steering reaches full speed (units/s^2)
+    pub bee_acceleration: f32,
+    /// How fast the bee coasts to a stop once steering stops (units/s^2)
+    pub bee_friction: f32,
+    /// Multiplier applied to every flower head movement pattern speed
+    pub flower_head_speed_scale: f32,
+    /// Retired absolute head speed that nothing read. Only kept so files
+    /// still setting it are told to use `flower_head_speed_scale` instead.
+    #[serde(skip_serializing, deserialize_with = "retired")]
+    pub flower_head_speed: Option<f32>,
+}
+
+/// Reads a retired key's value as `Some` so it can be reported
+fn retired<'de, D: serde::Deserializer<'de>>(deserializer: D) -> Result<Option<f32>, D::Error> {
+    f32::deserialize(deserializer).map(Some)
 }
 
 impl Default for MovementConfig {
     fn default() -> Self {
         Self {
             bee_speed: 150.0,
-            flower_head_speed: 50.0,
+            bee_acceleration: 900.0,
+            bee_friction: 600.0,
+            flower_head_speed_scale: 1.0,
+            flower_head_speed: None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct WiggleConfig {
+    pub duration: f32,
+    pub cooldown: f32,
+    pub range: f32,
+    /// Rizz granted to a head right next to the bee, falling off with distance
+    pub rizz_base: f32,
+    pub frequency: f32,
+    pub amplitude: f32,
+}
+
+impl Default for WiggleConfig {
+    fn default() -> Self {
+        Self {
+            duration: 0.5,
+            cooldown: 2.0,
+            range: 150.0,
+            rizz_base: 20.0,
+            frequency: 20.0,
+            amplitude: 10.0,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct RizzConfig {
+    pub max: f32,
+    pub decay_rate: f32,
+    /// Below this heads pursue the nearest bee
+    pub low_threshold: f32,
+    /// Above this heads become blissed and move lazily
+    pub high_threshold: f32,
+    pub pursuit_speed: f32,
+    pub tickle_drop: f32,
+    pub attention_snap_duration: f32,
+    pub attention_snap_speed: f32,
+    pub bliss_radius: f32,
+    pub bliss_speed: f32,
+}
+
+impl Default for RizzConfig {
+    fn default() -> Self {
+        Self {
+            max: 100.0,
+            decay_rate: 5.0,
+            low_threshold: 30.0,
+            high_threshold: 70.0,
+            pursuit_speed: 80.0,
+            tickle_drop: 30.0,
+            attention_snap_duration: 1.0,
+            attention_snap_speed: 150.0,
+            bliss_radius: 20.0,
+            bliss_speed: 0.3,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct CompanionConfig {
+    pub diva: DivaConfig,
+    pub healer: HealerConfig,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct DivaConfig {
+    pub move_speed: f32,
+    /// Wiggle when a head in range has less rizz than this
+    pub wiggle_threshold: f32,
+    pub wiggle_range: f32,
+    pub safe_distance: f32,
+    pub optimal_range: f32,
+}
+
+impl Default for DivaConfig {
+    fn default() -> Self {
+        Self {
+            move_speed: 100.0,
+            wiggle_threshold: 50.0,
+            wiggle_range: 150.0,
+            safe_distance: 80.0,
+            optimal_range: 100.0,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct HealerConfig {
+    pub move_speed: f32,
+    /// Player allergy percentage (0-100) that sends the healer over
+    pub heal_threshold: f32,
+    pub heal_rate: f32,
+    pub heal_range: f32,
+    /// Allergy buildup right next to a flower head, before sensitivity
+    pub proximity_multiplier: f32,
+    /// Healers are more allergic than other bees
+    pub allergy_sensitivity: f32,
+}
+
+impl Default for HealerConfig {
+    fn default() -> Self {
+        Self {
+            move_speed: 120.0,
+            heal_threshold: 60.0,
+            heal_rate: 20.0,
+            heal_range: 40.0,
+            proximity_multiplier: 50.0,
+            allergy_sensitivity: 2.0,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct WinLoseConfig {
+    /// Pollen a single bee must carry to win
+    pub win_pollen: u32,
+    /// Sneezes allowed per bee before the round is lost. Co-op teams share
+    /// one budget of `max_sneezes` times the number of bees, so any bee can
+    /// spend it.
+    pub max_sneezes: u32,
+    /// Seconds before the round is lost, if the garden is timed
+    pub time_limit: Option<f32>,
+    /// When set the round can only be won, never lost
+    pub no_fail: bool,
+}
+
+impl Default for WinLoseConfig {
+    fn default() -> Self {
+        Self {
+            win_pollen: 20,
+            max_sneezes: 3,
+            time_limit: None,
+            no_fail: false,
+        }
+    }
+}
+
+/// Per-level replacements for `WinLoseConfig` values
+#[derive(Resource, Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct RuleOverrides {
+    pub win_pollen: Option<u32>,
+    /// Per-bee sneeze budget, like `WinLoseConfig::max_sneezes`
+    pub max_sneezes: Option<u32>,
+    pub time_limit: Option<f32>,
+}
+
+/// Multipliers a difficulty applies on top of the base config
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct DifficultyProfile {
+    pub allergy_buildup: f32,
+    pub sneeze_limit: f32,
+    pub rizz_decay: f32,
+    pub head_speed: f32,
+    pub no_fail: bool,
+}
+
+impl Default for DifficultyProfile {
+    fn default() -> Self {
+        Self {
+            allergy_buildup: 1.0,
+            sneeze_limit: 1.0,
+            rizz_decay: 1.0,
+            head_speed: 1.0,
+            no_fail: false,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct DifficultyConfig {
+    pub cozy: DifficultyProfile,
+    pub normal: DifficultyProfile,
+    pub hay_fever: DifficultyProfile,
+}
+
+impl Default for DifficultyConfig {
+    fn default() -> Self {
+        Self {
+            cozy: DifficultyProfile {
+                allergy_buildup: 0.6,
+                sneeze_limit: 2.0,
+                rizz_decay: 0.6,
+                head_speed: 0.75,
+                no_fail: true,
+            },
+            normal: DifficultyProfile::default(),
+            hay_fever: DifficultyProfile {
+                allergy_buildup: 1.4,
+                sneeze_limit: 0.67,
+                rizz_decay: 1.5,
+                head_speed: 1.3,
+                no_fail: false,
+            },
+        }
+    }
+}
+
+impl DifficultyConfig {
+    pub fn profile(&self, difficulty: Difficulty) -> &DifficultyProfile {
+        match difficulty {
+            Difficulty::Cozy => &self.cozy,
+            Difficulty::Normal => &self.normal,
+            Difficulty::HayFever => &self.hay_fever,
+        }
+    }
+}
+
+impl GameConfig {
+    /// The config with a level's win/lose rules applied
+    pub fn with_rules(&self, rules: &RuleOverrides) -> Self {
+        let mut config = self.clone();
+        if let Some(win_pollen) = rules.win_pollen {
+            config.win_lose.win_pollen = win_pollen;
+        }
+        if let Some(max_sneezes) = rules.max_sneezes {
+            config.win_lose.max_sneezes = max_sneezes;
+        }
+        if rules.time_limit.is_some() {
+            config.win_lose.time_limit = rules.time_limit;
+        }
+        config
+    }
+
+    /// The config the game actually plays with at the given difficulty
+    pub fn with_difficulty(&self, difficulty: Difficulty) -> Self {
+        let profile = self.difficulty.profile(difficulty);
+        let mut scaled = self.clone();
+
+        scaled.allergy.proximity_multiplier *= profile.allergy_buildup;
+        scaled.companion.healer.proximity_multiplier *= profile.allergy_buildup;
+        scaled.win_lose.max_sneezes =
+            ((self.win_lose.max_sneezes as f32 * profile.sneeze_limit).round() as u32).max(1);
+        scaled.win_lose.no_fail |= profile.no_fail;
+        scaled.rizz.decay_rate *= profile.rizz_decay;
+        scaled.movement.flower_head_speed_scale *= profile.head_speed;
+        scaled.rizz.pursuit_speed *= profile.head_speed;
+        scaled.rizz.attention_snap_speed *= profile.head_speed;
+
+        scaled
+    }
+
+    /// Parse a config from RON source and validate it
+    pub fn from_ron(source: &[u8]) -> Result<Self, ConfigError> {
+        let config: GameConfig = ron::de::from_bytes(source)?;
+        config.validate()?;
+        Ok(config)
+    }
+
+    /// Check that every value is in a range the game systems can cope with
+    pub fn validate(&self) -> Result<(), ConfigError> {
+        let mut problems = Vec::new();
+        let mut check = |ok: bool, message: &str| {
+            if !ok {
+                problems.push(message.to_string());
+            }
+        };
+
+        let allergy = &self.allergy;
+        check(allergy.max_value > 0.0, "allergy.max_value must be > 0");
+        check(
+            allergy.base_decay_rate >= 0.0,
+            "allergy.base_decay_rate must be >= 0",
+        );
+        check(
+            allergy.proximity_multiplier >= 0.0,
+            "allergy.proximity_multiplier must be >= 0",
+        );
+        check(
+            allergy.proximity_threshold > 0.0,
+            "allergy.proximity_threshold must be > 0",
+        );
+
+        let sneeze = &self.sneeze;
+        check(
+            sneeze.threshold > 0.0 && sneeze.threshold <= allergy.max_value,
+            "sneeze.threshold must be > 0 and <= allergy.max_value",
+        );
+        check(
+            (0.0..=1.0).contains(&sneeze.drop_percent),
+            "sneeze.drop_percent must be between 0.0 and 1.0",
+        );
+        check(
+            sneeze.post_sneeze_value >= 0.0 && sneeze.post_sneeze_value < sneeze.threshold,
+            "sneeze.post_sneeze_value must be >= 0 and < sneeze.threshold",
+        );
+
+        check(
+            sneeze.stagger_duration >= 0.0,
+            "sneeze.stagger_duration must be >= 0",
+        );
+
+        let pollen = &self.pollen;
+        check(
+            pollen.collection_radius > 0.0 && pollen.cache_collection_radius > 0.0,
+            "pollen collection radii must be > 0",
+        );
+
+        check(
+            self.movement.bee_speed > 0.0,
+            "movement.bee_speed must be > 0",
+        );
+        check(
+            self.movement.bee_acceleration > 0.0,
+            "movement.bee_acceleration must be > 0",
+        );
+        check(
+            self.movement.bee_friction > 0.0,
+            "movement.bee_friction must be > 0",
+        );
+        check(
+            self.movement.flower_head_speed_scale >= 0.0,
+            "movement.flower_head_speed_scale must be >= 0",
+        );
+        check(
+            self.movement.flower_head_speed.is_none(),
+            "movement.flower_head_speed was replaced by flower_head_speed_scale, \
+             a multiplier on each pattern's own speed (1.0 = as authored)",
+        );
+
+        let wiggle = &self.wiggle;
+        check(wiggle.duration > 0.0, "wiggle.duration must be > 0");
+        check(wiggle.cooldown >= 0.0, "wiggle.cooldown must be >= 0");
+        check(wiggle.range > 0.0, "wiggle.range must be > 0");
+
+        let rizz = &self.rizz;
+        check(rizz.max > 0.0, "rizz.max must be > 0");
+        check(rizz.decay_rate >= 0.0, "rizz.decay_rate must be >= 0");
+        check(
+            0.0 <= rizz.low_threshold
+                && rizz.low_threshold <= rizz.high_threshold
+                && rizz.high_threshold <= rizz.max,
+            "rizz thresholds must satisfy 0 <= low_threshold <= high_threshold <= max",
+        );
+
+        let healer = &self.companion.healer;
+        check(
+            (0.0..=100.0).contains(&healer.heal_threshold),
+            "companion.healer.heal_threshold must be between 0 and 100",
+        );
+        check(
+            healer.heal_range > 0.0,
+            "companion.healer.heal_range must be > 0",
+        );
+
+        check(
+            self.win_lose.win_pollen > 0,
+            "win_lose.win_pollen must be > 0",
+        );
+        check(
+            self.win_lose.max_sneezes > 0,
+            "win_lose.max_sneezes must be > 0",
+        );
+        check(
+            self.win_lose.time_limit.is_none_or(|limit| limit > 0.0),
+            "win_lose.time_limit must be > 0 when set",
+        );
+
+        for (name, profile) in [
+            ("cozy", &self.difficulty.cozy),
+            ("normal", &self.difficulty.normal),
+            ("hay_fever", &self.difficulty.hay_fever),
+        ] {
+            check(
+                profile.allergy_buildup >= 0.0
+                    && profile.sneeze_limit > 0.0
+                    && profile.rizz_decay >= 0.0
+                    && profile.head_speed >= 0.0,
+                &format!("difficulty.{name} multipliers must be >= 0 (sneeze_limit > 0)"),
+            );
+        }
+
+        if problems.is_empty() {
+            Ok(())
+        } else {
+            Err(ConfigError::Invalid(problems))
+        }
+    }
+}
+
+/// Reasons a config file can be rejected
+#[derive(Debug)]
+pub enum ConfigError {
+    Io(std::io::Error),
+    Parse(ron::error::SpannedError),
+    Invalid(Vec<String>),
+}
+
+impl fmt::Display for ConfigError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ConfigError::Io(err) => write!(f, "could not read config: {err}"),
+            ConfigError::Parse(err) => write!(f, "could not parse config: {err}"),
+            ConfigError::Invalid(problems) => {
+                write!(f, "invalid config: {}", problems.join("; "))
+            }
         }
     }
 }
+
+impl std::error::Error for ConfigError {}
+
+impl From<std::io::Error> for ConfigError {
+    fn from(err: std::io::Error) -> Self {
+        ConfigError::Io(err)
+    }
+}
+
+impl From<ron::error::SpannedError> for ConfigError {
+    fn from(err: ron::error::SpannedError) -> Self {
+        ConfigError::Parse(err)
+    }
+}
+
+/// Loads and validates `GameConfig` from `.ron` files
+#[derive(Default)]
+pub struct GameConfigLoader;
+
+impl AssetLoader for GameConfigLoader {
+    type Asset = GameConfig;
+    type Settings = ();
+    type Error = ConfigError;
+
+    async fn load(
+        &self,
+        reader: &mut dyn Reader,
+        _settings: &(),
+        _load_context: &mut LoadContext<'_>,
+    ) -> Result<Self::Asset, Self::Error> {
+        let mut bytes = Vec::new();
+        reader.read_to_end(&mut bytes).await?;
+        GameConfig::from_ron(&bytes)
+    }
+
+    fn extensions(&self) -> &[&str] {
+        &["ron"]
+    }
+}
+
+/// Keeps the config asset alive so it can be hot-reloaded
+#[derive(Resource)]
+pub struct GameConfigHandle(pub Handle<GameConfig>);
+
+/// Last config load error, shown on screen until a good file is loaded
+#[derive(Resource, Default)]
+pub struct ConfigStatus {
+    pub error: Option<String>,
+}
+
+/// Start loading the config file, unless a config was supplied up front
+/// (e.g. by the headless harness)
+pub fn load_game_config(
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+    existing: Option<Res<GameConfigHandle>>,
+) {
+    if existing.is_none() {
+        commands.insert_resource(GameConfigHandle(asset_server.load(GAME_CONFIG_PATH)));
+    }
+}
+
+/// Rebuild the `GameConfig` resource whenever the config asset (re)loads, the
+/// level's rules change or the difficulty changes. A file that fails to load or
+/// validate leaves the last good config in place.
+#[allow(clippy::too_many_arguments)]
+pub fn apply_game_config(
+    mut asset_events: EventReader<AssetEvent<GameConfig>>,
+    mut failed_events: EventReader<AssetLoadFailedEvent<GameConfig>>,
+    handle: Option<Res<GameConfigHandle>>,
+    assets: Res<Assets<GameConfig>>,
+    difficulty: Res<Difficulty>,
+    rules: Option<Res<RuleOverrides>>,
+    mut config: ResMut<GameConfig>,
+    mut status: ResMut<ConfigStatus>,
+) {
+    let mut rebuild = difficulty.is_changed() && !difficulty.is_added();
+    rebuild |= rules.as_ref().is_some_and(|rules| rules.is_changed());
+
+    if let Some(handle) = &handle {
+        for event in failed_events.read() {
+            if event.id != handle.0.id() {
+                continue;
+            }
+            let message = format!("{}: {}", event.path, event.error);
+            warn!("Keeping previous game config - {}", message);
+            status.error = Some(message);
+        }
+
+        for event in asset_events.read() {
+            if event.is_loaded_with_dependencies(&handle.0) || event.is_modified(&handle.0) {
+                status.error = None;
+                rebuild = true;
+                info!("Game config loaded from {}", GAME_CONFIG_PATH);
+            }
+        }
+    }
+
+    if !rebuild {
+        return;
+    }
+
+    *config = layered_config(handle.as_deref(), &assets, rules.as_deref(), *difficulty);
+}
+
+/// The config file with a level's rules and the difficulty layered on. The
+/// defaults stand in for a file that hasn't loaded.
+pub fn layered_config(
+    handle: Option<&GameConfigHandle>,
+    assets: &Assets<GameConfig>,
+    rules: Option<&RuleOverrides>,
+    difficulty: Difficulty,
+) -> GameConfig {
+    let base = handle
+        .and_then(|handle| assets.get(&handle.0).cloned())
+        .unwrap_or_default();
+    let rules = rules.cloned().unwrap_or_default();
+    base.with_rules(&rules).with_difficulty(difficulty)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn default_config_is_valid() {
+        assert!(GameConfig::default().validate().is_ok());
+    }
+
+    #[test]
+    fn shipped_config_matches_defaults() {
+        let shipped = GameConfig::from_ron(include_bytes!("../../assets/config/game.ron")).unwrap();
+        let defaults = GameConfig::default();
+        assert_eq!(shipped.win_lose.win_pollen, defaults.win_lose.win_pollen);
+        assert_eq!(shipped.wiggle.cooldown, defaults.wiggle.cooldown);
+        assert_eq!(shipped.rizz.pursuit_speed, defaults.rizz.pursuit_speed);
+    }
+
+    #[test]
+    fn partial_ron_falls_back_to_defaults() {
+        let config = GameConfig::from_ron(b"(sneeze: (threshold: 60.0))").unwrap();
+        assert_eq!(config.sneeze.threshold, 60.0);
+        assert_eq!(config.allergy.max_value, 100.0);
+    }
+
+    #[test]
+    fn old_absolute_head_speed_points_at_the_scale() {
+        let result = GameConfig::from_ron(b"(movement: (flower_head_speed: 50.0))");
+        let Err(ConfigError::Invalid(problems)) = result else {
+            panic!("expected validation error");
+        };
+        assert_eq!(problems.len(), 1);
+        assert!(problems[0].contains("flower_head_speed_scale"));
+    }
+
+    #[test]
+    fn misspelled_keys_are_rejected() {
+        let result = GameConfig::from_ron(b"(allergy: (proximty_multiplier: 50.0))");
+        let Err(ConfigError::Parse(err)) = result else {
+            panic!("expected parse error");
+        };
+        assert!(err.to_string().contains("proximty_multiplier"));
+    }
+
+    #[test]
+    fn invalid_values_are_rejected() {
+        let result = GameConfig::from_ron(b"(sneeze: (threshold: 150.0, drop_percent: 2.0))");
+        let Err(ConfigError::Invalid(problems)) = result else {
+            panic!("expected validation error");
+        };
+        assert_eq!(problems.len(), 2);
+    }
+
+    #[test]
+    fn difficulty_scales_config() {
+        let base = GameConfig::default();
+
+        let normal = base.with_difficulty(Difficulty::Normal);
+        assert_eq!(normal.win_lose.max_sneezes, base.win_lose.max_sneezes);
+        assert_eq!(normal.rizz.decay_rate, base.rizz.decay_rate);
+
+        let cozy = base.with_difficulty(Difficulty::Cozy);
+        assert!(cozy.win_lose.no_fail);
+        assert!(cozy.allergy.proximity_multiplier < base.allergy.proximity_multiplier);
+
+        let hay_fever = base.with_difficulty(Difficulty::HayFever);
+        assert_eq!(hay_fever.win_lose.max_sneezes, 2);
+        assert!(hay_fever.movement.flower_head_speed_scale > base.movement.flower_head_speed_scale);
+    }
+
+    #[test]
+    fn malformed_ron_is_a_parse_error() {
+        let result = GameConfig::from_ron(b"(sneeze: (threshold: ");
+        assert!(matches!(result, Err(ConfigError::Parse(_))));
+    }
+}
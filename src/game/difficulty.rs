@@ -0,0 +1,63 @@
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use super::GameState;
+use crate::controls::{Action, ActionState};
+
+/// Named difficulty the player picks before a round
+#[derive(
+    Resource,
+    Debug,
+    Clone,
+    Copy,
+    PartialEq,
+    Eq,
+    PartialOrd,
+    Ord,
+    Hash,
+    Default,
+    Serialize,
+    Deserialize,
+)]
+pub enum Difficulty {
+    /// No-fail mode: sneezes still drop pollen but the round can't be lost
+    Cozy,
+    #[default]
+    Normal,
+    HayFever,
+}
+
+impl Difficulty {
+    pub const ALL: [Difficulty; 3] = [Difficulty::Cozy, Difficulty::Normal, Difficulty::HayFever];
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            Difficulty::Cozy => "Cozy",
+            Difficulty::Normal => "Normal",
+            Difficulty::HayFever => "Hay Fever",
+        }
+    }
+}
+
+/// The difficulty actions (number keys 1-3 by default) pick a difficulty
+/// between rounds and in menus
+pub fn handle_difficulty_keys(
+    actions: Res<ActionState>,
+    current_state: Option<Res<State<GameState>>>,
+    mut difficulty: ResMut<Difficulty>,
+) {
+    if current_state.is_some_and(|state| *state.get() == GameState::Playing) {
+        return;
+    }
+
+    let picks = [
+        Action::DifficultyCozy,
+        Action::DifficultyNormal,
+        Action::DifficultyHayFever,
+    ];
+    for (action, choice) in picks.into_iter().zip(Difficulty::ALL) {
+        if actions.just_pressed(action) && *difficulty != choice {
+            *difficulty = choice;
+        }
+    }
+}
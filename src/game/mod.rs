@@ -1,13 +1,25 @@
 mod conditions;
 mod config;
+mod difficulty;
+mod pause;
+mod play_area;
 mod reset;
+mod rng;
+mod simulation;
 mod state;
+mod team;
 mod timer;
 
 pub use conditions::*;
 pub use config::*;
+pub use difficulty::*;
+pub use pause::*;
+pub use play_area::*;
 pub use reset::*;
+pub use rng::*;
+pub use simulation::*;
 pub use state::*;
+pub use team::*;
 pub use timer::*;
 
 use bevy::prelude::*;
@@ -16,24 +28,48 @@ pub struct GamePlugin;
 
 impl Plugin for GamePlugin {
     fn build(&self, app: &mut App) {
-        app.init_state::<GameState>()
+        app.init_state::<AppState>()
+            .add_sub_state::<GameState>()
+            .add_sub_state::<PauseState>()
+            .enable_state_scoped_entities::<AppState>()
+            .enable_state_scoped_entities::<GameState>()
+            .enable_state_scoped_entities::<PauseState>()
             .init_resource::<GameConfig>()
+            .init_resource::<ConfigStatus>()
+            .init_resource::<Difficulty>()
+            .init_resource::<GameRng>()
+            .init_resource::<Team>()
+            .init_asset::<GameConfig>()
+            .init_asset_loader::<GameConfigLoader>()
             .init_resource::<SessionTimer>()
-            .add_systems(Startup, setup_timer_ui)
+            .add_systems(Startup, load_game_config)
             .add_systems(
                 Update,
                 (
-                    check_win_condition,
-                    check_lose_condition,
-                    handle_restart_input,
-                    update_timer,
-                    update_timer_display,
-                    stop_timer_on_end,
+                    apply_game_config,
+                    finish_boot.run_if(in_state(AppState::Boot)),
+                    handle_difficulty_keys,
+                    toggle_pause.run_if(in_state(GameState::Playing)),
+                    (
+                        handle_restart_input,
+                        update_timer_display,
+                        stop_timer_on_end,
+                    )
+                        .run_if(in_state(AppState::InGame)),
                 ),
             )
+            .add_systems(OnEnter(AppState::InGame), setup_timer_ui)
             .add_systems(
-                OnEnter(GameState::Playing),
-                (on_enter_playing, reset_timer_on_play),
-            );
+                FixedUpdate,
+                (update_timer, check_win_condition, check_lose_condition)
+                    .chain()
+                    .run_if(in_state(GameState::Playing)),
+            )
+            .add_systems(OnEnter(GameState::Restarting), finish_restart)
+            .add_systems(OnEnter(PauseState::Paused), freeze_time)
+            .add_systems(OnExit(PauseState::Paused), unfreeze_time)
+            .add_systems(OnExit(AppState::InGame), despawn_round_entities);
+
+        build_simulation(app);
     }
 }
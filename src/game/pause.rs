@@ -0,0 +1,45 @@
+use bevy::prelude::*;
+
+use super::GameState;
+use crate::controls::{Action, ActionState};
+
+/// Whether a round in progress is running or paused. Only exists while
+/// `GameState::Playing`, so leaving a round always unpauses.
+#[derive(SubStates, Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+#[source(GameState = GameState::Playing)]
+pub enum PauseState {
+    #[default]
+    Running,
+    Paused,
+}
+
+/// The pause action (Escape or Start by default) pauses and resumes
+pub fn toggle_pause(
+    actions: Res<ActionState>,
+    state: Res<State<PauseState>>,
+    mut next_state: ResMut<NextState<PauseState>>,
+) {
+    if actions.just_pressed(Action::Pause) {
+        next_state.set(match state.get() {
+            PauseState::Running => PauseState::Paused,
+            PauseState::Paused => PauseState::Running,
+        });
+    }
+}
+
+/// Stop virtual time, which freezes `FixedUpdate` (all gameplay and the
+/// session timer) as well as frame-based effects
+pub fn freeze_time(mut time: ResMut<Time<Virtual>>) {
+    time.pause();
+}
+
+pub fn unfreeze_time(mut time: ResMut<Time<Virtual>>) {
+    time.unpause();
+}
+
+/// Start the current round over. Bevy skips `OnExit` and `OnEnter` for a
+/// transition to the state we're already in, so go by way of `Restarting`.
+/// Leaving `Playing` also unpauses.
+pub fn restart_round(next_state: &mut NextState<GameState>) {
+    next_state.set(GameState::Restarting);
+}
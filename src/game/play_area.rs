@@ -0,0 +1,29 @@
+use bevy::prelude::*;
+
+/// Size of the current garden, centred on the origin. Bees and flower heads
+/// can't leave it.
+#[derive(Resource, Debug, Clone, Copy, PartialEq)]
+pub struct PlayArea(pub Vec2);
+
+impl PlayArea {
+    /// The closest point to `position` inside the garden
+    pub fn clamp(&self, position: Vec2) -> Vec2 {
+        let half = self.0 / 2.0;
+        position.clamp(-half, half)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn points_outside_are_pulled_back_to_the_edge() {
+        let area = PlayArea(Vec2::new(700.0, 500.0));
+        assert_eq!(area.clamp(Vec2::new(10.0, -20.0)), Vec2::new(10.0, -20.0));
+        assert_eq!(
+            area.clamp(Vec2::new(900.0, -300.0)),
+            Vec2::new(350.0, -250.0)
+        );
+    }
+}
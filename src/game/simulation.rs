@@ -0,0 +1,133 @@
+use bevy::prelude::*;
+use bevy::transform::systems::{propagate_transforms, sync_simple_transforms};
+
+use super::GameState;
+
+/// Gameplay ticks per second. Every gameplay system runs in `FixedUpdate` at
+/// this rate so the outcome of a round doesn't depend on the frame rate.
+pub const SIMULATION_HZ: f64 = 60.0;
+
+/// Number of fixed ticks since the current garden was spawned. Replays key
+/// recorded input by this count.
+#[derive(Resource, Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct SimulationTick(pub u32);
+
+/// Simulated translation at the last two fixed ticks. Rendering blends between
+/// them so movement stays smooth when the frame rate and tick rate differ.
+#[derive(Component, Debug, Clone, Default)]
+pub struct InterpolatedTransform {
+    pub previous: Vec3,
+    pub current: Vec3,
+    /// Translation last written for rendering, used to spot teleports
+    rendered: Vec3,
+}
+
+impl InterpolatedTransform {
+    fn snap_to(&mut self, translation: Vec3) {
+        self.previous = translation;
+        self.current = translation;
+        self.rendered = translation;
+    }
+}
+
+/// Start interpolation from wherever a newly spawned entity was placed
+pub fn init_interpolated_transforms(
+    mut query: Query<(&Transform, &mut InterpolatedTransform), Added<InterpolatedTransform>>,
+) {
+    for (transform, mut interpolated) in &mut query {
+        interpolated.snap_to(transform.translation);
+    }
+}
+
+/// Put the simulated translation back before a tick runs. Anything that moved
+/// the entity outside the simulation (e.g. a round reset) is kept as a teleport.
+pub fn restore_simulated_transforms(
+    mut query: Query<(&mut Transform, &mut InterpolatedTransform)>,
+) {
+    for (mut transform, mut interpolated) in &mut query {
+        if transform.translation != interpolated.rendered {
+            interpolated.snap_to(transform.translation);
+        } else {
+            transform.translation = interpolated.current;
+        }
+        interpolated.previous = interpolated.current;
+    }
+}
+
+/// Remember where the tick left each entity
+pub fn record_simulated_transforms(mut query: Query<(&Transform, &mut InterpolatedTransform)>) {
+    for (transform, mut interpolated) in &mut query {
+        interpolated.current = transform.translation;
+    }
+}
+
+pub fn advance_simulation_tick(mut tick: ResMut<SimulationTick>) {
+    tick.0 += 1;
+}
+
+/// Draw entities part-way between the last two ticks
+pub fn interpolate_transforms(
+    mut query: Query<(&mut Transform, &mut InterpolatedTransform)>,
+    fixed_time: Res<Time<Fixed>>,
+) {
+    let alpha = fixed_time.overstep_fraction();
+
+    for (mut transform, mut interpolated) in &mut query {
+        // A teleport on a frame with no tick still has to show up
+        if transform.translation != interpolated.rendered {
+            interpolated.snap_to(transform.translation);
+        }
+        let translation = interpolated.previous.lerp(interpolated.current, alpha);
+        transform.translation = translation;
+        interpolated.rendered = translation;
+    }
+}
+
+pub(super) fn build_simulation(app: &mut App) {
+    app.insert_resource(Time::<Fixed>::from_hz(SIMULATION_HZ))
+        .init_resource::<SimulationTick>()
+        .add_systems(
+            FixedFirst,
+            (
+                init_interpolated_transforms,
+                restore_simulated_transforms,
+                // Gameplay reads `GlobalTransform`, so keep it in step with each tick
+                sync_simple_transforms,
+                propagate_transforms,
+            )
+                .chain(),
+        )
+        .add_systems(
+            FixedPostUpdate,
+            (sync_simple_transforms, propagate_transforms).chain(),
+        )
+        .add_systems(
+            FixedLast,
+            (
+                record_simulated_transforms,
+                advance_simulation_tick.run_if(in_state(GameState::Playing)),
+            ),
+        )
+        .add_systems(
+            RunFixedMainLoop,
+            (init_interpolated_transforms, interpolate_transforms)
+                .chain()
+                .in_set(RunFixedMainLoopSystem::AfterFixedMainLoop),
+        );
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn teleports_are_not_interpolated() {
+        let mut interpolated = InterpolatedTransform::default();
+        interpolated.snap_to(Vec3::new(10.0, 0.0, 1.0));
+        interpolated.previous = Vec3::ZERO;
+
+        interpolated.snap_to(Vec3::new(-200.0, 0.0, 1.0));
+        assert_eq!(interpolated.previous, interpolated.current);
+        assert_eq!(interpolated.rendered, Vec3::new(-200.0, 0.0, 1.0));
+    }
+}
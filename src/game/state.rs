@@ -1,9 +1,55 @@
 use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
 
+use super::{ConfigStatus, GameConfig, GameConfigHandle};
+
+/// Which screen the app is on
 #[derive(States, Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum AppState {
+    /// Waiting for the game config before showing anything
+    #[default]
+    Boot,
+    MainMenu,
+    /// Choosing a garden from the campaign
+    LevelSelect,
+    InGame,
+    Settings,
+    /// Rebinding the input actions, reached from settings
+    Controls,
+    /// Leaderboards of every garden
+    Records,
+    /// Playing in a garden run by a server
+    Online,
+}
+
+/// Progress of the round being played; only exists in `AppState::InGame`
+#[derive(SubStates, Debug, Clone, Copy, PartialEq, Eq, Hash, Default, Serialize, Deserialize)]
+#[source(AppState = AppState::InGame)]
 pub enum GameState {
     #[default]
     Playing,
     Won,
     Lost,
+    /// Passed through on the way back to `Playing`, so restarting a round in
+    /// progress runs its `OnExit` and `OnEnter` systems like any other
+    Restarting,
+}
+
+/// Go straight on to the new round
+pub fn finish_restart(mut next_state: ResMut<NextState<GameState>>) {
+    next_state.set(GameState::Playing);
+}
+
+/// Leave `Boot` once the config file has loaded, or failed to (the error
+/// banner explains and the defaults are used)
+pub fn finish_boot(
+    handle: Option<Res<GameConfigHandle>>,
+    assets: Res<Assets<GameConfig>>,
+    status: Res<ConfigStatus>,
+    mut next_state: ResMut<NextState<AppState>>,
+) {
+    let loaded = handle.is_some_and(|handle| assets.contains(&handle.0));
+    if loaded || status.error.is_some() {
+        next_state.set(AppState::MainMenu);
+    }
 }
@@ -0,0 +1,14 @@
+use bevy::prelude::*;
+
+/// How many player bees a round is played with. Set from the co-op roster
+/// when a garden is picked, or from the replay being watched.
+#[derive(Resource, Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Team {
+    pub players: usize,
+}
+
+impl Default for Team {
+    fn default() -> Self {
+        Self { players: 1 }
+    }
+}
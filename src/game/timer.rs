@@ -1,6 +1,6 @@
 use bevy::prelude::*;
 
-use super::GameState;
+use super::{AppState, GameConfig, GameState};
 
 /// Resource to track session time
 #[derive(Resource, Default)]
@@ -20,13 +20,18 @@ impl SessionTimer {
     }
 
     pub fn formatted(&self) -> String {
-        let minutes = (self.elapsed / 60.0) as u32;
-        let seconds = (self.elapsed % 60.0) as u32;
-        let millis = ((self.elapsed * 10.0) as u32) % 10;
-        format!("{:02}:{:02}.{}", minutes, seconds, millis)
+        format_time(self.elapsed)
     }
 }
 
+/// Format seconds as `MM:SS.t`
+pub fn format_time(elapsed: f32) -> String {
+    let minutes = (elapsed / 60.0) as u32;
+    let seconds = (elapsed % 60.0) as u32;
+    let millis = ((elapsed * 10.0) as u32) % 10;
+    format!("{:02}:{:02}.{}", minutes, seconds, millis)
+}
+
 /// Marker for timer display
 #[derive(Component)]
 pub struct TimerDisplay;
@@ -35,6 +40,7 @@ pub struct TimerDisplay;
 pub fn setup_timer_ui(mut commands: Commands) {
     commands.spawn((
         TimerDisplay,
+        StateScoped(AppState::InGame),
         Text::new("00:00.0"),
         TextFont {
             font_size: 20.0,
@@ -61,21 +67,20 @@ pub fn update_timer(
     }
 }
 
-/// Update timer display
+/// Update timer display, showing the limit for timed gardens
 pub fn update_timer_display(
     timer: Res<SessionTimer>,
+    config: Res<GameConfig>,
     mut displays: Query<&mut Text, With<TimerDisplay>>,
 ) {
     for mut text in &mut displays {
-        **text = timer.formatted();
+        **text = match config.win_lose.time_limit {
+            Some(limit) => format!("{} / {}", timer.formatted(), format_time(limit)),
+            None => timer.formatted(),
+        };
     }
 }
 
-/// Reset timer when entering playing state
-pub fn reset_timer_on_play(mut timer: ResMut<SessionTimer>) {
-    timer.reset();
-}
-
 /// Stop timer and log balance data when game ends
 pub fn stop_timer_on_end(mut timer: ResMut<SessionTimer>, state: Res<State<GameState>>) {
     if !timer.running {
@@ -122,6 +127,6 @@ pub fn stop_timer_on_end(mut timer: ResMut<SessionTimer>, state: Res<State<GameS
                 timer.elapsed
             );
         }
-        GameState::Playing => {}
+        GameState::Playing | GameState::Restarting => {}
     }
 }
@@ -0,0 +1,206 @@
+//! The game without a window, for integration tests and offline tools.
+//!
+//! `HeadlessGame` builds the gameplay plugins on top of `MinimalPlugins` and
+//! advances time by exactly one fixed tick per step, so a run depends only on
+//! the config, garden, seed and scripted input.
+
+use std::path::Path;
+use std::time::Duration;
+
+use bevy::asset::AssetPlugin;
+use bevy::input::InputPlugin;
+use bevy::prelude::*;
+use bevy::state::app::StatesPlugin;
+use bevy::time::TimeUpdateStrategy;
+
+use crate::ai::{AiPlugin, PlayerBee};
+use crate::bee::{Bee, BeePlugin, PlayerInput, PlayerInputs, PlayerSlot};
+use crate::controls::ControlsPlugin;
+use crate::flower::FlowerPlugin;
+use crate::game::{
+    AppState, Difficulty, GameConfig, GameConfigHandle, GamePlugin, GameRng, GameState,
+    SimulationTick, Team,
+};
+use crate::level::{
+    player_spawn_position, spawn_player_bee, start_round, LevelDefinition, LevelError,
+};
+use crate::net::MAX_ONLINE_PLAYERS;
+
+pub struct HeadlessGame {
+    app: App,
+    /// Garden of the current round
+    level: Option<LevelDefinition>,
+}
+
+impl HeadlessGame {
+    /// Build the gameplay plugins around `config`. The shipped config file is
+    /// not read, so tests are unaffected by tuning changes.
+    pub fn new(config: GameConfig) -> Self {
+        let mut app = App::new();
+        app.add_plugins((
+            MinimalPlugins,
+            StatesPlugin,
+            AssetPlugin {
+                watch_for_changes_override: Some(false),
+                ..default()
+            },
+            InputPlugin,
+        ))
+        // Skip the menus and go straight to a round
+        .insert_state(AppState::InGame)
+        .add_plugins((
+            ControlsPlugin,
+            GamePlugin,
+            BeePlugin,
+            FlowerPlugin,
+            AiPlugin,
+        ));
+
+        let handle = app
+            .world_mut()
+            .resource_mut::<Assets<GameConfig>>()
+            .add(config.clone());
+        app.insert_resource(GameConfigHandle(handle))
+            .insert_resource(config);
+
+        let timestep = app.world().resource::<Time<Fixed>>().timestep();
+        app.insert_resource(TimeUpdateStrategy::ManualDuration(timestep));
+
+        // Run startup and the initial state transition
+        app.update();
+        Self { app, level: None }
+    }
+
+    /// Pin the random seed; takes effect from the next `load_level`
+    pub fn set_seed(&mut self, seed: u64) {
+        self.app.insert_resource(GameRng::pinned(seed));
+    }
+
+    /// Switch difficulty for the next `load_level`
+    pub fn set_difficulty(&mut self, difficulty: Difficulty) {
+        *self.app.world_mut().resource_mut::<Difficulty>() = difficulty;
+    }
+
+    /// Play the next `load_level` with `players` co-op bees
+    pub fn set_players(&mut self, players: usize) {
+        self.app.insert_resource(Team { players });
+    }
+
+    /// Replace the current garden with `level` and start a new round
+    pub fn load_level(&mut self, level: &LevelDefinition) {
+        let world = self.app.world_mut();
+        start_round(world, level);
+        world
+            .resource_mut::<NextState<GameState>>()
+            .set(GameState::Playing);
+        self.level = Some(level.clone());
+    }
+
+    /// Add a bee for `slot` to the round in progress, e.g. when an online
+    /// player joins. Returns `None` before any garden is loaded.
+    pub fn spawn_player(&mut self, slot: usize) -> Option<Entity> {
+        let level = self.level.as_ref()?;
+        let position = player_spawn_position(level, slot, MAX_ONLINE_PLAYERS);
+        let world = self.app.world_mut();
+        let config = world.resource::<GameConfig>().clone();
+        let entity = spawn_player_bee(&mut world.commands(), position, &config, PlayerSlot(slot));
+        world.flush();
+        Some(entity)
+    }
+
+    /// Take `slot`'s bee out of the round
+    pub fn remove_player(&mut self, slot: usize) {
+        let world = self.app.world_mut();
+        let leaving: Vec<Entity> = world
+            .query_filtered::<(Entity, &PlayerSlot), With<PlayerBee>>()
+            .iter(world)
+            .filter(|(_, player)| player.0 == slot)
+            .map(|(entity, _)| entity)
+            .collect();
+        for entity in leaving {
+            world.entity_mut(entity).despawn_recursive();
+        }
+    }
+
+    /// Garden of the current round
+    pub fn level(&self) -> Option<&LevelDefinition> {
+        self.level.as_ref()
+    }
+
+    /// Load a `.level.ron` file from disk and start a round in it
+    pub fn load_level_file(&mut self, path: impl AsRef<Path>) -> Result<(), LevelError> {
+        let level = LevelDefinition::from_ron(&std::fs::read(path)?)?;
+        self.load_level(&level);
+        Ok(())
+    }
+
+    /// Advance `ticks` fixed ticks with no player input
+    pub fn step(&mut self, ticks: u32) {
+        // Let go of any held direction
+        *self.app.world_mut().resource_mut::<PlayerInputs>() = PlayerInputs::default();
+        for _ in 0..ticks {
+            self.app.update();
+        }
+    }
+
+    /// Advance one fixed tick with `input` applied to the first player
+    pub fn step_with_input(&mut self, input: PlayerInput) {
+        self.step_with_inputs(vec![input]);
+    }
+
+    /// Advance one fixed tick with each player's input applied, in player
+    /// order
+    pub fn step_with_inputs(&mut self, inputs: Vec<PlayerInput>) {
+        *self.app.world_mut().resource_mut::<PlayerInputs>() = PlayerInputs { players: inputs };
+        self.app.update();
+    }
+
+    /// Fixed ticks since the round started
+    pub fn tick(&self) -> u32 {
+        self.app.world().resource::<SimulationTick>().0
+    }
+
+    /// Simulated time per tick
+    pub fn timestep(&self) -> Duration {
+        self.app.world().resource::<Time<Fixed>>().timestep()
+    }
+
+    pub fn state(&self) -> GameState {
+        *self.app.world().resource::<State<GameState>>().get()
+    }
+
+    /// The first player's bee in the current round
+    pub fn player(&mut self) -> Entity {
+        self.players()[0]
+    }
+
+    /// Every player's bee in the current round, in player order
+    pub fn players(&mut self) -> Vec<Entity> {
+        let world = self.app.world_mut();
+        let mut players: Vec<(PlayerSlot, Entity)> = world
+            .query_filtered::<(&PlayerSlot, Entity), (With<Bee>, With<PlayerBee>)>()
+            .iter(world)
+            .map(|(slot, entity)| (*slot, entity))
+            .collect();
+        players.sort_by_key(|(slot, _)| slot.0);
+        players.into_iter().map(|(_, entity)| entity).collect()
+    }
+
+    pub fn world(&self) -> &World {
+        self.app.world()
+    }
+
+    pub fn world_mut(&mut self) -> &mut World {
+        self.app.world_mut()
+    }
+
+    pub fn app_mut(&mut self) -> &mut App {
+        &mut self.app
+    }
+}
+
+impl Default for HeadlessGame {
+    fn default() -> Self {
+        Self::new(GameConfig::default())
+    }
+}
@@ -0,0 +1,78 @@
+//! Options passed when the game is started.
+//!
+//! Native builds read `--name <value>` or `--name=<value>` from the command
+//! line; web builds read `?name=<value>` from the page URL.
+
+/// Value of the launch option `name`, if it was given
+pub fn option(name: &str) -> Option<String> {
+    platform::option(name)
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+mod platform {
+    pub fn option(name: &str) -> Option<String> {
+        find_option(std::env::args().skip(1), name)
+    }
+
+    pub(super) fn find_option(
+        mut args: impl Iterator<Item = String>,
+        name: &str,
+    ) -> Option<String> {
+        let flag = format!("--{name}");
+        while let Some(arg) = args.next() {
+            match arg.strip_prefix(&flag) {
+                Some("") => return args.next(),
+                Some(rest) => {
+                    if let Some(value) = rest.strip_prefix('=') {
+                        return Some(value.to_string());
+                    }
+                }
+                None => {}
+            }
+        }
+        None
+    }
+}
+
+#[cfg(target_arch = "wasm32")]
+mod platform {
+    pub fn option(name: &str) -> Option<String> {
+        let search = web_sys::window()?.location().search().ok()?;
+        let prefix = format!("{name}=");
+        search
+            .trim_start_matches('?')
+            .split('&')
+            .find_map(|pair| pair.strip_prefix(&prefix))
+            .map(str::to_string)
+    }
+}
+
+#[cfg(all(test, not(target_arch = "wasm32")))]
+mod tests {
+    use super::platform::find_option;
+
+    fn args(list: &[&str]) -> impl Iterator<Item = String> {
+        list.iter()
+            .map(|arg| arg.to_string())
+            .collect::<Vec<_>>()
+            .into_iter()
+    }
+
+    #[test]
+    fn reads_separate_and_inline_values() {
+        assert_eq!(
+            find_option(args(&["--seed", "42"]), "seed").as_deref(),
+            Some("42")
+        );
+        assert_eq!(
+            find_option(args(&["--seed=7"]), "seed").as_deref(),
+            Some("7")
+        );
+    }
+
+    #[test]
+    fn ignores_options_with_a_longer_name() {
+        assert_eq!(find_option(args(&["--seeds=1"]), "seed"), None);
+        assert_eq!(find_option(args(&["--replay", "run.ron"]), "seed"), None);
+    }
+}
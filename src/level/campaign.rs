@@ -0,0 +1,136 @@
+use std::collections::BTreeSet;
+
+use bevy::asset::io::Reader;
+use bevy::asset::{AssetLoader, LoadContext};
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use super::{ActiveLevel, LevelError};
+
+/// Path of the ordered garden list, relative to the assets directory
+pub const CAMPAIGN_PATH: &str = "levels/main.campaign.ron";
+
+/// Ordered list of gardens; winning one unlocks the next
+#[derive(Asset, TypePath, Debug, Clone, Serialize, Deserialize)]
+pub struct Campaign {
+    pub levels: Vec<CampaignEntry>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CampaignEntry {
+    pub id: String,
+    pub name: String,
+    pub path: String,
+}
+
+impl Campaign {
+    pub fn index_of(&self, id: &str) -> Option<usize> {
+        self.levels.iter().position(|entry| entry.id == id)
+    }
+
+    /// The garden after `id`, if there is one
+    pub fn next_after(&self, id: &str) -> Option<&CampaignEntry> {
+        self.levels.get(self.index_of(id)? + 1)
+    }
+
+    /// The first garden is always open; every other one needs its predecessor won
+    pub fn is_unlocked(&self, index: usize, progress: &CampaignProgress) -> bool {
+        index == 0
+            || self
+                .levels
+                .get(index - 1)
+                .is_some_and(|previous| progress.completed.contains(&previous.id))
+    }
+}
+
+/// Loads `Campaign` from `.campaign.ron` files
+#[derive(Default)]
+pub struct CampaignLoader;
+
+impl AssetLoader for CampaignLoader {
+    type Asset = Campaign;
+    type Settings = ();
+    type Error = LevelError;
+
+    async fn load(
+        &self,
+        reader: &mut dyn Reader,
+        _settings: &(),
+        _load_context: &mut LoadContext<'_>,
+    ) -> Result<Self::Asset, Self::Error> {
+        let mut bytes = Vec::new();
+        reader.read_to_end(&mut bytes).await?;
+        let campaign: Campaign = ron::de::from_bytes(&bytes)?;
+        if campaign.levels.is_empty() {
+            return Err(LevelError::Invalid(vec![
+                "a campaign needs at least one level".to_string(),
+            ]));
+        }
+        Ok(campaign)
+    }
+
+    fn extensions(&self) -> &[&str] {
+        &["campaign.ron"]
+    }
+}
+
+#[derive(Resource)]
+pub struct CampaignHandle(pub Handle<Campaign>);
+
+/// Gardens the player has won, saved between sessions
+#[derive(Resource, Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct CampaignProgress {
+    pub completed: BTreeSet<String>,
+}
+
+pub fn load_campaign(mut commands: Commands, asset_server: Res<AssetServer>) {
+    commands.insert_resource(CampaignHandle(asset_server.load(CAMPAIGN_PATH)));
+}
+
+/// Mark the active garden as won, unlocking the next one
+pub fn record_level_completed(
+    active: Option<Res<ActiveLevel>>,
+    mut progress: ResMut<CampaignProgress>,
+) {
+    let Some(active) = active else {
+        return;
+    };
+
+    if progress.completed.insert(active.id.clone()) {
+        info!("Garden '{}' completed", active.name);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn campaign() -> Campaign {
+        ron::de::from_bytes(include_bytes!("../../assets/levels/main.campaign.ron")).unwrap()
+    }
+
+    #[test]
+    fn winning_a_level_unlocks_the_next() {
+        let campaign = campaign();
+        let mut progress = CampaignProgress::default();
+        assert!(campaign.is_unlocked(0, &progress));
+        assert!(!campaign.is_unlocked(1, &progress));
+
+        progress.completed.insert(campaign.levels[0].id.clone());
+        assert!(campaign.is_unlocked(1, &progress));
+        assert!(!campaign.is_unlocked(2, &progress));
+    }
+
+    #[test]
+    fn next_after_walks_the_campaign_in_order() {
+        let campaign = campaign();
+        let first = &campaign.levels[0].id;
+        assert_eq!(
+            campaign.next_after(first).unwrap().id,
+            campaign.levels[1].id
+        );
+
+        let last = &campaign.levels.last().unwrap().id;
+        assert!(campaign.next_after(last).is_none());
+    }
+}
@@ -0,0 +1,287 @@
+use std::fmt;
+
+use bevy::asset::io::Reader;
+use bevy::asset::{AssetLoader, LoadContext};
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::flower::MovementPattern;
+use crate::game::RuleOverrides;
+
+/// A garden: everything `spawn_level` needs to build a round
+#[derive(Asset, TypePath, Debug, Clone, Serialize, Deserialize)]
+pub struct LevelDefinition {
+    pub id: String,
+    pub name: String,
+    #[serde(default = "default_play_area")]
+    pub play_area: Vec2,
+    pub player_spawn: Vec2,
+    #[serde(default)]
+    pub companions: Vec<CompanionSpawn>,
+    pub flowers: Vec<FlowerDef>,
+    #[serde(default)]
+    pub rules: RuleOverrides,
+}
+
+fn default_play_area() -> Vec2 {
+    Vec2::new(700.0, 500.0)
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum CompanionKind {
+    Diva,
+    Healer,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CompanionSpawn {
+    pub kind: CompanionKind,
+    pub position: Vec2,
+}
+
+/// A stem with its pollen caches and flower heads
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FlowerDef {
+    pub position: Vec2,
+    #[serde(default = "default_stem_height")]
+    pub stem_height: f32,
+    #[serde(default)]
+    pub caches: Vec<CacheDef>,
+    pub heads: Vec<HeadDef>,
+}
+
+fn default_stem_height() -> f32 {
+    200.0
+}
+
+/// How far above the top of its stem a flower's heads move around
+const HEAD_CLEARANCE: f32 = 20.0;
+
+impl FlowerDef {
+    /// Height above the flower's position that its heads move around. The
+    /// stem is centred on that position, so its top is half its height up.
+    pub fn head_height(&self) -> f32 {
+        self.stem_height / 2.0 + HEAD_CLEARANCE
+    }
+}
+
+/// A pollen cache, positioned relative to its stem
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CacheDef {
+    pub offset: Vec2,
+    /// Falls back to `PollenConfig::cache_value`
+    #[serde(default)]
+    pub value: Option<u32>,
+    #[serde(default = "default_respawn_time")]
+    pub respawn_time: f32,
+}
+
+fn default_respawn_time() -> f32 {
+    10.0
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HeadDef {
+    pub pattern: HeadPattern,
+    /// Where the pattern is centred, relative to the flower's head height
+    #[serde(default)]
+    pub offset: Vec2,
+    #[serde(default = "default_head_size")]
+    pub size: f32,
+    #[serde(default = "default_head_color")]
+    pub color: (f32, f32, f32),
+    #[serde(default = "default_pollen_interval")]
+    pub pollen_interval: f32,
+}
+
+fn default_head_size() -> f32 {
+    45.0
+}
+
+fn default_head_color() -> (f32, f32, f32) {
+    (1.0, 0.4, 0.6)
+}
+
+fn default_pollen_interval() -> f32 {
+    2.0
+}
+
+/// Serializable description of a `MovementPattern`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub enum HeadPattern {
+    Circular { radius: f32, speed: f32 },
+    Figure8 { width: f32, height: f32, speed: f32 },
+    Sway { amplitude: f32, speed: f32 },
+}
+
+impl HeadPattern {
+    pub fn to_movement_pattern(&self) -> MovementPattern {
+        match *self {
+            HeadPattern::Circular { radius, speed } => MovementPattern::circular(radius, speed),
+            HeadPattern::Figure8 {
+                width,
+                height,
+                speed,
+            } => MovementPattern::figure8(width, height, speed),
+            HeadPattern::Sway { amplitude, speed } => MovementPattern::sway(amplitude, speed),
+        }
+    }
+}
+
+impl LevelDefinition {
+    /// Parse a level from RON source and validate it
+    pub fn from_ron(source: &[u8]) -> Result<Self, LevelError> {
+        let level: LevelDefinition = ron::de::from_bytes(source)?;
+        level.validate()?;
+        Ok(level)
+    }
+
+    pub fn validate(&self) -> Result<(), LevelError> {
+        let mut problems = Vec::new();
+
+        if self.id.is_empty() {
+            problems.push("id must not be empty".to_string());
+        }
+        if self.play_area.x <= 0.0 || self.play_area.y <= 0.0 {
+            problems.push("play_area must be positive".to_string());
+        }
+        if self.flowers.is_empty() {
+            problems.push("a level needs at least one flower".to_string());
+        }
+        for (i, flower) in self.flowers.iter().enumerate() {
+            if flower.heads.is_empty() {
+                problems.push(format!("flowers[{i}] needs at least one head"));
+            }
+            if flower.caches.iter().any(|cache| cache.respawn_time < 0.0) {
+                problems.push(format!("flowers[{i}] has a negative cache respawn_time"));
+            }
+            if flower
+                .heads
+                .iter()
+                .any(|head| head.size <= 0.0 || head.pollen_interval <= 0.0)
+            {
+                problems.push(format!(
+                    "flowers[{i}] heads need a positive size and pollen_interval"
+                ));
+            }
+        }
+        if self.rules.win_pollen == Some(0)
+            || self.rules.max_sneezes == Some(0)
+            || self.rules.time_limit.is_some_and(|limit| limit <= 0.0)
+        {
+            problems.push("rules must be > 0 when set".to_string());
+        }
+
+        if problems.is_empty() {
+            Ok(())
+        } else {
+            Err(LevelError::Invalid(problems))
+        }
+    }
+}
+
+/// Reasons a level file can be rejected
+#[derive(Debug)]
+pub enum LevelError {
+    Io(std::io::Error),
+    Parse(ron::error::SpannedError),
+    Invalid(Vec<String>),
+}
+
+impl fmt::Display for LevelError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            LevelError::Io(err) => write!(f, "could not read level: {err}"),
+            LevelError::Parse(err) => write!(f, "could not parse level: {err}"),
+            LevelError::Invalid(problems) => write!(f, "invalid level: {}", problems.join("; ")),
+        }
+    }
+}
+
+impl std::error::Error for LevelError {}
+
+impl From<std::io::Error> for LevelError {
+    fn from(err: std::io::Error) -> Self {
+        LevelError::Io(err)
+    }
+}
+
+impl From<ron::error::SpannedError> for LevelError {
+    fn from(err: ron::error::SpannedError) -> Self {
+        LevelError::Parse(err)
+    }
+}
+
+/// Loads and validates `LevelDefinition` from `.level.ron` files
+#[derive(Default)]
+pub struct LevelLoader;
+
+impl AssetLoader for LevelLoader {
+    type Asset = LevelDefinition;
+    type Settings = ();
+    type Error = LevelError;
+
+    async fn load(
+        &self,
+        reader: &mut dyn Reader,
+        _settings: &(),
+        _load_context: &mut LoadContext<'_>,
+    ) -> Result<Self::Asset, Self::Error> {
+        let mut bytes = Vec::new();
+        reader.read_to_end(&mut bytes).await?;
+        LevelDefinition::from_ron(&bytes)
+    }
+
+    fn extensions(&self) -> &[&str] {
+        &["level.ron"]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn shipped_meadow_level_is_valid() {
+        let level =
+            LevelDefinition::from_ron(include_bytes!("../../assets/levels/meadow.level.ron"))
+                .unwrap();
+        assert_eq!(level.id, "meadow");
+        assert_eq!(level.flowers[0].caches.len(), 3);
+        assert_eq!(level.flowers[0].heads.len(), 3);
+    }
+
+    #[test]
+    fn heads_spread_out_around_their_stem() {
+        let level =
+            LevelDefinition::from_ron(include_bytes!("../../assets/levels/meadow.level.ron"))
+                .unwrap();
+        let offsets: Vec<Vec2> = level.flowers[0]
+            .heads
+            .iter()
+            .map(|head| head.offset)
+            .collect();
+        assert_eq!(
+            offsets,
+            [Vec2::ZERO, Vec2::new(-60.0, -20.0), Vec2::new(60.0, -20.0)]
+        );
+    }
+
+    #[test]
+    fn shipped_campaign_levels_are_valid() {
+        for source in [
+            include_bytes!("../../assets/levels/orchard.level.ron").as_slice(),
+            include_bytes!("../../assets/levels/thicket.level.ron").as_slice(),
+        ] {
+            LevelDefinition::from_ron(source).unwrap();
+        }
+    }
+
+    #[test]
+    fn level_without_flowers_is_rejected() {
+        let result = LevelDefinition::from_ron(
+            b"(id: \"empty\", name: \"Empty\", player_spawn: (0.0, 0.0), flowers: [])",
+        );
+        assert!(matches!(result, Err(LevelError::Invalid(_))));
+    }
+}
@@ -0,0 +1,189 @@
+mod campaign;
+mod definition;
+mod spawn;
+
+pub use campaign::*;
+pub use definition::*;
+pub use spawn::*;
+
+use bevy::asset::AssetLoadFailedEvent;
+use bevy::prelude::*;
+
+use crate::game::{
+    layered_config, AppState, Difficulty, GameConfig, GameConfigHandle, GameRng, GameState,
+    RoundEntity, SessionTimer, SimulationTick, Team,
+};
+
+/// Garden loaded at startup
+pub const DEFAULT_LEVEL_PATH: &str = "levels/meadow.level.ron";
+
+/// The level that is (or is about to be) spawned
+#[derive(Resource)]
+pub struct CurrentLevel {
+    pub handle: Handle<LevelDefinition>,
+    /// Set when the garden must be (re)spawned, cleared once it has been
+    pub pending: bool,
+}
+
+impl CurrentLevel {
+    pub fn new(handle: Handle<LevelDefinition>) -> Self {
+        Self {
+            handle,
+            pending: true,
+        }
+    }
+}
+
+/// Id and display name of the garden currently spawned
+#[derive(Resource, Debug, Clone)]
+pub struct ActiveLevel {
+    pub id: String,
+    pub name: String,
+}
+
+/// Request to replace the current garden with another level file
+#[derive(Event)]
+pub struct LoadLevelEvent {
+    pub path: String,
+}
+
+pub struct LevelPlugin;
+
+impl Plugin for LevelPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_asset::<LevelDefinition>()
+            .init_asset_loader::<LevelLoader>()
+            .init_asset::<Campaign>()
+            .init_asset_loader::<CampaignLoader>()
+            .init_resource::<CampaignProgress>()
+            .add_event::<LoadLevelEvent>()
+            .add_systems(Startup, (load_default_level, load_campaign))
+            // Before the state transition, so a garden picked from a menu is
+            // the one spawned when the round starts
+            .add_systems(PreUpdate, handle_load_level_events)
+            .add_systems(
+                Update,
+                (
+                    report_level_errors,
+                    watch_current_level,
+                    spawn_current_level.run_if(in_state(AppState::InGame)),
+                )
+                    .chain(),
+            )
+            .add_systems(
+                OnEnter(GameState::Playing),
+                (request_level_respawn, spawn_current_level).chain(),
+            )
+            .add_systems(OnEnter(GameState::Won), record_level_completed);
+    }
+}
+
+pub fn load_default_level(mut commands: Commands, asset_server: Res<AssetServer>) {
+    commands.insert_resource(CurrentLevel::new(asset_server.load(DEFAULT_LEVEL_PATH)));
+}
+
+pub fn handle_load_level_events(
+    mut commands: Commands,
+    mut events: EventReader<LoadLevelEvent>,
+    asset_server: Res<AssetServer>,
+) {
+    if let Some(event) = events.read().last() {
+        commands.insert_resource(CurrentLevel::new(asset_server.load(&event.path)));
+    }
+}
+
+pub fn report_level_errors(mut events: EventReader<AssetLoadFailedEvent<LevelDefinition>>) {
+    for event in events.read() {
+        error!("Failed to load level {}: {}", event.path, event.error);
+    }
+}
+
+/// Every round starts from a freshly spawned garden, so a restart plays out
+/// exactly like the first attempt (and like a replay of it)
+pub fn request_level_respawn(current: Option<ResMut<CurrentLevel>>) {
+    if let Some(mut current) = current {
+        current.pending = true;
+    }
+}
+
+/// Respawn the garden when its file finishes loading or is hot-reloaded
+pub fn watch_current_level(
+    mut asset_events: EventReader<AssetEvent<LevelDefinition>>,
+    current: Option<ResMut<CurrentLevel>>,
+) {
+    let Some(mut current) = current else {
+        return;
+    };
+
+    let reloaded = asset_events.read().fold(false, |reloaded, event| {
+        reloaded
+            || event.is_loaded_with_dependencies(&current.handle)
+            || event.is_modified(&current.handle)
+    });
+    if reloaded {
+        current.pending = true;
+    }
+}
+
+/// Start a round in the current garden once its definition is available
+pub fn spawn_current_level(world: &mut World) {
+    let Some(current) = world.get_resource::<CurrentLevel>() else {
+        return;
+    };
+    if !current.pending {
+        return;
+    }
+    let Some(level) = world
+        .resource::<Assets<LevelDefinition>>()
+        .get(&current.handle)
+        .cloned()
+    else {
+        return;
+    };
+    world.resource_mut::<CurrentLevel>().pending = false;
+
+    start_round(world, &level);
+}
+
+/// Start a round in `level`: despawn everything the last round left behind,
+/// spawn the level, and rewind the tick count, session timer and random
+/// streams. The game, the headless harness and the server all start rounds
+/// here.
+pub fn start_round(world: &mut World, level: &LevelDefinition) {
+    let existing: Vec<Entity> = world
+        .query_filtered::<Entity, With<RoundEntity>>()
+        .iter(world)
+        .collect();
+    for entity in existing {
+        world.entity_mut(entity).despawn_recursive();
+    }
+
+    // Apply the level's rules now rather than on the next Update, so the
+    // first tick already plays by them
+    let config = layered_config(
+        world.get_resource::<GameConfigHandle>(),
+        world.resource::<Assets<GameConfig>>(),
+        Some(&level.rules),
+        *world.resource::<Difficulty>(),
+    );
+    let players = world.resource::<Team>().players;
+    spawn_level(&mut world.commands(), level, &config, players);
+    world.insert_resource(level.rules.clone());
+    world.insert_resource(config);
+    world.insert_resource(ActiveLevel {
+        id: level.id.clone(),
+        name: level.name.clone(),
+    });
+    world.flush();
+
+    world.resource_mut::<SimulationTick>().0 = 0;
+    world.resource_mut::<SessionTimer>().reset();
+    let mut rng = world.resource_mut::<GameRng>();
+    rng.start_round();
+    info!(
+        "Spawned level '{}' ({}) with seed {}",
+        level.name,
+        level.id,
+        rng.seed()
+    );
+}
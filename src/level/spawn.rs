@@ -0,0 +1,201 @@
+use bevy::prelude::*;
+
+use super::{CompanionKind, LevelDefinition};
+use crate::ai::{AiDivaBundle, AiHealerBundle, PlayerBee};
+use crate::bee::{AllergyMeter, BeeBundle, MoveTarget, PlayerSlot, SneezeCount, Steering};
+use crate::flower::{CacheSpawnPoint, FlowerBundle, FlowerHead, FlowerHeadBundle};
+use crate::game::{GameConfig, PlayArea, RoundEntity};
+
+/// Gap between co-op bees lined up on the player spawn
+const PLAYER_SPACING: f32 = 40.0;
+
+/// Position of a companion, flower head or stem cache among the others of its
+/// kind in the level file, so a copy of the garden built elsewhere (e.g. by
+/// an online client) can be matched up with this one
+#[derive(Component, Debug, Clone, Copy, PartialEq, Eq)]
+pub struct LevelIndex(pub usize);
+
+/// Where bee `index` of `players` starts, stacked around the spawn point
+pub fn player_spawn_position(level: &LevelDefinition, index: usize, players: usize) -> Vec2 {
+    let offset = (index as f32 - (players.max(1) - 1) as f32 / 2.0) * PLAYER_SPACING;
+    level.player_spawn + Vec2::new(0.0, -offset)
+}
+
+/// Spawn the bee played from `slot` (a circle placeholder in the player's
+/// colour) at `position`
+pub fn spawn_player_bee(
+    commands: &mut Commands,
+    position: Vec2,
+    config: &GameConfig,
+    slot: PlayerSlot,
+) -> Entity {
+    commands
+        .spawn((
+            RoundEntity,
+            BeeBundle {
+                allergy_meter: AllergyMeter::new(config.allergy.max_value),
+                transform: Transform::from_translation(position.extend(1.0)),
+                ..default()
+            },
+            MoveTarget::default(),
+            Steering::default(),
+            SneezeCount::default(),
+            PlayerBee,
+            slot,
+            Sprite {
+                color: slot.color(),
+                custom_size: Some(Vec2::new(30.0, 30.0)),
+                ..default()
+            },
+        ))
+        .id()
+}
+
+/// Spawn everything a level describes, with a bee for each of `players`.
+/// Only root entities are tagged with `RoundEntity`; children go with their
+/// parent on despawn.
+pub fn spawn_level(
+    commands: &mut Commands,
+    level: &LevelDefinition,
+    config: &GameConfig,
+    players: usize,
+) {
+    commands.insert_resource(PlayArea(level.play_area));
+
+    // Play area background
+    commands.spawn((
+        RoundEntity,
+        Sprite {
+            color: Color::srgb(0.3, 0.5, 0.3),
+            custom_size: Some(level.play_area),
+            ..default()
+        },
+        Transform::from_xyz(0.0, 0.0, 0.0),
+    ));
+
+    for index in 0..players {
+        let position = player_spawn_position(level, index, players);
+        spawn_player_bee(commands, position, config, PlayerSlot(index));
+    }
+
+    for (index, companion) in level.companions.iter().enumerate() {
+        let transform = Transform::from_translation(companion.position.extend(1.0));
+        match companion.kind {
+            // AI Diva Companion (purple/pink color)
+            CompanionKind::Diva => {
+                commands.spawn((
+                    RoundEntity,
+                    LevelIndex(index),
+                    AiDivaBundle::default(),
+                    Sprite {
+                        color: Color::srgb(0.8, 0.4, 0.7),
+                        custom_size: Some(Vec2::new(28.0, 28.0)),
+                        ..default()
+                    },
+                    transform,
+                ));
+            }
+            // AI Healer Companion (green color)
+            CompanionKind::Healer => {
+                commands.spawn((
+                    RoundEntity,
+                    LevelIndex(index),
+                    AiHealerBundle::default(),
+                    Sprite {
+                        color: Color::srgb(0.3, 0.8, 0.4),
+                        custom_size: Some(Vec2::new(26.0, 26.0)),
+                        ..default()
+                    },
+                    transform,
+                ));
+            }
+        }
+    }
+
+    let mut head_index = 0;
+    let mut cache_index = 0;
+    for flower in &level.flowers {
+        // Flower stem (green rectangle)
+        let flower_entity = commands
+            .spawn((
+                RoundEntity,
+                FlowerBundle {
+                    transform: Transform::from_translation(flower.position.extend(1.0)),
+                    ..default()
+                },
+                Sprite {
+                    color: Color::srgb(0.2, 0.6, 0.2),
+                    custom_size: Some(Vec2::new(10.0, flower.stem_height)),
+                    ..default()
+                },
+            ))
+            .id();
+
+        // Stem caches (larger yellow circles with outline effect)
+        for cache in &flower.caches {
+            let pos = cache.offset.extend(1.5);
+
+            // Cache outline (slightly larger, darker)
+            commands
+                .spawn((
+                    Sprite {
+                        color: Color::srgb(0.7, 0.5, 0.0),
+                        custom_size: Some(Vec2::splat(26.0)),
+                        ..default()
+                    },
+                    Transform::from_translation(pos - Vec3::Z * 0.01),
+                ))
+                .set_parent(flower_entity);
+
+            // Cache fill (bright yellow)
+            commands
+                .spawn((
+                    LevelIndex(cache_index),
+                    CacheSpawnPoint {
+                        respawn_timer: Timer::from_seconds(cache.respawn_time, TimerMode::Once),
+                        is_active: true,
+                        value: cache.value.unwrap_or(config.pollen.cache_value),
+                    },
+                    Sprite {
+                        color: Color::srgb(1.0, 0.9, 0.2),
+                        custom_size: Some(Vec2::splat(22.0)),
+                        ..default()
+                    },
+                    Transform::from_translation(pos),
+                    Visibility::Visible,
+                ))
+                .set_parent(flower_entity);
+            cache_index += 1;
+        }
+
+        // Flower heads move along their pattern around the top of the stem
+        for head in &flower.heads {
+            let (r, g, b) = head.color;
+            let centre = Vec2::new(0.0, flower.head_height()) + head.offset;
+            commands
+                .spawn((
+                    LevelIndex(head_index),
+                    FlowerHeadBundle {
+                        head: FlowerHead {
+                            movement_pattern: head.pattern.to_movement_pattern(),
+                            pollen_drop_timer: Timer::from_seconds(
+                                head.pollen_interval,
+                                TimerMode::Repeating,
+                            ),
+                            centre,
+                            ..default()
+                        },
+                        transform: Transform::from_translation(centre.extend(2.0)),
+                        ..default()
+                    },
+                    Sprite {
+                        color: Color::srgb(r, g, b),
+                        custom_size: Some(Vec2::splat(head.size)),
+                        ..default()
+                    },
+                ))
+                .set_parent(flower_entity);
+            head_index += 1;
+        }
+    }
+}
@@ -1,15 +1,30 @@
+pub mod achievements;
 pub mod ai;
 pub mod bee;
+pub mod controls;
 pub mod effects;
 pub mod flower;
 pub mod game;
+pub mod headless;
+pub mod launch;
+pub mod level;
+pub mod net;
+pub mod replay;
+pub mod save;
+pub mod storage;
 pub mod ui;
 
 pub mod prelude {
+    pub use crate::achievements::*;
     pub use crate::ai::*;
     pub use crate::bee::*;
+    pub use crate::controls::*;
     pub use crate::effects::*;
     pub use crate::flower::*;
     pub use crate::game::*;
+    pub use crate::level::*;
+    pub use crate::net::*;
+    pub use crate::replay::*;
+    pub use crate::save::*;
     pub use crate::ui::*;
 }
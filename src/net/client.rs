@@ -0,0 +1,440 @@
+use std::time::Duration;
+
+use bevy::prelude::*;
+use rand::Rng;
+
+use super::{
+    blend, ClientMessage, Connection, LinkConditions, LinkSimulator, NetError, Prediction,
+    RoomState, ServerMessage, SnapshotBuffer, DEFAULT_PORT,
+};
+use crate::bee::{Bee, Emote, Emoting, PlayerInput, PlayerInputs, PlayerSlot};
+use crate::flower::{CacheSpawnPoint, FlowerHead};
+use crate::game::{GameConfig, GameRng, PlayArea, RoundEntity};
+use crate::launch;
+use crate::level::{spawn_level, LevelDefinition, LevelIndex};
+
+const POLLEN_SIZE: f32 = 10.0;
+
+/// Server the online mode connects to: the `server` launch option, or a
+/// garden server on this machine
+#[derive(Resource, Debug, Clone, PartialEq)]
+pub struct ServerUrl(pub String);
+
+impl Default for ServerUrl {
+    fn default() -> Self {
+        Self(launch::option("server").unwrap_or_else(|| format!("ws://127.0.0.1:{DEFAULT_PORT}")))
+    }
+}
+
+/// How the connection to the server is going
+#[derive(Debug, Clone, Default, PartialEq)]
+pub enum ConnectionStatus {
+    #[default]
+    Connecting,
+    /// Connected to a lobby, not in a room yet
+    Lobby { cap: usize },
+    /// Waiting in a lobby room for everyone to be ready
+    InRoom(RoomState),
+    /// Playing the bee in this slot
+    Playing { player: usize },
+    /// The connection failed or was closed, with the reason
+    Disconnected(String),
+}
+
+#[derive(Resource, Debug, Default)]
+pub struct OnlineSession {
+    pub status: ConnectionStatus,
+    /// Why the last lobby request was turned down
+    pub notice: Option<String>,
+}
+
+impl OnlineSession {
+    pub fn player(&self) -> Option<usize> {
+        match self.status {
+            ConnectionStatus::Playing { player } => Some(player),
+            _ => None,
+        }
+    }
+}
+
+/// Ask the server for something outside of play, e.g. to join a room
+#[derive(Event, Debug, Clone, PartialEq)]
+pub struct SendToServer(pub ClientMessage);
+
+/// A bee drawn where the server says it is
+#[derive(Component, Debug, Clone, Copy, PartialEq, Eq)]
+pub struct NetBee {
+    pub player: usize,
+}
+
+/// This client's own bee
+#[derive(Component)]
+pub struct LocalBee;
+
+/// A pollen grain the server reported
+#[derive(Component, Debug, Clone, Copy, PartialEq, Eq)]
+pub struct NetPollen {
+    pub id: u64,
+}
+
+/// Open the connection when entering online mode
+pub fn connect_to_server(world: &mut World) {
+    world.insert_resource(OnlineSession::default());
+    world.insert_resource(SnapshotBuffer::default());
+    world.insert_resource(Prediction::default());
+
+    let conditions = *world.get_resource_or_insert_with(LinkConditions::default);
+    let seed = world
+        .get_resource_mut::<GameRng>()
+        .map_or(0, |mut rng| rng.effects().gen());
+    world.insert_resource(LinkSimulator::new(conditions, seed));
+
+    let url = world
+        .get_resource_or_insert_with(ServerUrl::default)
+        .0
+        .clone();
+    match Connection::connect(&url) {
+        Ok(connection) => world.insert_non_send_resource(connection),
+        Err(err) => {
+            warn!("Could not connect to {url}: {err}");
+            world.resource_mut::<OnlineSession>().status =
+                ConnectionStatus::Disconnected(format!("Could not reach {url}: {err}"));
+        }
+    }
+}
+
+pub fn disconnect_from_server(world: &mut World) {
+    world.remove_non_send_resource::<Connection>();
+}
+
+/// Build the garden locally, without bees: those come from snapshots
+fn rebuild_garden(
+    commands: &mut Commands,
+    level: &LevelDefinition,
+    config: &GameConfig,
+    existing: &Query<Entity, With<RoundEntity>>,
+) {
+    for entity in existing {
+        commands.entity(entity).despawn_recursive();
+    }
+    spawn_level(commands, level, &config.with_rules(&level.rules), 0);
+}
+
+/// Hand the server whatever has made it across the link by `now`
+fn flush_outgoing(
+    connection: &mut Connection,
+    link: &mut LinkSimulator,
+    now: Duration,
+) -> Result<(), NetError> {
+    for message in link.take_outgoing(now) {
+        connection.send(&message)?;
+    }
+    Ok(())
+}
+
+/// Put requests for the server on the link; `receive_server_messages` sends
+/// them on
+pub fn send_requests(
+    mut requests: EventReader<SendToServer>,
+    mut link: ResMut<LinkSimulator>,
+    time: Res<Time<Real>>,
+) {
+    for SendToServer(message) in requests.read() {
+        link.send(message.clone(), time.elapsed());
+    }
+}
+
+#[allow(clippy::too_many_arguments)]
+pub fn receive_server_messages(
+    mut commands: Commands,
+    connection: Option<NonSendMut<Connection>>,
+    mut session: ResMut<OnlineSession>,
+    mut buffer: ResMut<SnapshotBuffer>,
+    mut link: ResMut<LinkSimulator>,
+    mut prediction: ResMut<Prediction>,
+    config: Res<GameConfig>,
+    time: Res<Time<Real>>,
+    existing: Query<Entity, With<RoundEntity>>,
+) {
+    let Some(mut connection) = connection else {
+        return;
+    };
+
+    let now = time.elapsed();
+    let mut received = Vec::new();
+    let result = flush_outgoing(&mut connection, &mut link, now)
+        .and_then(|()| connection.receive(&mut received));
+    for message in received {
+        link.receive(message, now);
+    }
+
+    for message in link.take_incoming(now) {
+        match message {
+            ServerMessage::Welcome { player, level } => {
+                session.status = ConnectionStatus::Playing { player };
+                buffer.clear();
+                prediction.reset();
+                prediction.set_play_area(PlayArea(level.play_area));
+                rebuild_garden(&mut commands, &level, &config, &existing);
+            }
+            ServerMessage::Round { level } => {
+                prediction.reset();
+                prediction.set_play_area(PlayArea(level.play_area));
+                rebuild_garden(&mut commands, &level, &config, &existing);
+            }
+            ServerMessage::Snapshot(snapshot) => buffer.push(snapshot),
+            ServerMessage::Rejected { reason } => {
+                session.status = ConnectionStatus::Disconnected(reason);
+            }
+            ServerMessage::Lobby { cap } => session.status = ConnectionStatus::Lobby { cap },
+            ServerMessage::Room(room) => {
+                session.status = ConnectionStatus::InRoom(room);
+                session.notice = None;
+            }
+            ServerMessage::Denied { reason } => session.notice = Some(reason),
+        }
+    }
+
+    if let Err(err) = result {
+        if !matches!(session.status, ConnectionStatus::Disconnected(_)) {
+            session.status = ConnectionStatus::Disconnected(err.to_string());
+        }
+    }
+    if matches!(session.status, ConnectionStatus::Disconnected(_)) {
+        commands.queue(disconnect_from_server);
+    }
+}
+
+/// Each fixed tick, move the predicted bee with this player's input and
+/// send the input to the server
+#[allow(clippy::too_many_arguments)]
+pub fn send_intents(
+    connection: Option<NonSendMut<Connection>>,
+    mut session: ResMut<OnlineSession>,
+    mut link: ResMut<LinkSimulator>,
+    mut prediction: ResMut<Prediction>,
+    mut inputs: ResMut<PlayerInputs>,
+    config: Res<GameConfig>,
+    time: Res<Time>,
+    real_time: Res<Time<Real>>,
+) {
+    let Some(mut connection) = connection else {
+        return;
+    };
+    if session.player().is_none() {
+        return;
+    }
+
+    // Everyone on this machine plays the one online bee
+    let input = inputs.player_mut(0);
+    let held = PlayerInput {
+        direction: input.direction,
+        ..default()
+    };
+    let input = std::mem::replace(input, held);
+
+    let intent = prediction.apply(&input, &config, time.delta());
+    let now = real_time.elapsed();
+    link.send(ClientMessage::Intent(intent), now);
+    if let Err(err) = flush_outgoing(&mut connection, &mut link, now) {
+        session.status = ConnectionStatus::Disconnected(err.to_string());
+    }
+}
+
+/// Correct the predicted bee against each new snapshot
+pub fn reconcile_prediction(
+    buffer: Res<SnapshotBuffer>,
+    session: Res<OnlineSession>,
+    mut prediction: ResMut<Prediction>,
+    config: Res<GameConfig>,
+    time: Res<Time<Fixed>>,
+) {
+    let (Some(player), Some(snapshot)) = (session.player(), buffer.latest()) else {
+        return;
+    };
+    if prediction
+        .reconciled()
+        .is_some_and(|tick| tick >= snapshot.tick)
+    {
+        return;
+    }
+    if let Some(state) = snapshot.bee(player) {
+        prediction.reconcile(snapshot.tick, state, &config, time.timestep());
+    }
+}
+
+pub fn smooth_corrections(mut prediction: ResMut<Prediction>, time: Res<Time<Real>>) {
+    prediction.smooth(time.delta_secs());
+}
+
+pub fn advance_playback(mut buffer: ResMut<SnapshotBuffer>, time: Res<Time<Real>>) {
+    buffer.advance(time.delta_secs_f64());
+}
+
+/// Put up or take down the bubble over `entity` to match what the server
+/// last had it saying
+fn show_emote(
+    commands: &mut Commands,
+    entity: Entity,
+    shown: Option<&Emoting>,
+    emote: Option<Emote>,
+) {
+    if shown.map(|emoting| emoting.emote) == emote {
+        return;
+    }
+    match emote {
+        Some(emote) => commands.entity(entity).insert(Emoting::new(emote)),
+        None => commands.entity(entity).remove::<Emoting>(),
+    };
+}
+
+/// Spawn, move and remove bees to match the snapshots around playback. This
+/// player's bee is drawn where it is predicted to be instead.
+pub fn apply_net_bees(
+    mut commands: Commands,
+    buffer: Res<SnapshotBuffer>,
+    session: Res<OnlineSession>,
+    prediction: Res<Prediction>,
+    time: Res<Time<Fixed>>,
+    mut bees: Query<(Entity, &NetBee, &mut Transform, Option<&Emoting>)>,
+) {
+    let Some((from, to, alpha)) = buffer.sample() else {
+        return;
+    };
+    let predicted = prediction.displayed_position(time.overstep_fraction());
+
+    for (entity, bee, mut transform, emoting) in &mut bees {
+        let Some(state) = to.bee(bee.player) else {
+            commands.entity(entity).despawn_recursive();
+            continue;
+        };
+        let position = match predicted {
+            Some(position) if session.player() == Some(bee.player) => position,
+            _ => blend(
+                from.bee(bee.player).map(|state| state.position),
+                state.position,
+                alpha,
+            ),
+        };
+        transform.translation = position.extend(transform.translation.z);
+        show_emote(&mut commands, entity, emoting, state.emote);
+    }
+
+    for state in &to.bees {
+        if bees.iter().any(|(_, bee, _, _)| bee.player == state.player) {
+            continue;
+        }
+        let slot = PlayerSlot(state.player);
+        let mut bee = commands.spawn((
+            RoundEntity,
+            NetBee {
+                player: state.player,
+            },
+            Sprite {
+                color: slot.color(),
+                custom_size: Some(Vec2::new(30.0, 30.0)),
+                ..default()
+            },
+            Transform::from_translation(state.position.extend(1.0)),
+        ));
+        // Lets input that looks for the player's bee (e.g. double-tap to
+        // wiggle) find it; online, this device only ever has one player
+        if session.player() == Some(state.player) {
+            bee.insert((LocalBee, Bee::default(), PlayerSlot(0)));
+        }
+    }
+}
+
+/// Move flower heads and companions, and show which caches are ready
+#[allow(clippy::type_complexity)]
+pub fn apply_net_garden(
+    mut commands: Commands,
+    buffer: Res<SnapshotBuffer>,
+    mut heads: Query<(&LevelIndex, &mut Transform, &mut FlowerHead)>,
+    mut companions: Query<
+        (Entity, &LevelIndex, &mut Transform, Option<&Emoting>),
+        (
+            Without<FlowerHead>,
+            Without<CacheSpawnPoint>,
+            Without<NetBee>,
+        ),
+    >,
+    mut caches: Query<(&LevelIndex, &mut CacheSpawnPoint, &mut Visibility)>,
+) {
+    let Some((from, to, alpha)) = buffer.sample() else {
+        return;
+    };
+
+    for (index, mut transform, mut head) in &mut heads {
+        let Some(state) = to.heads.iter().find(|head| head.index == index.0) else {
+            continue;
+        };
+        let previous = from
+            .heads
+            .iter()
+            .find(|head| head.index == index.0)
+            .map(|head| head.position);
+        let position = blend(previous, state.position, alpha);
+        transform.translation = position.extend(transform.translation.z);
+        head.rizz = state.rizz;
+    }
+
+    for (entity, index, mut transform, emoting) in &mut companions {
+        let Some(state) = to.companions.iter().find(|c| c.index == index.0) else {
+            continue;
+        };
+        let previous = from
+            .companions
+            .iter()
+            .find(|c| c.index == index.0)
+            .map(|c| c.position);
+        let position = blend(previous, state.position, alpha);
+        transform.translation = position.extend(transform.translation.z);
+        show_emote(&mut commands, entity, emoting, state.emote);
+    }
+
+    for (index, mut cache, mut visibility) in &mut caches {
+        let Some(active) = to.caches.get(index.0) else {
+            continue;
+        };
+        cache.is_active = *active;
+        *visibility = if *active {
+            Visibility::Visible
+        } else {
+            Visibility::Hidden
+        };
+    }
+}
+
+/// Keep a sprite for each pollen grain in the newest snapshot. Grains don't
+/// move, so they aren't blended.
+pub fn apply_net_pollen(
+    mut commands: Commands,
+    buffer: Res<SnapshotBuffer>,
+    pollen: Query<(Entity, &NetPollen)>,
+) {
+    let Some((_, to, _)) = buffer.sample() else {
+        return;
+    };
+
+    for (entity, grain) in &pollen {
+        if !to.pollen.iter().any(|state| state.id == grain.id) {
+            commands.entity(entity).despawn();
+        }
+    }
+    for state in &to.pollen {
+        if pollen.iter().any(|(_, grain)| grain.id == state.id) {
+            continue;
+        }
+        commands.spawn((
+            RoundEntity,
+            NetPollen { id: state.id },
+            Sprite {
+                color: Color::srgb(1.0, 0.85, 0.0),
+                custom_size: Some(Vec2::splat(POLLEN_SIZE)),
+                ..default()
+            },
+            Transform::from_translation(state.position.extend(0.5)),
+        ));
+    }
+}
@@ -0,0 +1,183 @@
+use std::collections::VecDeque;
+
+use bevy::prelude::*;
+
+use super::Snapshot;
+use crate::game::SIMULATION_HZ;
+
+/// How far behind the newest snapshot clients draw the garden, in ticks, so
+/// there is nearly always a later snapshot to blend toward
+pub const INTERPOLATION_DELAY: f64 = 6.0;
+
+/// Snapshots kept for blending; older ones are dropped
+const BUFFER_LENGTH: usize = 32;
+
+/// How far the playback clock may drift from where it should be before it
+/// jumps rather than catching up smoothly, in ticks
+const MAX_DRIFT: f64 = 30.0;
+
+/// Recent server snapshots and the tick the client is drawing
+#[derive(Resource, Debug, Default)]
+pub struct SnapshotBuffer {
+    snapshots: VecDeque<Snapshot>,
+    /// Server tick being drawn, between snapshots
+    playback: f64,
+}
+
+impl SnapshotBuffer {
+    /// Keep `snapshot` in tick order. Only a restarted server sends an older
+    /// tick than the newest one, so that starts the buffer over.
+    pub fn push(&mut self, snapshot: Snapshot) {
+        if self
+            .latest()
+            .is_some_and(|latest| snapshot.tick < latest.tick)
+        {
+            self.snapshots.clear();
+        }
+        if self.snapshots.is_empty() {
+            self.playback = snapshot.tick as f64 - INTERPOLATION_DELAY;
+        }
+        self.snapshots.push_back(snapshot);
+        while self.snapshots.len() > BUFFER_LENGTH {
+            self.snapshots.pop_front();
+        }
+    }
+
+    pub fn latest(&self) -> Option<&Snapshot> {
+        self.snapshots.back()
+    }
+
+    pub fn clear(&mut self) {
+        self.snapshots.clear();
+    }
+
+    /// Move the playback clock on by `seconds`, staying about
+    /// `INTERPOLATION_DELAY` behind the newest snapshot
+    pub fn advance(&mut self, seconds: f64) {
+        let Some(latest) = self.latest() else {
+            return;
+        };
+        let target = latest.tick as f64 - INTERPOLATION_DELAY;
+        self.playback += seconds * SIMULATION_HZ;
+
+        let drift = self.playback - target;
+        if drift.abs() > MAX_DRIFT {
+            self.playback = target;
+        } else {
+            // Ease toward the target so jitter in arrival times doesn't
+            // speed playback up and down
+            self.playback -= drift * 0.1;
+        }
+    }
+
+    pub fn playback(&self) -> f64 {
+        self.playback
+    }
+
+    /// The two snapshots either side of the playback tick and how far
+    /// between them it is. Before the first snapshot or after the last, both
+    /// are the same snapshot.
+    pub fn sample(&self) -> Option<(&Snapshot, &Snapshot, f32)> {
+        let first = self.snapshots.front()?;
+        if self.playback <= first.tick as f64 {
+            return Some((first, first, 0.0));
+        }
+
+        for (from, to) in self.snapshots.iter().zip(self.snapshots.iter().skip(1)) {
+            if self.playback < to.tick as f64 {
+                let span = (to.tick - from.tick).max(1) as f64;
+                let alpha = (self.playback - from.tick as f64) / span;
+                return Some((from, to, alpha as f32));
+            }
+        }
+
+        let last = self.snapshots.back()?;
+        Some((last, last, 0.0))
+    }
+}
+
+/// Blend a position between two snapshots, or take the newer one when the
+/// thing only exists there
+pub fn blend(from: Option<Vec2>, to: Vec2, alpha: f32) -> Vec2 {
+    from.map_or(to, |from| from.lerp(to, alpha))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::game::GameState;
+    use crate::net::BeeState;
+
+    fn snapshot(tick: u32, x: f32) -> Snapshot {
+        Snapshot {
+            tick,
+            state: GameState::Playing,
+            elapsed: 0.0,
+            bees: vec![BeeState {
+                player: 0,
+                role: default(),
+                position: Vec2::new(x, 0.0),
+                allergy: 0.0,
+                allergy_max: 100.0,
+                pollen: 0,
+                sneezes: 0,
+                wiggling: false,
+                sneezing: false,
+                emote: None,
+                ack: 0,
+                motion: default(),
+            }],
+            heads: Vec::new(),
+            companions: Vec::new(),
+            caches: Vec::new(),
+            pollen: Vec::new(),
+            harvested: 0,
+        }
+    }
+
+    #[test]
+    fn samples_between_the_snapshots_around_playback() {
+        let mut buffer = SnapshotBuffer::default();
+        buffer.push(snapshot(10, 0.0));
+        buffer.push(snapshot(14, 40.0));
+        buffer.push(snapshot(18, 80.0));
+        buffer.playback = 15.0;
+
+        let (from, to, alpha) = buffer.sample().unwrap();
+        assert_eq!((from.tick, to.tick), (14, 18));
+        assert!((alpha - 0.25).abs() < 1e-6);
+
+        let x = blend(Some(from.bees[0].position), to.bees[0].position, alpha).x;
+        assert!((x - 50.0).abs() < 1e-4);
+    }
+
+    #[test]
+    fn holds_the_newest_snapshot_when_playback_runs_past_it() {
+        let mut buffer = SnapshotBuffer::default();
+        buffer.push(snapshot(10, 0.0));
+        buffer.playback = 50.0;
+        let (from, to, _) = buffer.sample().unwrap();
+        assert_eq!((from.tick, to.tick), (10, 10));
+    }
+
+    #[test]
+    fn playback_trails_the_newest_snapshot() {
+        let mut buffer = SnapshotBuffer::default();
+        buffer.push(snapshot(100, 0.0));
+        assert_eq!(buffer.playback(), 100.0 - INTERPOLATION_DELAY);
+
+        // A long stall jumps straight back to the right delay
+        buffer.push(snapshot(400, 0.0));
+        buffer.advance(1.0 / SIMULATION_HZ);
+        assert_eq!(buffer.playback(), 400.0 - INTERPOLATION_DELAY);
+    }
+
+    #[test]
+    fn a_restarted_server_starts_the_buffer_over() {
+        let mut buffer = SnapshotBuffer::default();
+        buffer.push(snapshot(500, 0.0));
+        buffer.push(snapshot(3, 0.0));
+        assert_eq!(buffer.sample().unwrap().0.tick, 3);
+        assert_eq!(buffer.playback(), 3.0 - INTERPOLATION_DELAY);
+    }
+}
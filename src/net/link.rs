@@ -0,0 +1,202 @@
+use std::collections::VecDeque;
+use std::time::Duration;
+
+use bevy::prelude::*;
+use rand::Rng;
+use rand_chacha::rand_core::SeedableRng;
+use rand_chacha::ChaCha8Rng;
+
+use super::{ClientMessage, ServerMessage};
+use crate::launch;
+
+/// Shortest wait before a lost packet is sent again, like TCP's minimum
+/// retransmission timeout
+const MIN_RETRANSMIT: Duration = Duration::from_millis(200);
+
+/// Resends of one packet before the link gives up losing it
+const MAX_RETRANSMITS: u32 = 6;
+
+/// Network conditions to fake between this client and the server, for
+/// trying online play against a server on the same machine. The default is
+/// a perfect link.
+///
+/// Set with `--latency <ms>`, `--jitter <ms>` and `--loss <percent>`
+/// (native) or `?latency=<ms>&...` (web).
+#[derive(Resource, Debug, Clone, Copy, Default, PartialEq)]
+pub struct LinkConditions {
+    /// Added to every message, each way
+    pub latency: Duration,
+    /// Up to this much more, picked at random for each message
+    pub jitter: Duration,
+    /// Chance of losing each packet, from 0.0 to 1.0. WebSockets run over
+    /// TCP, so a lost packet is sent again and arrives late rather than not
+    /// at all.
+    pub loss: f32,
+}
+
+impl LinkConditions {
+    pub fn from_launch_options() -> Self {
+        let number = |name| {
+            launch::option(name)
+                .and_then(|value| value.parse::<f32>().ok())
+                .filter(|value| *value >= 0.0)
+        };
+        let millis =
+            |name| number(name).map_or(Duration::ZERO, |ms| Duration::from_secs_f32(ms / 1000.0));
+
+        Self {
+            latency: millis("latency"),
+            jitter: millis("jitter"),
+            loss: number("loss").map_or(0.0, |percent| (percent / 100.0).min(1.0)),
+        }
+    }
+
+    pub fn is_perfect(&self) -> bool {
+        *self == Self::default()
+    }
+}
+
+/// Holds messages back as if they crossed the network described by its
+/// `LinkConditions`. As on a real WebSocket every message arrives, in
+/// order: a lost one is resent, holding up those behind it.
+#[derive(Resource, Debug)]
+pub struct LinkSimulator {
+    conditions: LinkConditions,
+    rng: ChaCha8Rng,
+    /// Messages with the time they arrive, in arrival order
+    outgoing: VecDeque<(Duration, ClientMessage)>,
+    incoming: VecDeque<(Duration, ServerMessage)>,
+}
+
+impl LinkSimulator {
+    pub fn new(conditions: LinkConditions, seed: u64) -> Self {
+        Self {
+            conditions,
+            rng: ChaCha8Rng::seed_from_u64(seed),
+            outgoing: VecDeque::new(),
+            incoming: VecDeque::new(),
+        }
+    }
+
+    pub fn conditions(&self) -> LinkConditions {
+        self.conditions
+    }
+
+    /// Put a message for the server on the link at time `now`
+    pub fn send(&mut self, message: ClientMessage, now: Duration) {
+        let arrival = self.arrival(self.outgoing.back().map(|(at, _)| *at), now);
+        self.outgoing.push_back((arrival, message));
+    }
+
+    /// Put a message from the server on the link at time `now`
+    pub fn receive(&mut self, message: ServerMessage, now: Duration) {
+        let arrival = self.arrival(self.incoming.back().map(|(at, _)| *at), now);
+        self.incoming.push_back((arrival, message));
+    }
+
+    /// Messages for the server that have crossed the link by `now`
+    pub fn take_outgoing(&mut self, now: Duration) -> Vec<ClientMessage> {
+        take_arrived(&mut self.outgoing, now)
+    }
+
+    /// Messages from the server that have crossed the link by `now`
+    pub fn take_incoming(&mut self, now: Duration) -> Vec<ServerMessage> {
+        take_arrived(&mut self.incoming, now)
+    }
+
+    /// When a message sent at `now` arrives. Each time it is lost it waits
+    /// out a retransmission timeout, doubling as TCP's does, and it never
+    /// overtakes the message before it, which arrives at `previous`.
+    fn arrival(&mut self, previous: Option<Duration>, now: Duration) -> Duration {
+        let conditions = self.conditions;
+        let mut sent = now;
+        let mut timeout = (conditions.latency * 2).max(MIN_RETRANSMIT);
+        for _ in 0..MAX_RETRANSMITS {
+            if conditions.loss <= 0.0 || self.rng.gen::<f32>() >= conditions.loss {
+                break;
+            }
+            sent += timeout;
+            timeout *= 2;
+        }
+
+        let jitter = if conditions.jitter.is_zero() {
+            Duration::ZERO
+        } else {
+            self.rng.gen_range(Duration::ZERO..=conditions.jitter)
+        };
+        let arrival = sent + conditions.latency + jitter;
+        previous.map_or(arrival, |previous| arrival.max(previous))
+    }
+}
+
+impl Default for LinkSimulator {
+    fn default() -> Self {
+        Self::new(LinkConditions::default(), 0)
+    }
+}
+
+fn take_arrived<T>(queue: &mut VecDeque<(Duration, T)>, now: Duration) -> Vec<T> {
+    let arrived = queue.iter().take_while(|(at, _)| *at <= now).count();
+    queue.drain(..arrived).map(|(_, message)| message).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::net::Intent;
+
+    fn intent(sequence: u32) -> ClientMessage {
+        ClientMessage::Intent(Intent {
+            sequence,
+            ..default()
+        })
+    }
+
+    fn ms(millis: u64) -> Duration {
+        Duration::from_millis(millis)
+    }
+
+    #[test]
+    fn a_perfect_link_delivers_straight_away() {
+        let mut link = LinkSimulator::new(LinkConditions::default(), 1);
+        link.send(intent(1), ms(10));
+        assert_eq!(link.take_outgoing(ms(10)), vec![intent(1)]);
+    }
+
+    #[test]
+    fn messages_arrive_after_the_latency_in_order() {
+        let conditions = LinkConditions {
+            latency: ms(100),
+            jitter: ms(50),
+            ..default()
+        };
+        let mut link = LinkSimulator::new(conditions, 1);
+        for sequence in 0..20 {
+            link.send(intent(sequence), ms(sequence as u64));
+        }
+
+        assert!(link.take_outgoing(ms(99)).is_empty());
+        let mut arrived = link.take_outgoing(ms(120));
+        arrived.extend(link.take_outgoing(ms(200)));
+        assert_eq!(arrived, (0..20).map(intent).collect::<Vec<_>>());
+    }
+
+    #[test]
+    fn lost_messages_arrive_late_but_in_order() {
+        let conditions = LinkConditions {
+            loss: 0.5,
+            ..default()
+        };
+        let mut link = LinkSimulator::new(conditions, 7);
+        for sequence in 0..200 {
+            link.send(intent(sequence), Duration::ZERO);
+        }
+
+        // The first loss holds up everything behind it until it is resent
+        let on_time = link.take_outgoing(Duration::ZERO).len();
+        assert!(on_time < 20, "{on_time} of 200 arrived on time");
+        let mut arrived = (0..on_time as u32).map(intent).collect::<Vec<_>>();
+        arrived.extend(link.take_outgoing(Duration::from_secs(60)));
+        assert_eq!(arrived, (0..200).map(intent).collect::<Vec<_>>());
+    }
+}
@@ -0,0 +1,453 @@
+//! The lobby: players open a room or join one by its code, pick roles and
+//! ready up, and the room's garden starts once everyone is ready. Slots
+//! nobody took are played by AI companions.
+
+use std::net::{SocketAddr, ToSocketAddrs};
+use std::sync::atomic::AtomicBool;
+use std::time::Duration;
+
+use bevy::prelude::*;
+use rand::Rng;
+use rand_chacha::rand_core::SeedableRng;
+use rand_chacha::ChaCha8Rng;
+
+use super::{
+    run_paced, version_mismatch, ClientMessage, Doorway, Garden, Guest, NetError, RoomMember,
+    RoomState, ServerMessage, MAX_ONLINE_PLAYERS,
+};
+use crate::bee::Role;
+use crate::game::{GameConfig, SIMULATION_HZ};
+use crate::level::{CompanionKind, CompanionSpawn, LevelDefinition};
+
+/// Letters room codes are made of; no 0/O or 1/I to mix up when reading one
+/// out
+const CODE_ALPHABET: &[u8] = b"ABCDEFGHJKLMNPQRSTUVWXYZ23456789";
+
+pub const ROOM_CODE_LENGTH: usize = 4;
+
+/// Distance from the player spawn of companions the level has no place for
+const COMPANION_RING: f32 = 90.0;
+
+/// Tidy up a typed room code: codes are upper case and never have spaces
+pub fn normalize_room_code(code: &str) -> String {
+    code.chars()
+        .filter(|c| !c.is_whitespace())
+        .flat_map(char::to_uppercase)
+        .collect()
+}
+
+/// `level` with its companions replaced by one for each of the `cap` slots
+/// players didn't take. Players in the Diva and Healer roles do those
+/// companions' jobs, so roles no player picked are covered first, then
+/// divas and healers take turns. Companions stand where the level puts ones
+/// of their kind while there's room, then around the player spawn.
+pub fn fill_companions(level: &LevelDefinition, roles: &[Role], cap: usize) -> LevelDefinition {
+    let mut kinds: Vec<CompanionKind> = [(Role::Diva, CompanionKind::Diva)]
+        .into_iter()
+        .chain([(Role::Healer, CompanionKind::Healer)])
+        .filter(|(role, _)| !roles.contains(role))
+        .map(|(_, kind)| kind)
+        .collect();
+    let empty = cap.saturating_sub(roles.len());
+    let mut next = CompanionKind::Diva;
+    while kinds.len() < empty {
+        kinds.push(next);
+        next = match next {
+            CompanionKind::Diva => CompanionKind::Healer,
+            CompanionKind::Healer => CompanionKind::Diva,
+        };
+    }
+    kinds.truncate(empty);
+
+    let mut places: Vec<Option<&CompanionSpawn>> = level.companions.iter().map(Some).collect();
+    let mut ring = 0;
+    let companions = kinds
+        .into_iter()
+        .map(|kind| {
+            let place = places
+                .iter()
+                .position(|place| place.is_some_and(|spawn| spawn.kind == kind))
+                .or_else(|| places.iter().position(Option::is_some));
+            let position = match place {
+                Some(index) => places[index].take().map(|spawn| spawn.position),
+                None => None,
+            }
+            .unwrap_or_else(|| {
+                let angle = std::f32::consts::TAU * ring as f32 / MAX_ONLINE_PLAYERS as f32;
+                ring += 1;
+                level.player_spawn - Vec2::from_angle(angle) * COMPANION_RING
+            });
+            CompanionSpawn { kind, position }
+        })
+        .collect();
+
+    LevelDefinition {
+        companions,
+        ..level.clone()
+    }
+}
+
+/// A player in a room that hasn't started yet
+struct Member {
+    guest: Guest,
+    player: usize,
+    role: Role,
+    ready: bool,
+}
+
+struct Room {
+    code: String,
+    /// Until the round starts
+    members: Vec<Member>,
+    /// Once it has
+    garden: Option<Garden>,
+}
+
+impl Room {
+    fn state(&self, cap: usize, you: usize) -> RoomState {
+        RoomState {
+            code: self.code.clone(),
+            cap,
+            members: self
+                .members
+                .iter()
+                .map(|member| RoomMember {
+                    player: member.player,
+                    role: member.role,
+                    ready: member.ready,
+                })
+                .collect(),
+            you,
+        }
+    }
+
+    /// Tell every member who is in the room now
+    fn announce(&mut self, cap: usize) {
+        for index in 0..self.members.len() {
+            let state = self.state(cap, self.members[index].player);
+            self.members[index].guest.send(&ServerMessage::Room(state));
+        }
+    }
+
+    fn free_slot(&self) -> Option<usize> {
+        (0..MAX_ONLINE_PLAYERS).find(|slot| self.members.iter().all(|m| m.player != *slot))
+    }
+
+    fn everyone_ready(&self) -> bool {
+        !self.members.is_empty() && self.members.iter().all(|member| member.ready)
+    }
+
+    fn is_over(&self) -> bool {
+        match &self.garden {
+            Some(garden) => garden.players() == 0,
+            None => self.members.is_empty(),
+        }
+    }
+}
+
+/// Matches players up into rooms, each of which becomes its own garden
+pub struct LobbyServer {
+    doorway: Doorway,
+    config: GameConfig,
+    level: LevelDefinition,
+    /// Most players in a room
+    cap: usize,
+    rng: ChaCha8Rng,
+    /// Connections that haven't said hello yet
+    guests: Vec<Guest>,
+    /// Said hello but aren't in a room
+    waiting: Vec<Guest>,
+    rooms: Vec<Room>,
+}
+
+impl LobbyServer {
+    /// Listen on `address`; rooms play `level` with up to `cap` bees (at
+    /// most `MAX_ONLINE_PLAYERS`). Bind to port 0 to have the system pick a
+    /// free port (see `local_addr`).
+    pub fn bind(
+        address: impl ToSocketAddrs,
+        config: GameConfig,
+        level: LevelDefinition,
+        cap: usize,
+    ) -> Result<Self, NetError> {
+        let doorway = Doorway::bind(address)?;
+        let seed = getrandom::u64().unwrap_or_default();
+        Ok(Self {
+            doorway,
+            config,
+            level,
+            cap: cap.clamp(1, MAX_ONLINE_PLAYERS),
+            rng: ChaCha8Rng::seed_from_u64(seed),
+            guests: Vec::new(),
+            waiting: Vec::new(),
+            rooms: Vec::new(),
+        })
+    }
+
+    pub fn local_addr(&self) -> SocketAddr {
+        self.doorway.local_addr()
+    }
+
+    pub fn cap(&self) -> usize {
+        self.cap
+    }
+
+    /// Codes of the rooms open or playing
+    pub fn rooms(&self) -> Vec<&str> {
+        self.rooms.iter().map(|room| room.code.as_str()).collect()
+    }
+
+    /// Garden of the room with `code`, once its round has started
+    pub fn garden(&mut self, code: &str) -> Option<&mut Garden> {
+        self.rooms
+            .iter_mut()
+            .find(|room| room.code == code)
+            .and_then(|room| room.garden.as_mut())
+    }
+
+    /// Run in real time, one tick per fixed step, until `stop` is set
+    pub fn run(&mut self, stop: &AtomicBool) {
+        let timestep = Duration::from_secs_f64(1.0 / SIMULATION_HZ);
+        run_paced(timestep, stop, || self.update());
+    }
+
+    /// Take in new players and lobby requests, then run each garden in play
+    /// for a tick
+    pub fn update(&mut self) {
+        self.doorway.admit(&mut self.guests);
+        self.greet_guests();
+        self.read_waiting();
+        for index in 0..self.rooms.len() {
+            self.read_members(index);
+        }
+        for room in &mut self.rooms {
+            if let Some(garden) = &mut room.garden {
+                garden.update();
+            }
+        }
+        self.rooms.retain(|room| {
+            let over = room.is_over();
+            if over {
+                info!("Room {} closed", room.code);
+            }
+            !over
+        });
+    }
+
+    fn greet_guests(&mut self) {
+        let mut index = 0;
+        while index < self.guests.len() {
+            let mut messages = self.guests[index].receive().into_iter();
+            let hello = messages.find_map(|message| match message {
+                ClientMessage::Hello { version } => Some(version),
+                _ => None,
+            });
+            let Some(version) = hello else {
+                if self.guests[index].connected {
+                    index += 1;
+                } else {
+                    self.guests.swap_remove(index);
+                }
+                continue;
+            };
+
+            let mut guest = self.guests.swap_remove(index);
+            if let Some(reason) = version_mismatch(version) {
+                guest.reject(reason);
+                continue;
+            }
+            guest.send(&ServerMessage::Lobby { cap: self.cap });
+            // Requests sent straight after hello
+            guest.put_back(messages);
+            self.waiting.push(guest);
+        }
+    }
+
+    /// Put players who asked into rooms
+    fn read_waiting(&mut self) {
+        let mut index = 0;
+        while index < self.waiting.len() {
+            let mut seated = None;
+            let mut messages = self.waiting[index].receive().into_iter();
+            for message in messages.by_ref() {
+                seated = self.answer_waiting(index, message);
+                if seated.is_some() {
+                    break;
+                }
+            }
+            // Anything after joining is for the room
+            self.waiting[index].put_back(messages);
+
+            match seated {
+                Some(room) => {
+                    let guest = self.waiting.swap_remove(index);
+                    self.join(room, guest);
+                }
+                None if !self.waiting[index].connected => {
+                    self.waiting.swap_remove(index);
+                }
+                None => index += 1,
+            }
+        }
+    }
+
+    /// Act on `message` from waiting player `index`. Returns the room to put
+    /// them in, if any.
+    fn answer_waiting(&mut self, index: usize, message: ClientMessage) -> Option<usize> {
+        let denial = match message {
+            ClientMessage::CreateRoom => {
+                let code = self.new_code();
+                info!("Room {code} opened");
+                self.rooms.push(Room {
+                    code,
+                    members: Vec::new(),
+                    garden: None,
+                });
+                return Some(self.rooms.len() - 1);
+            }
+            ClientMessage::JoinRoom { code } => {
+                let code = normalize_room_code(&code);
+                match self.rooms.iter().position(|room| room.code == code) {
+                    None => format!("there's no room {code}"),
+                    Some(room) if self.rooms[room].garden.is_some() => {
+                        format!("room {code} has already started")
+                    }
+                    Some(room) if self.rooms[room].members.len() >= self.cap => {
+                        format!("room {code} is full")
+                    }
+                    Some(room) => return Some(room),
+                }
+            }
+            ClientMessage::ChooseRole { .. } | ClientMessage::SetReady { .. } => {
+                "join a room first".to_string()
+            }
+            ClientMessage::Hello { .. } | ClientMessage::Intent(_) => return None,
+        };
+        self.waiting[index].send(&ServerMessage::Denied { reason: denial });
+        None
+    }
+
+    fn join(&mut self, room: usize, guest: Guest) {
+        let cap = self.cap;
+        let room = &mut self.rooms[room];
+        let player = room.free_slot().expect("a room with room has a free slot");
+        room.members.push(Member {
+            guest,
+            player,
+            role: Role::Gatherer,
+            ready: false,
+        });
+        room.announce(cap);
+    }
+
+    /// Act on what the members of a room that hasn't started have sent, and
+    /// start it once everyone is ready
+    fn read_members(&mut self, room: usize) {
+        let cap = self.cap;
+        let room = &mut self.rooms[room];
+        if room.garden.is_some() {
+            return;
+        }
+
+        let mut changed = false;
+        for member in &mut room.members {
+            for message in member.guest.receive() {
+                match message {
+                    ClientMessage::ChooseRole { role } => member.role = role,
+                    ClientMessage::SetReady { ready } => member.ready = ready,
+                    ClientMessage::CreateRoom | ClientMessage::JoinRoom { .. } => {
+                        let reason = format!("already in room {}", room.code);
+                        member.guest.send(&ServerMessage::Denied { reason });
+                        continue;
+                    }
+                    ClientMessage::Hello { .. } | ClientMessage::Intent(_) => continue,
+                }
+                changed = true;
+            }
+        }
+        let before = room.members.len();
+        room.members.retain(|member| member.guest.connected);
+        if changed || room.members.len() != before {
+            room.announce(cap);
+        }
+
+        if room.everyone_ready() {
+            let roles: Vec<Role> = room.members.iter().map(|member| member.role).collect();
+            let level = fill_companions(&self.level, &roles, cap);
+            let mut garden = Garden::new(self.config.clone(), level);
+            for member in room.members.drain(..) {
+                garden.seat(member.guest, member.player, member.role);
+            }
+            info!("Room {} started", room.code);
+            room.garden = Some(garden);
+        }
+    }
+
+    /// A code no open room has
+    fn new_code(&mut self) -> String {
+        loop {
+            let code = random_code(&mut self.rng);
+            if self.rooms.iter().all(|room| room.code != code) {
+                return code;
+            }
+        }
+    }
+}
+
+fn random_code(rng: &mut impl Rng) -> String {
+    (0..ROOM_CODE_LENGTH)
+        .map(|_| char::from(CODE_ALPHABET[rng.gen_range(0..CODE_ALPHABET.len())]))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn meadow() -> LevelDefinition {
+        LevelDefinition::from_ron(include_bytes!("../../assets/levels/meadow.level.ron")).unwrap()
+    }
+
+    fn kinds(level: &LevelDefinition) -> Vec<CompanionKind> {
+        level.companions.iter().map(|c| c.kind).collect()
+    }
+
+    #[test]
+    fn room_codes_are_short_and_easy_to_read_out() {
+        let mut rng = ChaCha8Rng::seed_from_u64(3);
+        for _ in 0..100 {
+            let code = random_code(&mut rng);
+            assert_eq!(code.len(), ROOM_CODE_LENGTH);
+            assert!(code.bytes().all(|c| CODE_ALPHABET.contains(&c)));
+            assert!(!code.contains(['O', '0', 'I', '1']));
+        }
+        assert_eq!(normalize_room_code(" ab c7 "), "ABC7");
+    }
+
+    #[test]
+    fn companions_fill_the_slots_players_left_empty() {
+        let level = meadow();
+
+        // A lone gatherer gets the level's own diva and healer, where the
+        // level puts them, plus one more
+        let filled = fill_companions(&level, &[Role::Gatherer], 4);
+        assert_eq!(
+            kinds(&filled),
+            [
+                CompanionKind::Diva,
+                CompanionKind::Healer,
+                CompanionKind::Diva
+            ]
+        );
+        assert_eq!(filled.companions[0].position, level.companions[0].position);
+        assert_eq!(filled.companions[1].position, level.companions[1].position);
+
+        // A player healing leaves the companions to be divas
+        let filled = fill_companions(&level, &[Role::Healer, Role::Gatherer], 4);
+        assert_eq!(kinds(&filled), [CompanionKind::Diva, CompanionKind::Diva]);
+
+        // A full room has none
+        let roles = [Role::Gatherer; 4];
+        assert!(fill_companions(&level, &roles, 4).companions.is_empty());
+    }
+}
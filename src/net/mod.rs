@@ -0,0 +1,70 @@
+//! Online play: a garden server runs the simulation and clients send their
+//! player's intents over WebSocket, drawing the snapshots they get back.
+//! Each client predicts its own bee so it answers input straight away. A
+//! lobby server can sit in front, matching players up into rooms that each
+//! get their own garden.
+
+mod client;
+mod interpolation;
+mod link;
+#[cfg(not(target_arch = "wasm32"))]
+mod lobby;
+mod prediction;
+mod protocol;
+#[cfg(not(target_arch = "wasm32"))]
+mod server;
+mod transport;
+#[cfg(not(target_arch = "wasm32"))]
+mod world;
+
+pub use client::*;
+pub use interpolation::*;
+pub use link::*;
+#[cfg(not(target_arch = "wasm32"))]
+pub use lobby::*;
+pub use prediction::*;
+pub use protocol::*;
+#[cfg(not(target_arch = "wasm32"))]
+pub use server::*;
+pub use transport::*;
+#[cfg(not(target_arch = "wasm32"))]
+pub use world::*;
+
+use bevy::prelude::*;
+
+use crate::game::{despawn_round_entities, AppState};
+
+pub struct NetPlugin;
+
+impl Plugin for NetPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<ServerUrl>()
+            .init_resource::<OnlineSession>()
+            .init_resource::<SnapshotBuffer>()
+            .init_resource::<Prediction>()
+            .insert_resource(LinkConditions::from_launch_options())
+            .init_resource::<LinkSimulator>()
+            .add_event::<SendToServer>()
+            .add_systems(OnEnter(AppState::Online), connect_to_server)
+            .add_systems(FixedUpdate, send_intents.run_if(in_state(AppState::Online)))
+            .add_systems(
+                Update,
+                (
+                    send_requests,
+                    receive_server_messages,
+                    reconcile_prediction,
+                    advance_playback,
+                    smooth_corrections,
+                    apply_net_bees,
+                    apply_net_garden,
+                    apply_net_pollen,
+                )
+                    .chain()
+                    .run_if(in_state(AppState::Online)),
+            )
+            .add_systems(
+                OnExit(AppState::Online),
+                (disconnect_from_server, despawn_round_entities),
+            );
+    }
+}
@@ -0,0 +1,340 @@
+use std::collections::VecDeque;
+use std::time::Duration;
+
+use bevy::prelude::*;
+
+use super::{BeeMotion, BeeState, Intent, WiggleMotion};
+use crate::bee::{follow_destination, starts_wiggle, steer, wiggle_step, PlayerInput, Steering};
+use crate::game::{GameConfig, PlayArea};
+
+/// Inputs kept for replay, about two seconds' worth. A server that hasn't
+/// acknowledged any of them for that long is far behind anyway.
+const MAX_PENDING: usize = 120;
+
+/// Seconds for a correction to shrink by half
+const CORRECTION_HALF_LIFE: f32 = 0.08;
+
+/// Corrections bigger than this (e.g. the bee was put back at the start of
+/// a new round) are shown straight away rather than smoothed
+const SNAP_DISTANCE: f32 = 120.0;
+
+/// The client's copy of its own bee, stepped with the same movement rules
+/// the server's `FixedUpdate` systems use
+#[derive(Debug, Clone, PartialEq)]
+pub struct PredictedBee {
+    pub position: Vec2,
+    /// Not predicted: a sneeze comes from the server and lasts until it
+    /// says otherwise
+    pub sneezing: bool,
+    pub motion: BeeMotion,
+}
+
+impl PredictedBee {
+    pub fn from_state(state: &BeeState) -> Self {
+        Self {
+            position: state.position,
+            sneezing: state.sneezing,
+            motion: state.motion.clone(),
+        }
+    }
+
+    /// Advance one tick of `dt` with `input`, in the order the server's
+    /// `FixedUpdate` chain runs, then keep the bee inside `area`
+    pub fn step(
+        &mut self,
+        input: &PlayerInput,
+        config: &GameConfig,
+        area: Option<PlayArea>,
+        dt: Duration,
+    ) {
+        let seconds = dt.as_secs_f32();
+        let motion = &mut self.motion;
+
+        if let Some(destination) = input.move_target {
+            motion.destination = Some(destination);
+        }
+        if starts_wiggle(input, motion.wiggle.is_some(), motion.cooldown.is_zero()) {
+            motion.wiggle = Some(WiggleMotion {
+                elapsed: Duration::ZERO,
+                origin_x: self.position.x,
+            });
+        }
+
+        motion.cooldown = motion.cooldown.saturating_sub(dt);
+
+        if let Some(wiggle) = &mut motion.wiggle {
+            wiggle.elapsed += dt;
+            let (x, finished) = wiggle_step(wiggle.origin_x, wiggle.elapsed, &config.wiggle);
+            self.position.x = x;
+            if finished {
+                motion.wiggle = None;
+                motion.cooldown = Duration::from_secs_f32(config.wiggle.cooldown);
+            }
+        }
+
+        self.position = follow_destination(
+            self.position,
+            &mut motion.destination,
+            &config.movement,
+            seconds,
+        );
+
+        let mut steering = Steering {
+            direction: input.direction,
+            velocity: motion.velocity,
+        };
+        self.position += steer(
+            &mut steering,
+            &mut motion.destination,
+            self.sneezing,
+            &config.movement,
+            seconds,
+        );
+        motion.velocity = steering.velocity;
+
+        if self.sneezing {
+            motion.destination = None;
+        }
+        if let Some(area) = area {
+            self.position = area.clamp(self.position);
+        }
+    }
+}
+
+/// Client-side prediction of this player's bee. Each tick's input moves the
+/// predicted bee straight away; when a snapshot arrives the bee is put where
+/// the server had it and the inputs the server hadn't applied yet are
+/// replayed on top. The difference is drawn as an offset that fades out, so
+/// corrections don't make the bee jump.
+#[derive(Resource, Debug, Default)]
+pub struct Prediction {
+    bee: Option<PredictedBee>,
+    /// Position one tick earlier, for drawing between ticks
+    previous: Vec2,
+    /// Inputs sent but not yet acknowledged, oldest first
+    pending: VecDeque<Intent>,
+    /// Sequence of the last input sent
+    sequence: u32,
+    /// Tick of the newest snapshot reconciled against
+    reconciled: Option<u32>,
+    /// Offset still to fade out from earlier corrections
+    error: Vec2,
+    /// Edges of the garden being played
+    area: Option<PlayArea>,
+}
+
+impl Prediction {
+    pub fn bee(&self) -> Option<&PredictedBee> {
+        self.bee.as_ref()
+    }
+
+    /// Inputs the server hasn't acknowledged yet
+    pub fn pending(&self) -> usize {
+        self.pending.len()
+    }
+
+    pub fn error(&self) -> Vec2 {
+        self.error
+    }
+
+    pub fn reconciled(&self) -> Option<u32> {
+        self.reconciled
+    }
+
+    /// Keep the predicted bee inside `area`, as the server does
+    pub fn set_play_area(&mut self, area: PlayArea) {
+        self.area = Some(area);
+    }
+
+    /// Forget the bee, e.g. for a new round; the next snapshot places it.
+    /// Sequences carry on, since the server's acknowledgements do.
+    pub fn reset(&mut self) {
+        self.bee = None;
+        self.reconciled = None;
+        self.error = Vec2::ZERO;
+    }
+
+    /// Number this tick's `input`, keep it for replay and move the predicted
+    /// bee with it. Returns the intent to send to the server.
+    pub fn apply(&mut self, input: &PlayerInput, config: &GameConfig, dt: Duration) -> Intent {
+        self.sequence += 1;
+        let intent = Intent {
+            sequence: self.sequence,
+            ..Intent::from(input)
+        };
+
+        self.pending.push_back(intent.clone());
+        while self.pending.len() > MAX_PENDING {
+            self.pending.pop_front();
+        }
+        if let Some(bee) = &mut self.bee {
+            self.previous = bee.position;
+            bee.step(&PlayerInput::from(&intent), config, self.area, dt);
+        }
+        intent
+    }
+
+    /// Take the server's `state` of the bee as of snapshot `tick`, then
+    /// replay the inputs it hadn't applied yet
+    pub fn reconcile(&mut self, tick: u32, state: &BeeState, config: &GameConfig, dt: Duration) {
+        self.reconciled = Some(tick);
+        self.pending.retain(|intent| intent.sequence > state.ack);
+
+        let mut bee = PredictedBee::from_state(state);
+        let mut previous = bee.position;
+        for intent in &self.pending {
+            previous = bee.position;
+            bee.step(&PlayerInput::from(intent), config, self.area, dt);
+        }
+
+        // Keep drawing the bee where it was and fade the difference out
+        self.error = match &self.bee {
+            Some(old) => old.position + self.error - bee.position,
+            None => Vec2::ZERO,
+        };
+        if self.error.length() > SNAP_DISTANCE {
+            self.error = Vec2::ZERO;
+        }
+        self.previous = previous;
+        self.bee = Some(bee);
+    }
+
+    /// Fade out what's left of earlier corrections over `seconds`
+    pub fn smooth(&mut self, seconds: f32) {
+        self.error *= 0.5_f32.powf(seconds / CORRECTION_HALF_LIFE);
+        if self.error.length() < 0.01 {
+            self.error = Vec2::ZERO;
+        }
+    }
+
+    /// Where to draw the bee, `overstep` of the way from the last tick to the
+    /// next
+    pub fn displayed_position(&self, overstep: f32) -> Option<Vec2> {
+        let bee = self.bee.as_ref()?;
+        Some(self.previous.lerp(bee.position, overstep) + self.error)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const DT: Duration = Duration::from_nanos(16_666_667);
+
+    fn state(position: Vec2, ack: u32) -> BeeState {
+        BeeState {
+            player: 0,
+            role: default(),
+            position,
+            allergy: 0.0,
+            allergy_max: 100.0,
+            pollen: 0,
+            sneezes: 0,
+            wiggling: false,
+            sneezing: false,
+            emote: None,
+            ack,
+            motion: default(),
+        }
+    }
+
+    fn right() -> PlayerInput {
+        PlayerInput {
+            direction: Vec2::X,
+            ..default()
+        }
+    }
+
+    #[test]
+    fn replays_inputs_the_server_has_not_applied() {
+        let config = GameConfig::default();
+        let mut prediction = Prediction::default();
+        prediction.reconcile(0, &state(Vec2::ZERO, 0), &config, DT);
+        for _ in 0..10 {
+            prediction.apply(&right(), &config, DT);
+        }
+
+        // The server applied the first four inputs exactly as predicted
+        let mut server = PredictedBee::from_state(&state(Vec2::ZERO, 0));
+        for _ in 0..4 {
+            server.step(&right(), &config, None, DT);
+        }
+        let mut applied = state(server.position, 4);
+        applied.motion = server.motion.clone();
+        let predicted = prediction.bee().unwrap().position;
+
+        prediction.reconcile(4, &applied, &config, DT);
+        assert_eq!(prediction.pending(), 6);
+        assert!(prediction.bee().unwrap().position.distance(predicted) < 1e-3);
+        assert!(prediction.error().length() < 1e-3);
+    }
+
+    #[test]
+    fn corrections_fade_in_instead_of_jumping() {
+        let config = GameConfig::default();
+        let mut prediction = Prediction::default();
+        prediction.reconcile(0, &state(Vec2::ZERO, 0), &config, DT);
+
+        // The server had the bee somewhere else
+        prediction.reconcile(3, &state(Vec2::new(30.0, 0.0), 0), &config, DT);
+        assert_eq!(prediction.displayed_position(1.0), Some(Vec2::ZERO));
+
+        prediction.smooth(CORRECTION_HALF_LIFE);
+        let halfway = prediction.displayed_position(1.0).unwrap();
+        assert!((halfway.x - 15.0).abs() < 1e-3);
+
+        prediction.smooth(1.0);
+        assert_eq!(
+            prediction.displayed_position(1.0),
+            Some(Vec2::new(30.0, 0.0))
+        );
+    }
+
+    #[test]
+    fn big_corrections_snap() {
+        let config = GameConfig::default();
+        let mut prediction = Prediction::default();
+        prediction.reconcile(0, &state(Vec2::ZERO, 0), &config, DT);
+        prediction.reconcile(3, &state(Vec2::new(500.0, 0.0), 0), &config, DT);
+        assert_eq!(
+            prediction.displayed_position(1.0),
+            Some(Vec2::new(500.0, 0.0))
+        );
+    }
+
+    #[test]
+    fn predicted_wiggles_respect_the_cooldown() {
+        let config = GameConfig::default();
+        let mut bee = PredictedBee::from_state(&state(Vec2::ZERO, 0));
+        let wiggle = PlayerInput {
+            wiggle: true,
+            ..default()
+        };
+
+        bee.step(&wiggle, &config, None, DT);
+        assert!(bee.motion.wiggle.is_some());
+        assert_ne!(bee.position.x, 0.0);
+
+        // Runs its course, ends back where it started and can't go again
+        // straight away
+        while bee.motion.wiggle.is_some() {
+            bee.step(&PlayerInput::default(), &config, None, DT);
+        }
+        assert_eq!(bee.position.x, 0.0);
+        bee.step(&wiggle, &config, None, DT);
+        assert!(bee.motion.wiggle.is_none());
+        assert!(!bee.motion.cooldown.is_zero());
+    }
+
+    #[test]
+    fn predicted_bees_stop_at_the_edge_of_the_garden() {
+        let config = GameConfig::default();
+        let mut prediction = Prediction::default();
+        prediction.set_play_area(PlayArea(Vec2::new(100.0, 100.0)));
+        prediction.reconcile(0, &state(Vec2::ZERO, 0), &config, DT);
+        for _ in 0..120 {
+            prediction.apply(&right(), &config, DT);
+        }
+        assert_eq!(prediction.bee().unwrap().position, Vec2::new(50.0, 0.0));
+    }
+}
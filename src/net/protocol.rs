@@ -0,0 +1,342 @@
+use std::fmt;
+use std::time::Duration;
+
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::bee::{Emote, PlayerInput, Role};
+use crate::game::GameState;
+use crate::level::LevelDefinition;
+
+/// Bumped whenever a message changes shape; the server turns away clients
+/// built against another version
+pub const PROTOCOL_VERSION: u32 = 5;
+
+/// Port the garden server listens on unless told otherwise
+pub const DEFAULT_PORT: u16 = 7878;
+
+/// Most bees one garden server takes
+pub const MAX_ONLINE_PLAYERS: usize = 8;
+
+/// What a player wants their bee to do. Clients only ever send intents; the
+/// server decides what actually happens.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct Intent {
+    /// Counts up by one for each tick of input, so the server can say which
+    /// input it has applied
+    #[serde(default)]
+    pub sequence: u32,
+    /// Held direction, length at most 1
+    pub direction: Vec2,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub move_target: Option<Vec2>,
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub wiggle: bool,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub emote: Option<Emote>,
+}
+
+impl From<&PlayerInput> for Intent {
+    fn from(input: &PlayerInput) -> Self {
+        Self {
+            sequence: 0,
+            direction: input.direction,
+            move_target: input.move_target,
+            wiggle: input.wiggle,
+            emote: input.emote,
+        }
+    }
+}
+
+impl From<&Intent> for PlayerInput {
+    fn from(intent: &Intent) -> Self {
+        Self {
+            move_target: intent.move_target,
+            wiggle: intent.wiggle,
+            direction: intent.direction.clamp_length_max(1.0),
+            emote: intent.emote,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum ClientMessage {
+    /// First message on a new connection
+    Hello {
+        version: u32,
+    },
+    Intent(Intent),
+    /// Lobby only: open a new room and join it
+    CreateRoom,
+    /// Lobby only: join the room with this code
+    JoinRoom {
+        code: String,
+    },
+    /// Lobby only: the role to play once the round starts
+    ChooseRole {
+        role: Role,
+    },
+    /// Lobby only: ready (or not) to start
+    SetReady {
+        ready: bool,
+    },
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub enum ServerMessage {
+    /// Accepted: the slot this client plays and the garden to build locally
+    Welcome {
+        player: usize,
+        level: LevelDefinition,
+    },
+    /// A new round started in `level`
+    Round {
+        level: LevelDefinition,
+    },
+    Snapshot(Snapshot),
+    /// Turned away; the connection is closed after this
+    Rejected {
+        reason: String,
+    },
+    /// Hello answered by a lobby: create or join a room before playing
+    Lobby {
+        /// Most bees in a room
+        cap: usize,
+    },
+    /// Who is in the client's room and whether they're ready. Sent whenever
+    /// that changes.
+    Room(RoomState),
+    /// A lobby request that can't be done; the connection stays open
+    Denied {
+        reason: String,
+    },
+}
+
+/// A lobby room before its round starts
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RoomState {
+    /// Short code others join the room with
+    pub code: String,
+    pub cap: usize,
+    /// In the order they joined
+    pub members: Vec<RoomMember>,
+    /// Slot of the client this was sent to
+    pub you: usize,
+}
+
+impl RoomState {
+    pub fn member(&self, player: usize) -> Option<&RoomMember> {
+        self.members.iter().find(|member| member.player == player)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RoomMember {
+    /// Slot the member's bee plays in once the round starts
+    pub player: usize,
+    pub role: Role,
+    pub ready: bool,
+}
+
+/// One player's bee as the server last simulated it
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct BeeState {
+    pub player: usize,
+    pub role: Role,
+    pub position: Vec2,
+    pub allergy: f32,
+    pub allergy_max: f32,
+    pub pollen: u32,
+    pub sneezes: u32,
+    pub wiggling: bool,
+    pub sneezing: bool,
+    /// Bubble showing over the bee
+    pub emote: Option<Emote>,
+    /// Sequence of the last intent from this bee's player that the server
+    /// has applied
+    pub ack: u32,
+    pub motion: BeeMotion,
+}
+
+/// Movement state of a bee beyond its position, which a client needs to
+/// carry on simulating its own bee from a snapshot
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct BeeMotion {
+    /// Steering velocity
+    pub velocity: Vec2,
+    /// Click-to-move destination
+    pub destination: Option<Vec2>,
+    pub wiggle: Option<WiggleMotion>,
+    /// Time until the bee can wiggle again. Timers travel as exact durations
+    /// so a client counts down the same ticks as the server.
+    pub cooldown: Duration,
+}
+
+/// A wiggle under way
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct WiggleMotion {
+    pub elapsed: Duration,
+    /// Where the bee was across when it started; it sways around this
+    pub origin_x: f32,
+}
+
+/// A flower head, matched to the client's copy by `LevelIndex`
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct HeadState {
+    pub index: usize,
+    /// Translation relative to its flower
+    pub position: Vec2,
+    pub rizz: f32,
+}
+
+/// An AI companion, matched to the client's copy by `LevelIndex`
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CompanionState {
+    pub index: usize,
+    pub position: Vec2,
+    pub emote: Option<Emote>,
+}
+
+/// A pollen grain on the ground; `id` stays the same while it exists
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PollenState {
+    pub id: u64,
+    pub position: Vec2,
+}
+
+/// Everything a client draws, as of one server tick
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Snapshot {
+    /// Server ticks since it started; unlike `SimulationTick` this keeps
+    /// counting between rounds
+    pub tick: u32,
+    pub state: GameState,
+    /// Seconds since the round started
+    pub elapsed: f32,
+    pub bees: Vec<BeeState>,
+    pub heads: Vec<HeadState>,
+    pub companions: Vec<CompanionState>,
+    /// Whether each stem cache (by `LevelIndex`) can be collected
+    pub caches: Vec<bool>,
+    pub pollen: Vec<PollenState>,
+    /// Pollen carried away from the garden so far, across rounds and, in a
+    /// persistent garden, across sessions
+    pub harvested: u64,
+}
+
+impl Snapshot {
+    pub fn bee(&self, player: usize) -> Option<&BeeState> {
+        self.bees.iter().find(|bee| bee.player == player)
+    }
+}
+
+/// Why a connection failed or a message could not be read
+#[derive(Debug)]
+pub enum NetError {
+    Io(std::io::Error),
+    Decode(serde_json::Error),
+    /// The other side closed the connection
+    Closed,
+    #[cfg(not(target_arch = "wasm32"))]
+    WebSocket(tungstenite::Error),
+    #[cfg(target_arch = "wasm32")]
+    Browser(String),
+}
+
+impl fmt::Display for NetError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            NetError::Io(err) => write!(f, "connection failed: {err}"),
+            NetError::Decode(err) => write!(f, "could not read message: {err}"),
+            NetError::Closed => write!(f, "connection closed"),
+            #[cfg(not(target_arch = "wasm32"))]
+            NetError::WebSocket(err) => write!(f, "websocket error: {err}"),
+            #[cfg(target_arch = "wasm32")]
+            NetError::Browser(err) => write!(f, "websocket error: {err}"),
+        }
+    }
+}
+
+impl std::error::Error for NetError {}
+
+impl From<std::io::Error> for NetError {
+    fn from(err: std::io::Error) -> Self {
+        NetError::Io(err)
+    }
+}
+
+impl From<serde_json::Error> for NetError {
+    fn from(err: serde_json::Error) -> Self {
+        NetError::Decode(err)
+    }
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+impl From<tungstenite::Error> for NetError {
+    fn from(err: tungstenite::Error) -> Self {
+        match err {
+            tungstenite::Error::ConnectionClosed | tungstenite::Error::AlreadyClosed => {
+                NetError::Closed
+            }
+            tungstenite::Error::Io(err) => NetError::Io(err),
+            err => NetError::WebSocket(err),
+        }
+    }
+}
+
+/// Messages travel as JSON text frames, which browsers can read and send
+/// without any extra tooling
+pub fn encode<T: Serialize>(message: &T) -> String {
+    serde_json::to_string(message).expect("network messages always serialize")
+}
+
+pub fn decode<T: for<'de> Deserialize<'de>>(text: &str) -> Result<T, NetError> {
+    Ok(serde_json::from_str(text)?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn client_messages_round_trip() {
+        let intent = ClientMessage::Intent(Intent {
+            sequence: 42,
+            direction: Vec2::new(0.6, -0.8),
+            move_target: Some(Vec2::new(10.0, 20.0)),
+            wiggle: true,
+            emote: Some(Emote::Wave),
+        });
+        let decoded: ClientMessage = decode(&encode(&intent)).unwrap();
+        assert_eq!(decoded, intent);
+    }
+
+    #[test]
+    fn idle_intents_stay_small() {
+        let text = encode(&ClientMessage::Intent(Intent::default()));
+        assert!(!text.contains("wiggle"));
+        assert!(!text.contains("move_target"));
+        assert!(!text.contains("emote"));
+        assert_eq!(
+            decode::<ClientMessage>(&text).unwrap(),
+            ClientMessage::Intent(Intent::default())
+        );
+    }
+
+    #[test]
+    fn intents_cannot_push_faster_than_a_full_stick() {
+        let intent = Intent {
+            direction: Vec2::new(30.0, 40.0),
+            ..default()
+        };
+        assert_eq!(PlayerInput::from(&intent).direction, Vec2::new(0.6, 0.8));
+    }
+
+    #[test]
+    fn garbage_is_a_decode_error() {
+        assert!(matches!(
+            decode::<ServerMessage>("{not json"),
+            Err(NetError::Decode(_))
+        ));
+    }
+}
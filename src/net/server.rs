@@ -0,0 +1,735 @@
+//! The authoritative garden: a `HeadlessGame` fed by the intents of every
+//! connected client, broadcasting what happened.
+
+use std::collections::VecDeque;
+use std::io::ErrorKind;
+use std::net::{SocketAddr, TcpListener, TcpStream, ToSocketAddrs};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::{Duration, Instant};
+
+use bevy::prelude::*;
+use tungstenite::handshake::server::{NoCallback, ServerHandshake};
+use tungstenite::handshake::{HandshakeError, MidHandshake};
+use tungstenite::WebSocket;
+
+use super::{
+    read_messages, write_message, BeeMotion, BeeState, ClientMessage, CompanionState, HeadState,
+    Intent, NetError, PollenState, ServerMessage, Snapshot, WiggleMotion, WorldError, WorldState,
+    MAX_ONLINE_PLAYERS, PROTOCOL_VERSION, WORLD_SAVE_INTERVAL,
+};
+use crate::ai::{AiDiva, AiHealer, PlayerBee};
+use crate::bee::{
+    AllergyMeter, Bee, CollectedPollen, Emoting, MoveTarget, PlayerInput, PlayerSlot, Role,
+    SneezeCount, Sneezing, Steering, WiggleCooldown, Wiggling,
+};
+use crate::flower::{CacheSpawnPoint, FlowerHead, Pollen};
+use crate::game::{GameConfig, GameState, SessionTimer};
+use crate::headless::HeadlessGame;
+use crate::level::{LevelDefinition, LevelIndex};
+
+/// Ticks between snapshots (20 a second)
+pub const SNAPSHOT_INTERVAL: u32 = 3;
+
+/// Ticks a won or lost round stays on screen before the next one starts
+const ROUND_RESTART_DELAY: u32 = 180;
+
+/// Most intents a client may have waiting. Beyond this the oldest are folded
+/// into the next, so a burst after a stall doesn't leave its bee trailing
+/// further and further behind.
+const MAX_QUEUED_INTENTS: usize = 8;
+
+/// Longest a new connection may take over its WebSocket handshake
+const HANDSHAKE_TIMEOUT: Duration = Duration::from_secs(1);
+
+/// A connection that doesn't have a bee (yet)
+pub(crate) struct Guest {
+    socket: WebSocket<TcpStream>,
+    /// Messages read but left for whoever handles the guest next
+    unread: Vec<ClientMessage>,
+    pub(crate) connected: bool,
+}
+
+impl Guest {
+    pub(crate) fn send(&mut self, message: &ServerMessage) {
+        if write_message(&mut self.socket, message).is_err() {
+            self.connected = false;
+        }
+    }
+
+    /// Everything the guest has sent since the last call, and anything put
+    /// back since
+    pub(crate) fn receive(&mut self) -> Vec<ClientMessage> {
+        let mut messages = std::mem::take(&mut self.unread);
+        if let Err(err) = read_messages(&mut self.socket, &mut messages) {
+            if !matches!(err, NetError::Closed) {
+                warn!("Dropping a client: {err}");
+            }
+            self.connected = false;
+        }
+        messages
+    }
+
+    /// Leave `messages` to be received again, ahead of newer ones
+    pub(crate) fn put_back(&mut self, messages: impl IntoIterator<Item = ClientMessage>) {
+        self.unread.extend(messages);
+    }
+
+    /// Say why the guest can't stay, then hang up
+    pub(crate) fn reject(&mut self, reason: String) {
+        self.send(&ServerMessage::Rejected { reason });
+        let _ = self.socket.close(None);
+        self.connected = false;
+    }
+}
+
+/// A new connection part way through its WebSocket handshake
+struct Handshake {
+    attempt: MidHandshake<ServerHandshake<TcpStream, NoCallback>>,
+    address: SocketAddr,
+    started: Instant,
+}
+
+/// The listening socket and the connections still handshaking. Handshakes
+/// are moved on a little each tick instead of blocking it, so a slow or
+/// silent client can't hold up the garden.
+pub(crate) struct Doorway {
+    listener: TcpListener,
+    handshakes: Vec<Handshake>,
+}
+
+impl Doorway {
+    pub(crate) fn bind(address: impl ToSocketAddrs) -> Result<Self, NetError> {
+        let listener = TcpListener::bind(address)?;
+        listener.set_nonblocking(true)?;
+        Ok(Self {
+            listener,
+            handshakes: Vec::new(),
+        })
+    }
+
+    pub(crate) fn local_addr(&self) -> SocketAddr {
+        self.listener
+            .local_addr()
+            .expect("a bound listener has an address")
+    }
+
+    /// Take in every connection waiting on the listener, and add those that
+    /// finished their handshake to `guests`
+    pub(crate) fn admit(&mut self, guests: &mut Vec<Guest>) {
+        for handshake in std::mem::take(&mut self.handshakes) {
+            let result = handshake.attempt.handshake();
+            self.proceed(result, handshake.address, handshake.started, guests);
+        }
+
+        loop {
+            match self.listener.accept() {
+                Ok((stream, address)) => match prepare_stream(&stream) {
+                    Ok(()) => {
+                        let result = tungstenite::accept(stream);
+                        self.proceed(result, address, Instant::now(), guests);
+                    }
+                    Err(err) => warn!("Turned away {address}: {err}"),
+                },
+                Err(err) if err.kind() == ErrorKind::WouldBlock => return,
+                Err(err) => {
+                    warn!("Could not accept a connection: {err}");
+                    return;
+                }
+            }
+        }
+    }
+
+    /// Seat a finished handshake as a guest, or keep waiting on an
+    /// unfinished one until it runs out of time
+    fn proceed(
+        &mut self,
+        result: Result<
+            WebSocket<TcpStream>,
+            HandshakeError<ServerHandshake<TcpStream, NoCallback>>,
+        >,
+        address: SocketAddr,
+        started: Instant,
+        guests: &mut Vec<Guest>,
+    ) {
+        match result {
+            Ok(socket) => guests.push(Guest {
+                socket,
+                unread: Vec::new(),
+                connected: true,
+            }),
+            Err(HandshakeError::Interrupted(attempt)) if started.elapsed() < HANDSHAKE_TIMEOUT => {
+                self.handshakes.push(Handshake {
+                    attempt,
+                    address,
+                    started,
+                });
+            }
+            Err(HandshakeError::Interrupted(_)) => {
+                warn!(
+                    "Turned away {address}: {}",
+                    NetError::Io(ErrorKind::TimedOut.into())
+                );
+            }
+            Err(HandshakeError::Failure(err)) => {
+                warn!("Turned away {address}: {}", NetError::from(err));
+            }
+        }
+    }
+}
+
+/// Make a new connection non-blocking like the listener, so its handshake
+/// and later reads never stall a tick
+fn prepare_stream(stream: &TcpStream) -> std::io::Result<()> {
+    stream.set_nonblocking(true)?;
+    stream.set_nodelay(true)
+}
+
+/// Why a client saying hello with `version` can't play here, if it can't
+pub(crate) fn version_mismatch(version: u32) -> Option<String> {
+    (version != PROTOCOL_VERSION)
+        .then(|| format!("server speaks protocol {PROTOCOL_VERSION}, client speaks {version}"))
+}
+
+/// Call `update` once per `timestep` of real time until `stop` is set
+pub(crate) fn run_paced(timestep: Duration, stop: &AtomicBool, mut update: impl FnMut()) {
+    let mut next_tick = Instant::now();
+    while !stop.load(Ordering::Relaxed) {
+        update();
+        next_tick += timestep;
+        let now = Instant::now();
+        if next_tick > now {
+            std::thread::sleep(next_tick - now);
+        } else {
+            // Running behind; don't try to catch up in a burst
+            next_tick = now;
+        }
+    }
+}
+
+/// A guest playing a bee in a garden
+struct Client {
+    guest: Guest,
+    player: usize,
+    role: Role,
+    /// Intents waiting for their tick, oldest first. Each tick applies one.
+    intents: VecDeque<Intent>,
+    /// Held direction of the last intent applied, kept while none are waiting
+    direction: Vec2,
+    /// Sequence of the last intent applied
+    ack: u32,
+}
+
+impl Client {
+    fn queue(&mut self, mut intent: Intent) {
+        // Numbers too big for an f32 arrive as infinities, which would spread
+        // from this bee into every snapshot and the saved garden
+        if !intent.direction.is_finite() {
+            intent.direction = Vec2::ZERO;
+        }
+        intent.move_target = intent.move_target.filter(|target| target.is_finite());
+        self.intents.push_back(intent);
+        while self.intents.len() > MAX_QUEUED_INTENTS {
+            let Some(dropped) = self.intents.pop_front() else {
+                break;
+            };
+            // One-shot commands aren't lost with the intent that carried them
+            if let Some(next) = self.intents.front_mut() {
+                next.wiggle |= dropped.wiggle;
+                next.move_target = next.move_target.or(dropped.move_target);
+                next.emote = next.emote.or(dropped.emote);
+            }
+        }
+    }
+
+    /// Input for the next tick, from the oldest waiting intent or else the
+    /// held direction
+    fn next_input(&mut self) -> PlayerInput {
+        match self.intents.pop_front() {
+            Some(intent) => {
+                let input = PlayerInput::from(&intent);
+                self.ack = intent.sequence;
+                self.direction = input.direction;
+                input
+            }
+            None => PlayerInput {
+                direction: self.direction,
+                ..default()
+            },
+        }
+    }
+}
+
+/// A garden in play: the simulation and the clients playing its bees. Rounds
+/// follow one another for as long as it runs.
+pub struct Garden {
+    game: HeadlessGame,
+    level: LevelDefinition,
+    clients: Vec<Client>,
+    /// Ticks since the garden opened, which keep counting between rounds
+    tick: u32,
+    /// Ticks since the round was won or lost
+    ended_for: u32,
+    /// Pollen carried away by bees that left or whose round ended
+    banked: u64,
+    /// File a persistent garden is kept in
+    world_file: Option<PathBuf>,
+}
+
+impl Garden {
+    /// Start a round in `level` with no bees yet
+    pub fn new(config: GameConfig, level: LevelDefinition) -> Self {
+        let mut game = HeadlessGame::new(config);
+        game.set_players(0);
+        game.load_level(&level);
+        Self {
+            game,
+            level,
+            clients: Vec::new(),
+            tick: 0,
+            ended_for: 0,
+            banked: 0,
+            world_file: None,
+        }
+    }
+
+    /// Make the garden persistent: pick up where the garden in `path` was
+    /// left, if the file exists, and keep it up to date from now on. New
+    /// rounds keep the garden as it was rather than starting it over.
+    pub fn persist_to(&mut self, path: impl Into<PathBuf>) -> Result<(), WorldError> {
+        let path = path.into();
+        if let Some(state) = WorldState::load(&path, &self.level)? {
+            state.restore(&mut self.game);
+            self.banked = state.harvested;
+        }
+        self.world_file = Some(path);
+        self.save_world()
+    }
+
+    pub fn world_file(&self) -> Option<&Path> {
+        self.world_file.as_deref()
+    }
+
+    /// Write a persistent garden to its file now
+    pub fn save_world(&mut self) -> Result<(), WorldError> {
+        if self.world_file.is_none() {
+            return Ok(());
+        }
+        let harvested = self.harvested();
+        let state = WorldState::capture(&mut self.game, harvested);
+        match &self.world_file {
+            Some(path) => state.save(path),
+            None => Ok(()),
+        }
+    }
+
+    fn save_world_or_warn(&mut self) {
+        if let Err(err) = self.save_world() {
+            warn!("Could not save the garden: {err}");
+        }
+    }
+
+    /// Pollen carried away from this garden since it opened, or ever for a
+    /// persistent garden, counting what bees carry now
+    pub fn harvested(&mut self) -> u64 {
+        let world = self.game.world_mut();
+        let carried: u64 = world
+            .query_filtered::<&CollectedPollen, With<PlayerBee>>()
+            .iter(world)
+            .map(|pollen| u64::from(pollen.count))
+            .sum();
+        self.banked + carried
+    }
+
+    /// Bees currently in the garden
+    pub fn players(&self) -> usize {
+        self.clients.len()
+    }
+
+    pub fn game(&mut self) -> &mut HeadlessGame {
+        &mut self.game
+    }
+
+    pub fn level(&self) -> &LevelDefinition {
+        &self.level
+    }
+
+    /// Lowest slot without a bee, if the garden has room
+    pub(crate) fn free_slot(&self) -> Option<usize> {
+        (0..MAX_ONLINE_PLAYERS).find(|slot| self.clients.iter().all(|c| c.player != *slot))
+    }
+
+    /// Give `guest` the bee in `player`'s slot, playing `role`
+    pub(crate) fn seat(&mut self, mut guest: Guest, player: usize, role: Role) {
+        guest.send(&ServerMessage::Welcome {
+            player,
+            level: self.level.clone(),
+        });
+        self.clients.push(Client {
+            guest,
+            player,
+            role,
+            intents: VecDeque::new(),
+            direction: Vec2::ZERO,
+            ack: 0,
+        });
+        self.spawn_bee(player, role);
+        info!("Player {} joined as {role:?}", player + 1);
+    }
+
+    fn spawn_bee(&mut self, player: usize, role: Role) {
+        let Some(entity) = self.game.spawn_player(player) else {
+            return;
+        };
+        if let Some(mut bee) = self.game.world_mut().get_mut::<Bee>(entity) {
+            bee.role = role;
+        }
+    }
+
+    /// Act on `message` from the client playing `player`
+    pub(crate) fn handle(&mut self, player: usize, message: ClientMessage) {
+        let Some(client) = self.clients.iter_mut().find(|c| c.player == player) else {
+            return;
+        };
+        match message {
+            ClientMessage::Intent(intent) => client.queue(intent),
+            // Already playing
+            ClientMessage::Hello { .. }
+            | ClientMessage::CreateRoom
+            | ClientMessage::JoinRoom { .. }
+            | ClientMessage::ChooseRole { .. }
+            | ClientMessage::SetReady { .. } => {}
+        }
+    }
+
+    /// Take in the clients' intents, run one tick and send out a snapshot
+    /// when one is due
+    pub fn update(&mut self) {
+        for index in 0..self.clients.len() {
+            let player = self.clients[index].player;
+            for message in self.clients[index].guest.receive() {
+                self.handle(player, message);
+            }
+        }
+        self.step();
+
+        if self.tick.is_multiple_of(SNAPSHOT_INTERVAL) {
+            let mut snapshot = capture_snapshot(&mut self.game, self.tick);
+            for bee in &mut snapshot.bees {
+                let owner = self.clients.iter().find(|c| c.player == bee.player);
+                bee.ack = owner.map_or(0, |client| client.ack);
+            }
+            snapshot.harvested = self.harvested();
+            let snapshot = ServerMessage::Snapshot(snapshot);
+            for client in &mut self.clients {
+                client.guest.send(&snapshot);
+            }
+        }
+
+        self.drop_disconnected();
+        if self.tick.is_multiple_of(WORLD_SAVE_INTERVAL) {
+            self.save_world_or_warn();
+        }
+    }
+
+    fn step(&mut self) {
+        if self.game.state() == GameState::Playing {
+            self.ended_for = 0;
+        } else {
+            self.ended_for += 1;
+            if self.ended_for >= ROUND_RESTART_DELAY {
+                self.restart_round();
+            }
+        }
+
+        let mut inputs = Vec::new();
+        for client in &mut self.clients {
+            if inputs.len() <= client.player {
+                inputs.resize_with(client.player + 1, PlayerInput::default);
+            }
+            inputs[client.player] = client.next_input();
+        }
+        self.game.step_with_inputs(inputs);
+        self.tick += 1;
+    }
+
+    /// Start a new round with everyone who is still here. The bees' pollen
+    /// is banked; a persistent garden stays as it was.
+    fn restart_round(&mut self) {
+        self.ended_for = 0;
+        let harvested = self.harvested();
+        let garden = WorldState::capture(&mut self.game, harvested);
+        self.banked = harvested;
+
+        self.game.load_level(&self.level);
+        if self.world_file.is_some() {
+            garden.restore(&mut self.game);
+        }
+        let round = ServerMessage::Round {
+            level: self.level.clone(),
+        };
+        let seats: Vec<(usize, Role)> = self.clients.iter().map(|c| (c.player, c.role)).collect();
+        for (player, role) in seats {
+            self.spawn_bee(player, role);
+        }
+        for client in &mut self.clients {
+            client.guest.send(&round);
+        }
+    }
+
+    fn drop_disconnected(&mut self) {
+        let mut index = 0;
+        let mut left = false;
+        while index < self.clients.len() {
+            if self.clients[index].guest.connected {
+                index += 1;
+                continue;
+            }
+            let client = self.clients.swap_remove(index);
+            self.bank_pollen(client.player);
+            self.game.remove_player(client.player);
+            info!("Player {} left", client.player + 1);
+            left = true;
+        }
+        if left {
+            self.save_world_or_warn();
+        }
+    }
+
+    /// Count the pollen `player`'s bee carries as harvested, before it goes
+    fn bank_pollen(&mut self, player: usize) {
+        let world = self.game.world_mut();
+        let carried: u64 = world
+            .query_filtered::<(&PlayerSlot, &CollectedPollen), With<PlayerBee>>()
+            .iter(world)
+            .filter(|(slot, _)| slot.0 == player)
+            .map(|(_, pollen)| u64::from(pollen.count))
+            .sum();
+        self.banked += carried;
+    }
+}
+
+/// A garden anyone can drop into: each client that says hello gets a
+/// gatherer bee while there's room
+pub struct GardenServer {
+    doorway: Doorway,
+    garden: Garden,
+    /// Connections that haven't said hello yet
+    guests: Vec<Guest>,
+}
+
+impl GardenServer {
+    /// Listen on `address` and start a round in `level`. Bind to port 0 to
+    /// have the system pick a free port (see `local_addr`).
+    pub fn bind(
+        address: impl ToSocketAddrs,
+        config: GameConfig,
+        level: LevelDefinition,
+    ) -> Result<Self, NetError> {
+        Ok(Self {
+            doorway: Doorway::bind(address)?,
+            garden: Garden::new(config, level),
+            guests: Vec::new(),
+        })
+    }
+
+    pub fn local_addr(&self) -> SocketAddr {
+        self.doorway.local_addr()
+    }
+
+    pub fn garden(&mut self) -> &mut Garden {
+        &mut self.garden
+    }
+
+    /// See `Garden::persist_to`
+    pub fn persist_to(&mut self, path: impl Into<PathBuf>) -> Result<(), WorldError> {
+        self.garden.persist_to(path)
+    }
+
+    pub fn save_world(&mut self) -> Result<(), WorldError> {
+        self.garden.save_world()
+    }
+
+    pub fn harvested(&mut self) -> u64 {
+        self.garden.harvested()
+    }
+
+    pub fn players(&self) -> usize {
+        self.garden.players()
+    }
+
+    pub fn game(&mut self) -> &mut HeadlessGame {
+        self.garden.game()
+    }
+
+    /// Simulate in real time, one tick per `Time<Fixed>` step, until `stop`
+    /// is set. A persistent garden is written out on the way out.
+    pub fn run(&mut self, stop: &AtomicBool) {
+        let timestep = self.garden.game.timestep();
+        run_paced(timestep, stop, || self.update());
+        self.garden.save_world_or_warn();
+    }
+
+    /// Take in new clients, then run the garden for a tick
+    pub fn update(&mut self) {
+        self.doorway.admit(&mut self.guests);
+        self.greet_guests();
+        self.garden.update();
+    }
+
+    /// Give each guest that said hello a bee, or turn it away
+    fn greet_guests(&mut self) {
+        let mut index = 0;
+        while index < self.guests.len() {
+            let mut messages = self.guests[index].receive().into_iter();
+            let Some(version) = messages.find_map(|message| match message {
+                ClientMessage::Hello { version } => Some(version),
+                _ => None,
+            }) else {
+                if self.guests[index].connected {
+                    index += 1;
+                } else {
+                    self.guests.swap_remove(index);
+                }
+                continue;
+            };
+
+            let mut guest = self.guests.swap_remove(index);
+            let rejection = version_mismatch(version).or_else(|| {
+                self.garden
+                    .free_slot()
+                    .is_none()
+                    .then(|| "the garden is full".to_string())
+            });
+            if let Some(reason) = rejection {
+                guest.reject(reason);
+                continue;
+            }
+
+            // Intents sent straight after hello
+            guest.put_back(messages);
+            let player = self.garden.free_slot().expect("checked for room above");
+            self.garden.seat(guest, player, Role::Gatherer);
+        }
+    }
+}
+
+/// What every client needs to draw the garden as it is now, stamped with
+/// the server's `tick`
+pub fn capture_snapshot(game: &mut HeadlessGame, tick: u32) -> Snapshot {
+    let state = game.state();
+    let world = game.world_mut();
+    let elapsed = world.resource::<SessionTimer>().elapsed;
+
+    let mut bees: Vec<BeeState> = world
+        .query_filtered::<(
+            &PlayerSlot,
+            &Bee,
+            &Transform,
+            &AllergyMeter,
+            &CollectedPollen,
+            Option<&SneezeCount>,
+            Has<Sneezing>,
+            &Steering,
+            &MoveTarget,
+            Option<&Wiggling>,
+            Option<&WiggleCooldown>,
+            Option<&Emoting>,
+        ), With<PlayerBee>>()
+        .iter(world)
+        .map(
+            |(
+                slot,
+                bee,
+                transform,
+                allergy,
+                pollen,
+                sneezes,
+                sneezing,
+                steering,
+                target,
+                wiggling,
+                cooldown,
+                emoting,
+            )| {
+                let wiggle = wiggling.map(|wiggling| WiggleMotion {
+                    elapsed: wiggling.timer.elapsed(),
+                    origin_x: wiggling.original_x,
+                });
+                BeeState {
+                    player: slot.0,
+                    role: bee.role,
+                    position: transform.translation.truncate(),
+                    allergy: allergy.value,
+                    allergy_max: allergy.max,
+                    pollen: pollen.count,
+                    sneezes: sneezes.map_or(0, |count| count.count),
+                    wiggling: wiggle.is_some(),
+                    sneezing,
+                    emote: emoting.map(|emoting| emoting.emote),
+                    // Filled in per player by the server
+                    ack: 0,
+                    motion: BeeMotion {
+                        velocity: steering.velocity,
+                        destination: target.destination,
+                        wiggle,
+                        cooldown: cooldown
+                            .map_or(Duration::ZERO, |cooldown| cooldown.timer.remaining()),
+                    },
+                }
+            },
+        )
+        .collect();
+    bees.sort_by_key(|bee| bee.player);
+
+    let mut heads: Vec<HeadState> = world
+        .query::<(&LevelIndex, &Transform, &FlowerHead)>()
+        .iter(world)
+        .map(|(index, transform, head)| HeadState {
+            index: index.0,
+            position: transform.translation.truncate(),
+            rizz: head.rizz,
+        })
+        .collect();
+    heads.sort_by_key(|head| head.index);
+
+    let mut companions: Vec<CompanionState> = world
+        .query_filtered::<(&LevelIndex, &Transform, Option<&Emoting>), Or<(With<AiDiva>, With<AiHealer>)>>()
+        .iter(world)
+        .map(|(index, transform, emoting)| CompanionState {
+            index: index.0,
+            position: transform.translation.truncate(),
+            emote: emoting.map(|emoting| emoting.emote),
+        })
+        .collect();
+    companions.sort_by_key(|companion| companion.index);
+
+    let mut caches: Vec<(usize, bool)> = world
+        .query::<(&LevelIndex, &CacheSpawnPoint)>()
+        .iter(world)
+        .map(|(index, cache)| (index.0, cache.is_active))
+        .collect();
+    caches.sort_by_key(|(index, _)| *index);
+
+    let pollen = world
+        .query_filtered::<(Entity, &Transform), With<Pollen>>()
+        .iter(world)
+        .map(|(entity, transform)| PollenState {
+            id: entity.to_bits(),
+            position: transform.translation.truncate(),
+        })
+        .collect();
+
+    Snapshot {
+        tick,
+        state,
+        elapsed,
+        bees,
+        heads,
+        companions,
+        caches: caches.into_iter().map(|(_, active)| active).collect(),
+        pollen,
+        // Filled in by the server
+        harvested: 0,
+    }
+}
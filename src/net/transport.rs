@@ -0,0 +1,274 @@
+//! WebSocket connection from a game client to a garden server.
+//!
+//! Native builds connect on a background thread, then drive a non-blocking
+//! `tungstenite` socket from the game loop; web builds use the browser's
+//! `WebSocket`. Either way `connect` and `send` never block and `receive`
+//! hands back whatever has arrived since the last call.
+
+pub use platform::Connection;
+
+#[cfg(not(target_arch = "wasm32"))]
+pub(crate) use platform::{read_messages, write_message};
+
+#[cfg(not(target_arch = "wasm32"))]
+mod platform {
+    use std::io::ErrorKind;
+    use std::net::{TcpStream, ToSocketAddrs};
+    use std::sync::mpsc::{self, Receiver, TryRecvError};
+    use std::time::Duration;
+
+    use serde::{Deserialize, Serialize};
+    use tungstenite::client::IntoClientRequest;
+    use tungstenite::handshake::client::Request;
+    use tungstenite::handshake::HandshakeError;
+    use tungstenite::{Message, WebSocket};
+
+    use crate::net::{decode, encode, ClientMessage, NetError, ServerMessage, PROTOCOL_VERSION};
+
+    /// Longest wait for a server to answer a new connection
+    const CONNECT_TIMEOUT: Duration = Duration::from_secs(3);
+
+    fn would_block(err: &tungstenite::Error) -> bool {
+        matches!(err, tungstenite::Error::Io(err) if err.kind() == ErrorKind::WouldBlock)
+    }
+
+    /// Queue `message` on a non-blocking socket. Whatever the network can't
+    /// take yet stays buffered and goes out with a later write or read.
+    pub(crate) fn write_message<T: Serialize>(
+        socket: &mut WebSocket<TcpStream>,
+        message: &T,
+    ) -> Result<(), NetError> {
+        match socket.send(Message::text(encode(message))) {
+            Err(err) if !would_block(&err) => Err(err.into()),
+            _ => Ok(()),
+        }
+    }
+
+    /// Decode every message waiting on a non-blocking socket into
+    /// `messages`. Messages read before an error are kept.
+    pub(crate) fn read_messages<T: for<'de> Deserialize<'de>>(
+        socket: &mut WebSocket<TcpStream>,
+        messages: &mut Vec<T>,
+    ) -> Result<(), NetError> {
+        match socket.flush() {
+            Err(err) if !would_block(&err) => return Err(err.into()),
+            _ => {}
+        }
+        loop {
+            match socket.read() {
+                Ok(Message::Text(text)) => messages.push(decode(text.as_str())?),
+                Ok(Message::Close(_)) => return Err(NetError::Closed),
+                // Pings are answered by tungstenite itself
+                Ok(_) => {}
+                Err(err) if would_block(&err) => return Ok(()),
+                Err(err) => return Err(err.into()),
+            }
+        }
+    }
+
+    /// Open the socket the blocking way; run off the game loop
+    fn open(request: Request) -> Result<WebSocket<TcpStream>, NetError> {
+        let uri = request.uri();
+        let host = uri.host().unwrap_or("127.0.0.1");
+        let port = uri.port_u16().unwrap_or(80);
+        let address = (host, port)
+            .to_socket_addrs()?
+            .next()
+            .ok_or_else(|| std::io::Error::new(ErrorKind::NotFound, "unknown host"))?;
+
+        let stream = TcpStream::connect_timeout(&address, CONNECT_TIMEOUT)?;
+        stream.set_read_timeout(Some(CONNECT_TIMEOUT))?;
+        stream.set_nodelay(true)?;
+        let (socket, _) = tungstenite::client(request, stream).map_err(|err| match err {
+            HandshakeError::Failure(err) => NetError::from(err),
+            HandshakeError::Interrupted(_) => NetError::Io(ErrorKind::TimedOut.into()),
+        })?;
+        socket.get_ref().set_nonblocking(true)?;
+        Ok(socket)
+    }
+
+    enum Socket {
+        /// Waiting on the background thread that opens it
+        Connecting(Receiver<Result<WebSocket<TcpStream>, NetError>>),
+        Open(Box<WebSocket<TcpStream>>),
+    }
+
+    pub struct Connection {
+        socket: Socket,
+        /// Sent once the socket opens
+        outbox: Vec<ClientMessage>,
+    }
+
+    impl Connection {
+        /// Start connecting to the server at `url` (e.g.
+        /// `ws://127.0.0.1:7878`) and say hello. The connection is opened on
+        /// a background thread; messages sent before it opens are held back
+        /// until it does. Native builds only speak plain `ws://`.
+        pub fn connect(url: &str) -> Result<Self, NetError> {
+            let request = url.into_client_request()?;
+            if request.uri().scheme_str() != Some("ws") {
+                return Err(NetError::Io(std::io::Error::new(
+                    ErrorKind::Unsupported,
+                    "only ws:// servers can be reached from this build",
+                )));
+            }
+
+            let (opened, opening) = mpsc::channel();
+            std::thread::spawn(move || {
+                // Nobody is listening if the connection was dropped meanwhile
+                let _ = opened.send(open(request));
+            });
+
+            let mut connection = Self {
+                socket: Socket::Connecting(opening),
+                outbox: Vec::new(),
+            };
+            connection.send(&ClientMessage::Hello {
+                version: PROTOCOL_VERSION,
+            })?;
+            Ok(connection)
+        }
+
+        /// The open socket, once the background thread has opened it. Held
+        /// back messages go out first.
+        fn socket(&mut self) -> Result<Option<&mut WebSocket<TcpStream>>, NetError> {
+            if let Socket::Connecting(opening) = &self.socket {
+                let mut socket = match opening.try_recv() {
+                    Ok(opened) => opened?,
+                    Err(TryRecvError::Empty) => return Ok(None),
+                    Err(TryRecvError::Disconnected) => return Err(NetError::Closed),
+                };
+                for message in self.outbox.drain(..) {
+                    write_message(&mut socket, &message)?;
+                }
+                self.socket = Socket::Open(Box::new(socket));
+            }
+            match &mut self.socket {
+                Socket::Open(socket) => Ok(Some(&mut **socket)),
+                Socket::Connecting(_) => Ok(None),
+            }
+        }
+
+        pub fn send(&mut self, message: &ClientMessage) -> Result<(), NetError> {
+            match self.socket()? {
+                Some(socket) => write_message(socket, message),
+                None => {
+                    self.outbox.push(message.clone());
+                    Ok(())
+                }
+            }
+        }
+
+        pub fn receive(&mut self, messages: &mut Vec<ServerMessage>) -> Result<(), NetError> {
+            match self.socket()? {
+                Some(socket) => read_messages(socket, messages),
+                None => Ok(()),
+            }
+        }
+    }
+}
+
+#[cfg(target_arch = "wasm32")]
+mod platform {
+    use std::cell::RefCell;
+    use std::collections::VecDeque;
+    use std::rc::Rc;
+
+    use wasm_bindgen::closure::Closure;
+    use wasm_bindgen::JsCast;
+    use web_sys::{MessageEvent, WebSocket};
+
+    use crate::net::{decode, encode, ClientMessage, NetError, ServerMessage, PROTOCOL_VERSION};
+
+    /// Filled by the socket's callbacks, drained by the game loop
+    #[derive(Default)]
+    struct Inbox {
+        texts: VecDeque<String>,
+        closed: bool,
+    }
+
+    pub struct Connection {
+        socket: WebSocket,
+        inbox: Rc<RefCell<Inbox>>,
+        /// Sent once the socket opens
+        outbox: Vec<String>,
+        // Kept alive for as long as the socket may call them
+        _on_message: Closure<dyn FnMut(MessageEvent)>,
+        _on_close: Closure<dyn FnMut(web_sys::Event)>,
+    }
+
+    impl Connection {
+        /// Start connecting to the server at `url`; messages sent before the
+        /// socket opens are held back until it does
+        pub fn connect(url: &str) -> Result<Self, NetError> {
+            let socket =
+                WebSocket::new(url).map_err(|err| NetError::Browser(format!("{err:?}")))?;
+            let inbox = Rc::new(RefCell::new(Inbox::default()));
+
+            let messages = inbox.clone();
+            let on_message = Closure::<dyn FnMut(MessageEvent)>::new(move |event: MessageEvent| {
+                if let Some(text) = event.data().as_string() {
+                    messages.borrow_mut().texts.push_back(text);
+                }
+            });
+            socket.set_onmessage(Some(on_message.as_ref().unchecked_ref()));
+
+            let closed = inbox.clone();
+            let on_close = Closure::<dyn FnMut(web_sys::Event)>::new(move |_| {
+                closed.borrow_mut().closed = true;
+            });
+            socket.set_onclose(Some(on_close.as_ref().unchecked_ref()));
+            socket.set_onerror(Some(on_close.as_ref().unchecked_ref()));
+
+            let mut connection = Self {
+                socket,
+                inbox,
+                outbox: Vec::new(),
+                _on_message: on_message,
+                _on_close: on_close,
+            };
+            connection.send(&ClientMessage::Hello {
+                version: PROTOCOL_VERSION,
+            })?;
+            Ok(connection)
+        }
+
+        fn flush(&mut self) -> Result<(), NetError> {
+            if self.socket.ready_state() != WebSocket::OPEN {
+                return Ok(());
+            }
+            for text in self.outbox.drain(..) {
+                self.socket
+                    .send_with_str(&text)
+                    .map_err(|err| NetError::Browser(format!("{err:?}")))?;
+            }
+            Ok(())
+        }
+
+        pub fn send(&mut self, message: &ClientMessage) -> Result<(), NetError> {
+            self.outbox.push(encode(message));
+            self.flush()
+        }
+
+        pub fn receive(&mut self, messages: &mut Vec<ServerMessage>) -> Result<(), NetError> {
+            self.flush()?;
+            let mut inbox = self.inbox.borrow_mut();
+            while let Some(text) = inbox.texts.pop_front() {
+                messages.push(decode(&text)?);
+            }
+            if inbox.closed {
+                return Err(NetError::Closed);
+            }
+            Ok(())
+        }
+    }
+
+    impl Drop for Connection {
+        fn drop(&mut self) {
+            self.socket.set_onmessage(None);
+            self.socket.set_onclose(None);
+            self.socket.set_onerror(None);
+            let _ = self.socket.close();
+        }
+    }
+}
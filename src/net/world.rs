@@ -0,0 +1,296 @@
+//! Persistent gardens: what a garden server keeps of its garden between
+//! sessions, so bees joining later find it as others left it.
+
+use std::fmt;
+use std::path::Path;
+use std::time::Duration;
+
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::flower::{CacheSpawnPoint, FlowerHead};
+use crate::headless::HeadlessGame;
+use crate::level::{LevelDefinition, LevelIndex};
+
+/// Bump when the layout of `WorldState` changes
+pub const WORLD_VERSION: u32 = 1;
+
+/// Ticks between writes of a persistent garden (every ten seconds)
+pub const WORLD_SAVE_INTERVAL: u32 = 600;
+
+/// A persistent garden as of its last write
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct WorldState {
+    pub version: u32,
+    /// `id` of the level the garden grows in
+    pub level: String,
+    /// Rizz of each flower head, in `LevelIndex` order
+    pub rizz: Vec<f32>,
+    /// Each stem cache, in `LevelIndex` order
+    pub caches: Vec<CacheState>,
+    /// Pollen bees have carried away from this garden, ever
+    pub harvested: u64,
+}
+
+impl Default for WorldState {
+    fn default() -> Self {
+        Self {
+            version: WORLD_VERSION,
+            level: String::new(),
+            rizz: Vec::new(),
+            caches: Vec::new(),
+            harvested: 0,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CacheState {
+    pub active: bool,
+    /// Seconds until an emptied cache fills again
+    pub respawn_in: f32,
+}
+
+/// Just enough of a world file to find out which layout the rest is in
+#[derive(Deserialize)]
+struct WorldHeader {
+    #[serde(default)]
+    version: u32,
+}
+
+impl WorldState {
+    /// Read the heads and caches of the garden `game` is playing
+    pub fn capture(game: &mut HeadlessGame, harvested: u64) -> Self {
+        let level = game
+            .level()
+            .map(|level| level.id.clone())
+            .unwrap_or_default();
+        let world = game.world_mut();
+
+        let mut heads: Vec<(usize, f32)> = world
+            .query::<(&LevelIndex, &FlowerHead)>()
+            .iter(world)
+            .map(|(index, head)| (index.0, head.rizz))
+            .collect();
+        heads.sort_by_key(|(index, _)| *index);
+
+        let mut caches: Vec<(usize, CacheState)> = world
+            .query::<(&LevelIndex, &CacheSpawnPoint)>()
+            .iter(world)
+            .map(|(index, cache)| {
+                let respawn_in = if cache.is_active {
+                    0.0
+                } else {
+                    cache.respawn_timer.remaining_secs()
+                };
+                let state = CacheState {
+                    active: cache.is_active,
+                    respawn_in,
+                };
+                (index.0, state)
+            })
+            .collect();
+        caches.sort_by_key(|(index, _)| *index);
+
+        Self {
+            version: WORLD_VERSION,
+            level,
+            rizz: heads.into_iter().map(|(_, rizz)| rizz).collect(),
+            caches: caches.into_iter().map(|(_, cache)| cache).collect(),
+            harvested,
+        }
+    }
+
+    /// Put the heads and caches of a freshly loaded garden back as they were
+    pub fn restore(&self, game: &mut HeadlessGame) {
+        let world = game.world_mut();
+
+        for (index, mut head) in world
+            .query::<(&LevelIndex, &mut FlowerHead)>()
+            .iter_mut(world)
+        {
+            if let Some(rizz) = self.rizz.get(index.0) {
+                head.rizz = *rizz;
+            }
+        }
+
+        for (index, mut cache, mut visibility) in world
+            .query::<(&LevelIndex, &mut CacheSpawnPoint, &mut Visibility)>()
+            .iter_mut(world)
+        {
+            let Some(state) = self.caches.get(index.0) else {
+                continue;
+            };
+            cache.is_active = state.active;
+            cache.respawn_timer.reset();
+            if !state.active {
+                let duration = cache.respawn_timer.duration();
+                let waited = duration.saturating_sub(Duration::from_secs_f32(state.respawn_in));
+                cache.respawn_timer.set_elapsed(waited);
+            }
+            *visibility = if state.active {
+                Visibility::Visible
+            } else {
+                Visibility::Hidden
+            };
+        }
+    }
+
+    /// Parse a world file, which must be for `level`
+    pub fn from_ron(source: &str, level: &LevelDefinition) -> Result<Self, WorldError> {
+        let header: WorldHeader = ron::de::from_str(source)?;
+        if header.version != WORLD_VERSION {
+            return Err(WorldError::Version(header.version));
+        }
+        let state: Self = ron::de::from_str(source)?;
+        if state.level != level.id {
+            return Err(WorldError::OtherGarden(state.level));
+        }
+        // Hand-edited or damaged files could otherwise crash `restore`
+        if !state.rizz.iter().all(|rizz| rizz.is_finite()) {
+            return Err(WorldError::Invalid("rizz must be a number".to_string()));
+        }
+        if !state
+            .caches
+            .iter()
+            .all(|cache| cache.respawn_in.is_finite() && cache.respawn_in >= 0.0)
+        {
+            return Err(WorldError::Invalid(
+                "cache respawn_in must be a number >= 0".to_string(),
+            ));
+        }
+        Ok(state)
+    }
+
+    pub fn to_ron(&self) -> String {
+        ron::ser::to_string_pretty(self, ron::ser::PrettyConfig::default())
+            .expect("world state always serializes")
+    }
+
+    /// Read the world file at `path` for `level`, or `None` if there isn't
+    /// one yet
+    pub fn load(path: &Path, level: &LevelDefinition) -> Result<Option<Self>, WorldError> {
+        match std::fs::read_to_string(path) {
+            Ok(source) => Self::from_ron(&source, level).map(Some),
+            Err(err) if err.kind() == std::io::ErrorKind::NotFound => Ok(None),
+            Err(err) => Err(err.into()),
+        }
+    }
+
+    /// Write to `path`, through a temporary file so a crash mid-write leaves
+    /// the previous state intact
+    pub fn save(&self, path: &Path) -> Result<(), WorldError> {
+        let temporary = path.with_extension("ron.tmp");
+        std::fs::write(&temporary, self.to_ron())?;
+        std::fs::rename(&temporary, path)?;
+        Ok(())
+    }
+}
+
+/// Reasons a world file can't be used
+#[derive(Debug)]
+pub enum WorldError {
+    Io(std::io::Error),
+    Parse(ron::error::SpannedError),
+    /// Written by a newer or older build than this one
+    Version(u32),
+    /// Belongs to the garden with this level id
+    OtherGarden(String),
+    /// Holds a value the garden can't take
+    Invalid(String),
+}
+
+impl fmt::Display for WorldError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            WorldError::Io(err) => write!(f, "could not access world file: {err}"),
+            WorldError::Parse(err) => write!(f, "could not parse world file: {err}"),
+            WorldError::Version(version) => write!(
+                f,
+                "world file is version {version}, this build understands {WORLD_VERSION}"
+            ),
+            WorldError::OtherGarden(level) => {
+                write!(f, "world file belongs to the garden '{level}'")
+            }
+            WorldError::Invalid(problem) => write!(f, "invalid world file: {problem}"),
+        }
+    }
+}
+
+impl std::error::Error for WorldError {}
+
+impl From<std::io::Error> for WorldError {
+    fn from(err: std::io::Error) -> Self {
+        WorldError::Io(err)
+    }
+}
+
+impl From<ron::error::SpannedError> for WorldError {
+    fn from(err: ron::error::SpannedError) -> Self {
+        WorldError::Parse(err)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn meadow() -> LevelDefinition {
+        LevelDefinition::from_ron(include_bytes!("../../assets/levels/meadow.level.ron")).unwrap()
+    }
+
+    #[test]
+    fn world_state_round_trips_through_ron() {
+        let state = WorldState {
+            level: "meadow".to_string(),
+            rizz: vec![12.5, 0.0],
+            caches: vec![CacheState {
+                active: false,
+                respawn_in: 3.5,
+            }],
+            harvested: 240,
+            ..default()
+        };
+        assert_eq!(
+            WorldState::from_ron(&state.to_ron(), &meadow()).unwrap(),
+            state
+        );
+    }
+
+    #[test]
+    fn another_gardens_world_is_refused() {
+        let state = WorldState {
+            level: "orchard".to_string(),
+            ..default()
+        };
+        assert!(matches!(
+            WorldState::from_ron(&state.to_ron(), &meadow()),
+            Err(WorldError::OtherGarden(_))
+        ));
+
+        let source = format!("(version: {}, level: \"meadow\")", WORLD_VERSION + 1);
+        assert!(matches!(
+            WorldState::from_ron(&source, &meadow()),
+            Err(WorldError::Version(_))
+        ));
+    }
+
+    #[test]
+    fn impossible_respawn_times_are_refused() {
+        for respawn_in in [-1.0, f32::NAN] {
+            let state = WorldState {
+                level: "meadow".to_string(),
+                caches: vec![CacheState {
+                    active: false,
+                    respawn_in,
+                }],
+                ..default()
+            };
+            assert!(matches!(
+                WorldState::from_ron(&state.to_ron(), &meadow()),
+                Err(WorldError::Invalid(_))
+            ));
+        }
+    }
+}
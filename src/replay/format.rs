@@ -0,0 +1,244 @@
+use std::fmt;
+
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::bee::{Emote, PlayerInput};
+use crate::game::Difficulty;
+
+/// Bumped whenever the file layout or the meaning of a tick changes
+pub const REPLAY_VERSION: u32 = 5;
+
+/// Oldest version that still plays back correctly. Version 5 moved flower
+/// heads and keeps everything inside the garden, so older replays desync.
+const OLDEST_PLAYABLE_VERSION: u32 = REPLAY_VERSION;
+
+/// Everything needed to play a round again: where it was played, the random
+/// seed, how many bees played, and each player's input on every tick that
+/// had any.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Replay {
+    pub version: u32,
+    pub seed: u64,
+    pub level_id: String,
+    pub level_path: String,
+    pub difficulty: Difficulty,
+    #[serde(default = "solo")]
+    pub players: usize,
+    pub frames: Vec<ReplayFrame>,
+}
+
+fn solo() -> usize {
+    1
+}
+
+/// Input one player applied on one simulation tick
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ReplayFrame {
+    pub tick: u32,
+    #[serde(default, skip_serializing_if = "is_first_player")]
+    pub player: usize,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub target: Option<Vec2>,
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub wiggle: bool,
+    #[serde(default, skip_serializing_if = "is_zero")]
+    pub direction: Vec2,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub emote: Option<Emote>,
+}
+
+fn is_first_player(player: &usize) -> bool {
+    *player == 0
+}
+
+fn is_zero(direction: &Vec2) -> bool {
+    *direction == Vec2::ZERO
+}
+
+impl ReplayFrame {
+    pub fn new(tick: u32, player: usize, input: &PlayerInput) -> Self {
+        Self {
+            tick,
+            player,
+            target: input.move_target,
+            wiggle: input.wiggle,
+            direction: input.direction,
+            emote: input.emote,
+        }
+    }
+
+    pub fn input(&self) -> PlayerInput {
+        PlayerInput {
+            move_target: self.target,
+            wiggle: self.wiggle,
+            direction: self.direction,
+            emote: self.emote,
+        }
+    }
+}
+
+impl Replay {
+    /// Frames recorded for `tick`, one per player who gave input; frames
+    /// are kept in tick order
+    pub fn frames_at(&self, tick: u32) -> &[ReplayFrame] {
+        let start = self.frames.partition_point(|frame| frame.tick < tick);
+        let end = self.frames.partition_point(|frame| frame.tick <= tick);
+        &self.frames[start..end]
+    }
+
+    /// Input `player` gave on `tick`
+    pub fn input_at(&self, tick: u32, player: usize) -> PlayerInput {
+        self.frames_at(tick)
+            .iter()
+            .find(|frame| frame.player == player)
+            .map(ReplayFrame::input)
+            .unwrap_or_default()
+    }
+
+    pub fn from_ron(source: &str) -> Result<Self, ReplayError> {
+        let replay: Replay = ron::from_str(source)?;
+        if !(OLDEST_PLAYABLE_VERSION..=REPLAY_VERSION).contains(&replay.version) {
+            return Err(ReplayError::Version(replay.version));
+        }
+        Ok(replay)
+    }
+
+    /// Single-line RON; replays are long and only read by the game
+    pub fn to_ron(&self) -> String {
+        ron::to_string(self).expect("replays always serialize")
+    }
+}
+
+/// Reasons a replay file can be rejected
+#[derive(Debug)]
+pub enum ReplayError {
+    Io(std::io::Error),
+    Parse(ron::error::SpannedError),
+    Version(u32),
+}
+
+impl fmt::Display for ReplayError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ReplayError::Io(err) => write!(f, "could not read replay: {err}"),
+            ReplayError::Parse(err) => write!(f, "could not parse replay: {err}"),
+            ReplayError::Version(version) => {
+                write!(
+                    f,
+                    "replay version {version} is not supported (this build plays "
+                )?;
+                if OLDEST_PLAYABLE_VERSION == REPLAY_VERSION {
+                    write!(f, "version {REPLAY_VERSION})")
+                } else {
+                    write!(f, "versions {OLDEST_PLAYABLE_VERSION} to {REPLAY_VERSION})")
+                }
+            }
+        }
+    }
+}
+
+impl std::error::Error for ReplayError {}
+
+impl From<std::io::Error> for ReplayError {
+    fn from(err: std::io::Error) -> Self {
+        ReplayError::Io(err)
+    }
+}
+
+impl From<ron::error::SpannedError> for ReplayError {
+    fn from(err: ron::error::SpannedError) -> Self {
+        ReplayError::Parse(err)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sample() -> Replay {
+        Replay {
+            version: REPLAY_VERSION,
+            seed: 99,
+            level_id: "meadow".to_string(),
+            level_path: "levels/meadow.level.ron".to_string(),
+            difficulty: Difficulty::HayFever,
+            players: 2,
+            frames: vec![
+                ReplayFrame {
+                    tick: 3,
+                    player: 0,
+                    target: Some(Vec2::new(10.0, -20.0)),
+                    wiggle: false,
+                    direction: Vec2::ZERO,
+                    emote: None,
+                },
+                ReplayFrame {
+                    tick: 40,
+                    player: 0,
+                    target: None,
+                    wiggle: true,
+                    direction: Vec2::new(0.0, -1.0),
+                    emote: None,
+                },
+                ReplayFrame {
+                    tick: 40,
+                    player: 1,
+                    target: Some(Vec2::new(5.0, 5.0)),
+                    wiggle: false,
+                    direction: Vec2::ZERO,
+                    emote: None,
+                },
+            ],
+        }
+    }
+
+    #[test]
+    fn replay_round_trips_through_ron() {
+        let replay = sample();
+        assert_eq!(Replay::from_ron(&replay.to_ron()).unwrap(), replay);
+    }
+
+    #[test]
+    fn ticks_without_frames_have_no_input() {
+        let replay = sample();
+        assert!(replay.input_at(4, 0).is_empty());
+        assert!(replay.input_at(40, 0).wiggle);
+        assert_eq!(
+            replay.input_at(3, 0).move_target,
+            Some(Vec2::new(10.0, -20.0))
+        );
+    }
+
+    #[test]
+    fn each_player_has_their_own_frames() {
+        let replay = sample();
+        assert_eq!(replay.frames_at(40).len(), 2);
+        assert!(!replay.input_at(40, 1).wiggle);
+        assert_eq!(
+            replay.input_at(40, 1).move_target,
+            Some(Vec2::new(5.0, 5.0))
+        );
+        assert!(replay.input_at(3, 1).is_empty());
+    }
+
+    #[test]
+    fn replays_from_before_the_head_layout_are_rejected() {
+        let source = r#"(version: 4, seed: 1, level_id: "meadow", level_path: "levels/meadow.level.ron", difficulty: Normal, frames: [(tick: 5, wiggle: true)])"#;
+        let err = Replay::from_ron(source).unwrap_err();
+        assert!(matches!(err, ReplayError::Version(4)));
+        assert!(err
+            .to_string()
+            .contains(&format!("version {REPLAY_VERSION}")));
+    }
+
+    #[test]
+    fn other_versions_are_rejected() {
+        let mut replay = sample();
+        replay.version = REPLAY_VERSION + 1;
+        assert!(matches!(
+            Replay::from_ron(&replay.to_ron()),
+            Err(ReplayError::Version(_))
+        ));
+    }
+}
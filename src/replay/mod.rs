@@ -0,0 +1,42 @@
+mod format;
+mod playback;
+mod record;
+
+pub use format::*;
+pub use playback::*;
+pub use record::*;
+
+use bevy::prelude::*;
+
+use crate::bee::apply_player_input;
+use crate::game::{AppState, GameState};
+
+pub struct ReplayPlugin;
+
+impl Plugin for ReplayPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<ReplayRecorder>()
+            .add_event::<PlayReplayEvent>()
+            .add_systems(Update, start_replay)
+            .add_systems(
+                FixedUpdate,
+                (
+                    play_back_input.run_if(resource_exists::<ReplayPlayback>),
+                    record_player_input.run_if(not(resource_exists::<ReplayPlayback>)),
+                )
+                    .before(apply_player_input)
+                    .run_if(in_state(GameState::Playing)),
+            )
+            .add_systems(
+                OnEnter(GameState::Won),
+                (save_replay, finish_replay).chain(),
+            )
+            .add_systems(
+                OnEnter(GameState::Lost),
+                (save_replay, finish_replay).chain(),
+            );
+
+        #[cfg(not(target_arch = "wasm32"))]
+        app.add_systems(OnExit(AppState::Boot), play_launch_replay);
+    }
+}
@@ -0,0 +1,98 @@
+use bevy::prelude::*;
+
+use super::{Replay, LAST_REPLAY_KEY};
+use crate::bee::PlayerInputs;
+use crate::game::{AppState, Difficulty, GameRng, GameState, SimulationTick, Team};
+use crate::level::LoadLevelEvent;
+use crate::storage;
+
+/// Replay being watched; while present it replaces live player input
+#[derive(Resource, Debug)]
+pub struct ReplayPlayback {
+    pub replay: Replay,
+}
+
+/// Request to restart the recorded garden and play a replay in it
+#[derive(Event)]
+pub struct PlayReplayEvent(pub Replay);
+
+/// The most recently saved round, if there is a readable one
+pub fn load_last_replay() -> Option<Replay> {
+    let source = storage::load(LAST_REPLAY_KEY)?;
+    Replay::from_ron(&source)
+        .inspect_err(|err| warn!("Ignoring saved replay: {}", err))
+        .ok()
+}
+
+/// Play the file given as `--replay <path>` on the command line
+#[cfg(not(target_arch = "wasm32"))]
+pub fn play_launch_replay(mut events: EventWriter<PlayReplayEvent>) {
+    let Some(path) = crate::launch::option("replay") else {
+        return;
+    };
+
+    let replay = std::fs::read_to_string(&path)
+        .map_err(super::ReplayError::from)
+        .and_then(|source| Replay::from_ron(&source));
+    match replay {
+        Ok(replay) => {
+            events.send(PlayReplayEvent(replay));
+        }
+        Err(err) => error!("Could not play {}: {}", path, err),
+    }
+}
+
+/// Restore the recorded seed, difficulty and garden, then start the round
+pub fn start_replay(
+    mut commands: Commands,
+    mut events: EventReader<PlayReplayEvent>,
+    mut difficulty: ResMut<Difficulty>,
+    mut team: ResMut<Team>,
+    mut load_level: EventWriter<LoadLevelEvent>,
+    mut next_app_state: ResMut<NextState<AppState>>,
+    mut next_state: ResMut<NextState<GameState>>,
+) {
+    let Some(PlayReplayEvent(replay)) = events.read().last() else {
+        return;
+    };
+
+    info!(
+        "Playing replay of '{}' with seed {} ({} input frames)",
+        replay.level_id,
+        replay.seed,
+        replay.frames.len()
+    );
+    commands.insert_resource(GameRng::pinned(replay.seed));
+    *difficulty = replay.difficulty;
+    team.players = replay.players;
+    load_level.send(LoadLevelEvent {
+        path: replay.level_path.clone(),
+    });
+    commands.insert_resource(ReplayPlayback {
+        replay: replay.clone(),
+    });
+    next_app_state.set(AppState::InGame);
+    next_state.set(GameState::Playing);
+}
+
+/// Overwrite whatever the player did this tick with the recorded input
+pub fn play_back_input(
+    playback: Res<ReplayPlayback>,
+    tick: Res<SimulationTick>,
+    mut inputs: ResMut<PlayerInputs>,
+) {
+    *inputs = PlayerInputs::default();
+    for frame in playback.replay.frames_at(tick.0) {
+        *inputs.player_mut(frame.player) = frame.input();
+    }
+}
+
+/// Hand control back to the player once the replayed round ends
+pub fn finish_replay(mut commands: Commands, playback: Option<Res<ReplayPlayback>>) {
+    if playback.is_none() {
+        return;
+    }
+    commands.remove_resource::<ReplayPlayback>();
+    commands.insert_resource(GameRng::default());
+    info!("Replay finished");
+}
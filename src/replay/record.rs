@@ -0,0 +1,65 @@
+use bevy::prelude::*;
+
+use super::{Replay, ReplayFrame, ReplayPlayback, REPLAY_VERSION};
+use crate::bee::PlayerInputs;
+use crate::game::{Difficulty, GameRng, SimulationTick, Team};
+use crate::level::{ActiveLevel, CurrentLevel};
+use crate::storage;
+
+/// Storage key the most recent round is saved under
+pub const LAST_REPLAY_KEY: &str = "last_replay";
+
+/// Input captured since the current garden was spawned
+#[derive(Resource, Debug, Default)]
+pub struct ReplayRecorder {
+    pub frames: Vec<ReplayFrame>,
+}
+
+/// Capture this tick's input before the bees act on it
+pub fn record_player_input(
+    mut recorder: ResMut<ReplayRecorder>,
+    inputs: Res<PlayerInputs>,
+    tick: Res<SimulationTick>,
+) {
+    if tick.0 == 0 {
+        recorder.frames.clear();
+    }
+    for (player, input) in inputs.players.iter().enumerate() {
+        if !input.is_empty() {
+            recorder
+                .frames
+                .push(ReplayFrame::new(tick.0, player, input));
+        }
+    }
+}
+
+/// Save the round that just ended so it can be watched or attached to a bug report
+pub fn save_replay(
+    recorder: Res<ReplayRecorder>,
+    rng: Res<GameRng>,
+    difficulty: Res<Difficulty>,
+    team: Res<Team>,
+    active: Option<Res<ActiveLevel>>,
+    current: Option<Res<CurrentLevel>>,
+    playback: Option<Res<ReplayPlayback>>,
+) {
+    if playback.is_some() {
+        return;
+    }
+    let (Some(active), Some(path)) = (active, current.and_then(|c| c.handle.path().cloned()))
+    else {
+        return;
+    };
+
+    let replay = Replay {
+        version: REPLAY_VERSION,
+        seed: rng.seed(),
+        level_id: active.id.clone(),
+        level_path: path.to_string(),
+        difficulty: *difficulty,
+        players: team.players,
+        frames: recorder.frames.clone(),
+    };
+    storage::save(LAST_REPLAY_KEY, &replay.to_ron());
+    info!("Replay saved to {}", storage::location(LAST_REPLAY_KEY));
+}
@@ -0,0 +1,212 @@
+use std::collections::BTreeMap;
+use std::fmt;
+
+use serde::{Deserialize, Serialize};
+
+use super::{Leaderboards, RunRecord, Settings};
+use crate::achievements::UnlockedAchievements;
+use crate::controls::Bindings;
+use crate::game::Difficulty;
+use crate::level::CampaignProgress;
+
+/// Bump when the layout of `SaveData` changes, and teach `SaveData::from_ron`
+/// to upgrade the previous layout
+pub const SAVE_VERSION: u32 = 2;
+
+/// Everything that survives between sessions, stored as one versioned document
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct SaveData {
+    pub version: u32,
+    pub settings: Settings,
+    pub progress: CampaignProgress,
+    pub records: Leaderboards,
+    pub achievements: UnlockedAchievements,
+    pub bindings: Bindings,
+}
+
+impl Default for SaveData {
+    fn default() -> Self {
+        Self {
+            version: SAVE_VERSION,
+            settings: Settings::default(),
+            progress: CampaignProgress::default(),
+            records: Leaderboards::default(),
+            achievements: UnlockedAchievements::default(),
+            bindings: Bindings::default(),
+        }
+    }
+}
+
+/// Just enough of a save to find out which layout the rest is in
+#[derive(Deserialize)]
+struct SaveHeader {
+    version: u32,
+}
+
+impl SaveData {
+    /// Parse a save written by this or any earlier version of the game
+    pub fn from_ron(source: &str) -> Result<Self, SaveError> {
+        let header: SaveHeader = ron::de::from_str(source)?;
+        match header.version {
+            SAVE_VERSION => Ok(ron::de::from_str(source)?),
+            1 => Ok(ron::de::from_str::<SaveDataV1>(source)?.into()),
+            version => Err(SaveError::Version(version)),
+        }
+    }
+
+    /// Build a save from the campaign progress file written before settings
+    /// and best times were saved
+    pub fn from_legacy_progress(source: &str) -> Result<Self, SaveError> {
+        Ok(Self {
+            progress: ron::de::from_str(source)?,
+            ..Self::default()
+        })
+    }
+
+    pub fn to_ron(&self) -> String {
+        ron::ser::to_string_pretty(self, ron::ser::PrettyConfig::default())
+            .expect("save data always serializes")
+    }
+}
+
+/// Version 1 kept only the best time per garden and difficulty
+#[derive(Deserialize)]
+#[serde(default)]
+struct SaveDataV1 {
+    settings: Settings,
+    progress: CampaignProgress,
+    best_times: BTreeMap<String, BTreeMap<Difficulty, f32>>,
+}
+
+impl Default for SaveDataV1 {
+    fn default() -> Self {
+        let SaveData {
+            settings, progress, ..
+        } = SaveData::default();
+        Self {
+            settings,
+            progress,
+            best_times: BTreeMap::new(),
+        }
+    }
+}
+
+impl From<SaveDataV1> for SaveData {
+    fn from(old: SaveDataV1) -> Self {
+        let mut records = Leaderboards::default();
+        for (level_id, times) in old.best_times {
+            for (difficulty, seconds) in times {
+                let record = RunRecord {
+                    seconds,
+                    players: 1,
+                    pollen: None,
+                    sneezes: None,
+                    seed: None,
+                };
+                records.submit(&level_id, difficulty, record);
+            }
+        }
+
+        Self {
+            settings: old.settings,
+            progress: old.progress,
+            records,
+            ..Self::default()
+        }
+    }
+}
+
+/// Reasons a save can't be read
+#[derive(Debug)]
+pub enum SaveError {
+    Parse(ron::error::SpannedError),
+    /// Written by a newer build of the game than this one
+    Version(u32),
+}
+
+impl fmt::Display for SaveError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SaveError::Parse(err) => write!(f, "could not parse save: {err}"),
+            SaveError::Version(version) => write!(
+                f,
+                "save is version {version}, this build understands up to {SAVE_VERSION}"
+            ),
+        }
+    }
+}
+
+impl std::error::Error for SaveError {}
+
+impl From<ron::error::SpannedError> for SaveError {
+    fn from(err: ron::error::SpannedError) -> Self {
+        SaveError::Parse(err)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn save_round_trips_through_ron() {
+        let mut save = SaveData::default();
+        save.settings.reduce_motion = true;
+        save.progress.completed.insert("meadow".to_string());
+        save.records.submit(
+            "meadow",
+            Difficulty::Cozy,
+            RunRecord {
+                seconds: 42.5,
+                players: 1,
+                pollen: Some(50),
+                sneezes: Some(1),
+                seed: Some(7),
+            },
+        );
+
+        assert_eq!(SaveData::from_ron(&save.to_ron()).unwrap(), save);
+    }
+
+    #[test]
+    fn legacy_progress_is_imported() {
+        let save = SaveData::from_legacy_progress(r#"(completed: ["meadow"])"#).unwrap();
+        assert_eq!(save.version, SAVE_VERSION);
+        assert!(save.progress.completed.contains("meadow"));
+    }
+
+    #[test]
+    fn newer_saves_are_rejected() {
+        let source = format!("(version: {})", SAVE_VERSION + 1);
+        assert!(matches!(
+            SaveData::from_ron(&source),
+            Err(SaveError::Version(_))
+        ));
+    }
+
+    #[test]
+    fn saves_without_a_version_are_a_parse_error() {
+        assert!(matches!(
+            SaveData::from_ron("(settings: (reduce_motion: true))"),
+            Err(SaveError::Parse(_))
+        ));
+    }
+
+    #[test]
+    fn version_one_best_times_become_records() {
+        let source = r#"(
+            version: 1,
+            settings: (reduce_motion: true),
+            best_times: {"meadow": {Normal: 55.0}},
+        )"#;
+        let save = SaveData::from_ron(source).unwrap();
+        assert_eq!(save.version, SAVE_VERSION);
+        assert!(save.settings.reduce_motion);
+
+        let board = save.records.get("meadow", Difficulty::Normal, false);
+        assert_eq!(board.len(), 1);
+        assert_eq!(board[0].seconds, 55.0);
+        assert_eq!(board[0].seed, None);
+    }
+}
@@ -0,0 +1,166 @@
+//! Settings, key bindings, campaign progress, leaderboards and achievements,
+//! kept between sessions in one versioned save through `storage`.
+
+mod data;
+mod records;
+mod settings;
+
+pub use data::*;
+pub use records::*;
+pub use settings::*;
+
+use bevy::prelude::*;
+
+use crate::achievements::UnlockedAchievements;
+use crate::ai::PlayerBee;
+use crate::bee::{CollectedPollen, SneezeCount};
+use crate::controls::Bindings;
+use crate::game::{Difficulty, GameRng, GameState, SessionTimer};
+use crate::level::{ActiveLevel, CampaignProgress};
+use crate::replay::ReplayPlayback;
+use crate::storage;
+
+/// Storage key of the save document
+pub const SAVE_KEY: &str = "save";
+
+/// Where campaign progress lived before there was a save document
+const LEGACY_PROGRESS_KEY: &str = "campaign_progress";
+
+/// Where an unreadable save is copied before it gets overwritten
+const BACKUP_KEY: &str = "save.backup";
+
+pub struct SavePlugin;
+
+impl Plugin for SavePlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(PreStartup, load_save)
+            .add_systems(OnEnter(GameState::Won), record_run)
+            .add_systems(
+                Update,
+                (
+                    sync_difficulty_setting,
+                    apply_ui_scale,
+                    apply_virtual_joystick,
+                    write_save,
+                )
+                    .chain(),
+            );
+    }
+}
+
+/// Read the save (or the older progress file) and hand its parts out as
+/// resources. A save that can't be read is backed up rather than lost.
+pub fn load_save(mut commands: Commands) {
+    let save = match storage::load(SAVE_KEY) {
+        Some(source) => SaveData::from_ron(&source).unwrap_or_else(|err| {
+            warn!(
+                "Starting from a fresh save - {}. The old one was copied to {}",
+                err,
+                storage::location(BACKUP_KEY)
+            );
+            storage::save(BACKUP_KEY, &source);
+            SaveData::default()
+        }),
+        None => storage::load(LEGACY_PROGRESS_KEY)
+            .and_then(|source| SaveData::from_legacy_progress(&source).ok())
+            .unwrap_or_default(),
+    };
+
+    commands.insert_resource(save.settings.difficulty);
+    commands.insert_resource(save.settings);
+    commands.insert_resource(save.progress);
+    commands.insert_resource(save.records);
+    commands.insert_resource(save.achievements);
+    commands.insert_resource(save.bindings.with_missing_defaults());
+}
+
+/// Put the winning run on the solo or co-op leaderboard for this garden and
+/// difficulty, with co-op bees' pollen and sneezes added together
+#[allow(clippy::too_many_arguments)]
+pub fn record_run(
+    mut commands: Commands,
+    active: Option<Res<ActiveLevel>>,
+    timer: Res<SessionTimer>,
+    difficulty: Res<Difficulty>,
+    rng: Res<GameRng>,
+    players: Query<(&CollectedPollen, Option<&SneezeCount>), With<PlayerBee>>,
+    playback: Option<Res<ReplayPlayback>>,
+    mut records: ResMut<Leaderboards>,
+) {
+    commands.remove_resource::<LastRun>();
+
+    // Watching a replay doesn't earn a place on the board
+    if playback.is_some() {
+        return;
+    }
+    let Some(active) = active else {
+        return;
+    };
+    if players.is_empty() {
+        return;
+    }
+
+    let record = RunRecord {
+        seconds: timer.elapsed,
+        players: players.iter().count(),
+        pollen: Some(players.iter().map(|(pollen, _)| pollen.count).sum()),
+        sneezes: Some(
+            players
+                .iter()
+                .flat_map(|(_, sneezes)| sneezes)
+                .map(|sneezes| sneezes.count)
+                .sum(),
+        ),
+        seed: Some(rng.seed()),
+    };
+    let co_op = record.co_op();
+    let rank = records.submit(&active.id, *difficulty, record);
+    if let Some(rank) = rank {
+        info!(
+            "New record #{} on '{}' ({}): {}",
+            rank + 1,
+            active.name,
+            difficulty.label(),
+            timer.formatted()
+        );
+    }
+    commands.insert_resource(LastRun {
+        level_id: active.id.clone(),
+        difficulty: *difficulty,
+        co_op,
+        rank,
+    });
+}
+
+/// Write the save whenever any part of it changes
+pub fn write_save(
+    settings: Res<Settings>,
+    progress: Res<CampaignProgress>,
+    records: Res<Leaderboards>,
+    achievements: Res<UnlockedAchievements>,
+    bindings: Res<Bindings>,
+) {
+    let changed = settings.is_changed()
+        || progress.is_changed()
+        || records.is_changed()
+        || achievements.is_changed()
+        || bindings.is_changed();
+    let loaded = settings.is_added()
+        && progress.is_added()
+        && records.is_added()
+        && achievements.is_added()
+        && bindings.is_added();
+    if !changed || loaded {
+        return;
+    }
+
+    let save = SaveData {
+        settings: settings.clone(),
+        progress: progress.clone(),
+        records: records.clone(),
+        achievements: achievements.clone(),
+        bindings: bindings.clone(),
+        ..default()
+    };
+    storage::save(SAVE_KEY, &save.to_ron());
+}
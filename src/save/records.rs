@@ -0,0 +1,179 @@
+use std::collections::BTreeMap;
+
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::game::Difficulty;
+
+/// Runs kept per garden, difficulty and solo or co-op
+pub const LEADERBOARD_SIZE: usize = 10;
+
+/// One winning run on the leaderboard
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RunRecord {
+    pub seconds: f32,
+    /// Bees that played. Runs from before co-op were solo.
+    #[serde(default = "solo")]
+    pub players: usize,
+    /// Pollen, sneezes and seed are unknown for times carried over from
+    /// saves that only kept the best time
+    #[serde(default)]
+    pub pollen: Option<u32>,
+    #[serde(default)]
+    pub sneezes: Option<u32>,
+    #[serde(default)]
+    pub seed: Option<u64>,
+}
+
+fn solo() -> usize {
+    1
+}
+
+impl RunRecord {
+    pub fn co_op(&self) -> bool {
+        self.players > 1
+    }
+}
+
+/// Fastest winning runs per garden id and difficulty, best first. Solo and
+/// co-op runs are ranked separately, since a team shares its pollen.
+#[derive(Resource, Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct Leaderboards {
+    /// Solo runs
+    pub levels: BTreeMap<String, BTreeMap<Difficulty, Vec<RunRecord>>>,
+    #[serde(default)]
+    pub co_op: BTreeMap<String, BTreeMap<Difficulty, Vec<RunRecord>>>,
+}
+
+impl Leaderboards {
+    pub fn get(&self, level_id: &str, difficulty: Difficulty, co_op: bool) -> &[RunRecord] {
+        let levels = if co_op { &self.co_op } else { &self.levels };
+        levels
+            .get(level_id)
+            .and_then(|boards| boards.get(&difficulty))
+            .map_or(&[], Vec::as_slice)
+    }
+
+    /// Add a run to the solo or co-op board, keeping the best
+    /// `LEADERBOARD_SIZE`. Returns its rank (0 is the best) if it made the
+    /// list.
+    pub fn submit(
+        &mut self,
+        level_id: &str,
+        difficulty: Difficulty,
+        record: RunRecord,
+    ) -> Option<usize> {
+        let levels = if record.co_op() {
+            &mut self.co_op
+        } else {
+            &mut self.levels
+        };
+        let board = levels
+            .entry(level_id.to_string())
+            .or_default()
+            .entry(difficulty)
+            .or_default();
+
+        // Ties go to the run that got there first
+        let rank = board.partition_point(|existing| existing.seconds <= record.seconds);
+        if rank >= LEADERBOARD_SIZE {
+            return None;
+        }
+        board.insert(rank, record);
+        board.truncate(LEADERBOARD_SIZE);
+        Some(rank)
+    }
+}
+
+/// The win that was just recorded, for highlighting on the end screen
+#[derive(Resource, Debug, Clone)]
+pub struct LastRun {
+    pub level_id: String,
+    pub difficulty: Difficulty,
+    pub co_op: bool,
+    pub rank: Option<usize>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn run(seconds: f32) -> RunRecord {
+        RunRecord {
+            seconds,
+            players: 1,
+            pollen: Some(50),
+            sneezes: Some(0),
+            seed: Some(1),
+        }
+    }
+
+    #[test]
+    fn runs_are_ranked_fastest_first() {
+        let mut boards = Leaderboards::default();
+        assert_eq!(
+            boards.submit("meadow", Difficulty::Normal, run(60.0)),
+            Some(0)
+        );
+        assert_eq!(
+            boards.submit("meadow", Difficulty::Normal, run(45.0)),
+            Some(0)
+        );
+        assert_eq!(
+            boards.submit("meadow", Difficulty::Normal, run(50.0)),
+            Some(1)
+        );
+        assert_eq!(
+            boards.submit("meadow", Difficulty::Normal, run(50.0)),
+            Some(2)
+        );
+
+        let times: Vec<f32> = boards
+            .get("meadow", Difficulty::Normal, false)
+            .iter()
+            .map(|record| record.seconds)
+            .collect();
+        assert_eq!(times, [45.0, 50.0, 50.0, 60.0]);
+        assert!(boards.get("meadow", Difficulty::Cozy, false).is_empty());
+    }
+
+    #[test]
+    fn only_the_top_ten_are_kept() {
+        let mut boards = Leaderboards::default();
+        for i in 0..LEADERBOARD_SIZE {
+            boards.submit("meadow", Difficulty::Normal, run(10.0 + i as f32));
+        }
+        assert_eq!(
+            boards.submit("meadow", Difficulty::Normal, run(100.0)),
+            None
+        );
+        assert_eq!(
+            boards.submit("meadow", Difficulty::Normal, run(1.0)),
+            Some(0)
+        );
+
+        let board = boards.get("meadow", Difficulty::Normal, false);
+        assert_eq!(board.len(), LEADERBOARD_SIZE);
+        assert_eq!(board.last().unwrap().seconds, 18.0);
+    }
+
+    #[test]
+    fn co_op_runs_have_their_own_board() {
+        let mut boards = Leaderboards::default();
+        boards.submit("meadow", Difficulty::Normal, run(60.0));
+        let team = RunRecord {
+            players: 2,
+            ..run(30.0)
+        };
+        assert_eq!(boards.submit("meadow", Difficulty::Normal, team), Some(0));
+
+        assert_eq!(
+            boards.get("meadow", Difficulty::Normal, false)[0].seconds,
+            60.0
+        );
+        assert_eq!(
+            boards.get("meadow", Difficulty::Normal, true)[0].seconds,
+            30.0
+        );
+    }
+}
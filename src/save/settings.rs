@@ -0,0 +1,103 @@
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::controls::VirtualJoystick;
+use crate::game::Difficulty;
+
+/// Player preferences, saved with the rest of `SaveData`
+#[derive(Resource, Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Settings {
+    /// Master volume from 0.0 to 1.0, for when the game has sound
+    pub volume: f32,
+    /// Difficulty picked for the next round
+    pub difficulty: Difficulty,
+    /// Skip screen shake and other large camera movement
+    pub reduce_motion: bool,
+    /// Multiplier for every UI element and font size
+    pub ui_scale: f32,
+    /// Drag on the left half of a touch screen to steer
+    pub virtual_joystick: bool,
+}
+
+impl Default for Settings {
+    fn default() -> Self {
+        Self {
+            volume: 0.8,
+            difficulty: Difficulty::default(),
+            reduce_motion: false,
+            ui_scale: 1.0,
+            virtual_joystick: false,
+        }
+    }
+}
+
+impl Settings {
+    pub const UI_SCALES: [f32; 3] = [1.0, 1.25, 1.5];
+
+    /// Nudge the volume by `delta`, staying within 0-100%
+    pub fn adjust_volume(&mut self, delta: f32) {
+        self.volume = ((self.volume + delta) * 10.0).round().clamp(0.0, 10.0) / 10.0;
+    }
+
+    /// Step through `UI_SCALES`, wrapping back to the smallest
+    pub fn cycle_ui_scale(&mut self) {
+        let index = Self::UI_SCALES
+            .iter()
+            .position(|scale| *scale >= self.ui_scale)
+            .unwrap_or(0);
+        self.ui_scale = Self::UI_SCALES[(index + 1) % Self::UI_SCALES.len()];
+    }
+}
+
+/// Keep the saved difficulty in step with the one picked in menus
+pub fn sync_difficulty_setting(difficulty: Res<Difficulty>, mut settings: ResMut<Settings>) {
+    if difficulty.is_changed() && settings.difficulty != *difficulty {
+        settings.difficulty = *difficulty;
+    }
+}
+
+pub fn apply_ui_scale(settings: Res<Settings>, mut ui_scale: ResMut<UiScale>) {
+    if settings.is_changed() && ui_scale.0 != settings.ui_scale {
+        ui_scale.0 = settings.ui_scale;
+    }
+}
+
+pub fn apply_virtual_joystick(settings: Res<Settings>, mut joystick: ResMut<VirtualJoystick>) {
+    if settings.is_changed() && joystick.enabled != settings.virtual_joystick {
+        joystick.enabled = settings.virtual_joystick;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn volume_stays_in_range() {
+        let mut settings = Settings {
+            volume: 0.9,
+            ..default()
+        };
+        settings.adjust_volume(0.1);
+        settings.adjust_volume(0.1);
+        assert_eq!(settings.volume, 1.0);
+
+        settings.volume = 0.1;
+        settings.adjust_volume(-0.1);
+        settings.adjust_volume(-0.1);
+        assert_eq!(settings.volume, 0.0);
+    }
+
+    #[test]
+    fn ui_scale_cycles() {
+        let mut settings = Settings::default();
+        let seen: Vec<f32> = (0..3)
+            .map(|_| {
+                settings.cycle_ui_scale();
+                settings.ui_scale
+            })
+            .collect();
+        assert_eq!(seen, [1.25, 1.5, 1.0]);
+    }
+}
@@ -0,0 +1,93 @@
+//! Small key/value store for data that must survive between sessions.
+//!
+//! Native builds write one file per key under the user's config directory;
+//! web builds use the browser's `localStorage`.
+
+/// Read the value stored under `key`, if any
+pub fn load(key: &str) -> Option<String> {
+    platform::load(key)
+}
+
+/// Where the value for `key` lives, for pointing players at the file
+pub fn location(key: &str) -> String {
+    platform::location(key)
+}
+
+/// Store `value` under `key`, replacing what was there
+pub fn save(key: &str, value: &str) {
+    if let Err(err) = platform::save(key, value) {
+        bevy::log::warn!("Could not save {}: {}", key, err);
+    }
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+mod platform {
+    use std::path::PathBuf;
+
+    /// Per-user config directory for the game, without pulling in a crate for it
+    fn storage_dir() -> PathBuf {
+        let base = std::env::var_os("XDG_CONFIG_HOME")
+            .map(PathBuf::from)
+            .or_else(|| std::env::var_os("APPDATA").map(PathBuf::from))
+            .or_else(|| {
+                std::env::var_os("HOME").map(|home| {
+                    let home = PathBuf::from(home);
+                    if cfg!(target_os = "macos") {
+                        home.join("Library/Application Support")
+                    } else {
+                        home.join(".config")
+                    }
+                })
+            })
+            .unwrap_or_else(|| PathBuf::from("."));
+        base.join("allerbees")
+    }
+
+    fn path_for(key: &str) -> PathBuf {
+        storage_dir().join(format!("{key}.ron"))
+    }
+
+    pub fn location(key: &str) -> String {
+        path_for(key).display().to_string()
+    }
+
+    pub fn load(key: &str) -> Option<String> {
+        std::fs::read_to_string(path_for(key)).ok()
+    }
+
+    /// Write next to the real file and rename it over, so a crash mid-write
+    /// leaves the old value rather than a torn one
+    pub fn save(key: &str, value: &str) -> std::io::Result<()> {
+        std::fs::create_dir_all(storage_dir())?;
+        let path = path_for(key);
+        let temporary = path.with_extension("ron.tmp");
+        std::fs::write(&temporary, value)?;
+        std::fs::rename(&temporary, path)
+    }
+}
+
+#[cfg(target_arch = "wasm32")]
+mod platform {
+    fn local_storage() -> Option<web_sys::Storage> {
+        web_sys::window()?.local_storage().ok()?
+    }
+
+    fn storage_key(key: &str) -> String {
+        format!("allerbees.{key}")
+    }
+
+    pub fn location(key: &str) -> String {
+        format!("localStorage[\"{}\"]", storage_key(key))
+    }
+
+    pub fn load(key: &str) -> Option<String> {
+        local_storage()?.get_item(&storage_key(key)).ok()?
+    }
+
+    pub fn save(key: &str, value: &str) -> Result<(), String> {
+        let storage = local_storage().ok_or("localStorage is unavailable")?;
+        storage
+            .set_item(&storage_key(key), value)
+            .map_err(|err| format!("{err:?}"))
+    }
+}
@@ -0,0 +1,51 @@
+use bevy::prelude::*;
+
+use crate::game::ConfigStatus;
+
+/// Marker for the on-screen config error banner
+#[derive(Component)]
+pub struct ConfigErrorText;
+
+pub fn setup_config_error_text(mut commands: Commands) {
+    commands.spawn((
+        ConfigErrorText,
+        Text::new(""),
+        TextFont {
+            font_size: 16.0,
+            ..default()
+        },
+        TextColor(Color::srgb(1.0, 0.4, 0.4)),
+        BackgroundColor(Color::srgba(0.0, 0.0, 0.0, 0.8)),
+        Node {
+            position_type: PositionType::Absolute,
+            left: Val::Px(20.0),
+            right: Val::Px(20.0),
+            top: Val::Px(70.0),
+            padding: UiRect::all(Val::Px(8.0)),
+            ..default()
+        },
+        Visibility::Hidden,
+    ));
+}
+
+/// Show the last config load error, or hide the banner once a good file loads
+pub fn update_config_error_text(
+    status: Res<ConfigStatus>,
+    mut banners: Query<(&mut Text, &mut Visibility), With<ConfigErrorText>>,
+) {
+    if !status.is_changed() {
+        return;
+    }
+
+    for (mut text, mut visibility) in &mut banners {
+        match &status.error {
+            Some(error) => {
+                **text = format!("Config error (using last good config):\n{error}");
+                *visibility = Visibility::Visible;
+            }
+            None => {
+                *visibility = Visibility::Hidden;
+            }
+        }
+    }
+}
@@ -0,0 +1,180 @@
+use bevy::prelude::*;
+
+use super::{menu_root, spawn_button, BUTTON_COLOR, SELECTED_BUTTON_COLOR};
+use crate::controls::{Action, Binding, Bindings};
+use crate::game::AppState;
+
+/// Buttons on the controls screen
+#[derive(Component, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ControlsAction {
+    Rebind(Action),
+    ResetDefaults,
+    Back,
+}
+
+/// Text listing what an action is bound to
+#[derive(Component, Debug, Clone, Copy, PartialEq, Eq)]
+pub struct BindingText(pub Action);
+
+/// The action waiting for the player to press its new input, if any
+#[derive(Resource, Debug, Default)]
+pub struct Rebinding {
+    pub action: Option<Action>,
+}
+
+fn describe_bindings(action: Action, bindings: &Bindings, rebinding: &Rebinding) -> String {
+    if rebinding.action == Some(action) {
+        return "press a key or button...".to_string();
+    }
+    let bound: Vec<String> = bindings
+        .get(action)
+        .iter()
+        .map(Binding::to_string)
+        .collect();
+    if bound.is_empty() {
+        "unbound".to_string()
+    } else {
+        bound.join(", ")
+    }
+}
+
+pub fn setup_controls_menu(
+    mut commands: Commands,
+    bindings: Res<Bindings>,
+    mut rebinding: ResMut<Rebinding>,
+) {
+    rebinding.action = None;
+
+    // Tighter than the other menus so every action fits on screen
+    let (mut root, background) = menu_root();
+    root.row_gap = Val::Px(4.0);
+
+    commands
+        .spawn((StateScoped(AppState::Controls), root, background))
+        .with_children(|parent| {
+            parent.spawn((
+                Text::new("Controls"),
+                TextFont {
+                    font_size: 40.0,
+                    ..default()
+                },
+            ));
+
+            for action in Action::ALL {
+                parent
+                    .spawn(Node {
+                        column_gap: Val::Px(12.0),
+                        align_items: AlignItems::Center,
+                        ..default()
+                    })
+                    .with_children(|row| {
+                        row.spawn((
+                            Text::new(action.label()),
+                            Node {
+                                min_width: Val::Px(160.0),
+                                ..default()
+                            },
+                        ));
+                        row.spawn((
+                            BindingText(action),
+                            Text::new(describe_bindings(action, &bindings, &rebinding)),
+                            TextFont {
+                                font_size: 16.0,
+                                ..default()
+                            },
+                            Node {
+                                min_width: Val::Px(320.0),
+                                ..default()
+                            },
+                        ));
+                        spawn_button(row, "Rebind", ControlsAction::Rebind(action));
+                    });
+            }
+
+            parent
+                .spawn(Node {
+                    column_gap: Val::Px(12.0),
+                    ..default()
+                })
+                .with_children(|row| {
+                    spawn_button(row, "Reset to defaults", ControlsAction::ResetDefaults);
+                    spawn_button(row, "Back", ControlsAction::Back);
+                });
+        });
+}
+
+/// Bind the action being rebound to the next key, mouse button or gamepad
+/// button pressed; Escape cancels. Touch stays bound to its defaults.
+pub fn capture_rebinding(
+    keys: Res<ButtonInput<KeyCode>>,
+    mouse: Res<ButtonInput<MouseButton>>,
+    gamepads: Query<&Gamepad>,
+    mut rebinding: ResMut<Rebinding>,
+    mut bindings: ResMut<Bindings>,
+) {
+    let Some(action) = rebinding.action else {
+        return;
+    };
+
+    if keys.just_pressed(KeyCode::Escape) {
+        rebinding.action = None;
+        return;
+    }
+
+    let pressed = keys
+        .get_just_pressed()
+        .next()
+        .map(|key| Binding::Key(*key))
+        .or_else(|| {
+            mouse
+                .get_just_pressed()
+                .next()
+                .map(|button| Binding::Mouse(*button))
+        })
+        .or_else(|| {
+            gamepads
+                .iter()
+                .find_map(|gamepad| gamepad.get_just_pressed().next())
+                .map(|button| Binding::Gamepad(*button))
+        });
+
+    if let Some(binding) = pressed {
+        bindings.rebind(action, binding);
+        rebinding.action = None;
+    }
+}
+
+pub fn handle_controls_menu(
+    mut buttons: Query<(&ControlsAction, Ref<Interaction>, &mut BackgroundColor)>,
+    mut texts: Query<(&BindingText, &mut Text)>,
+    mut rebinding: ResMut<Rebinding>,
+    mut bindings: ResMut<Bindings>,
+    mut next_state: ResMut<NextState<AppState>>,
+) {
+    // The press that finished a rebind must not also hit a button
+    let capturing = rebinding.action.is_some() || rebinding.is_changed();
+
+    for (action, interaction, mut color) in &mut buttons {
+        if !capturing && interaction.is_changed() && *interaction == Interaction::Pressed {
+            match action {
+                ControlsAction::Rebind(target) => rebinding.action = Some(*target),
+                ControlsAction::ResetDefaults => *bindings = Bindings::default(),
+                ControlsAction::Back => next_state.set(AppState::Settings),
+            }
+        }
+
+        let waiting =
+            matches!(action, ControlsAction::Rebind(target) if rebinding.action == Some(*target));
+        *color = BackgroundColor(if waiting {
+            SELECTED_BUTTON_COLOR
+        } else {
+            BUTTON_COLOR
+        });
+    }
+
+    if bindings.is_changed() || rebinding.is_changed() {
+        for (text, mut value) in &mut texts {
+            **value = describe_bindings(text.0, &bindings, &rebinding);
+        }
+    }
+}
@@ -0,0 +1,220 @@
+use bevy::prelude::*;
+
+use super::{spawn_button, BUTTON_COLOR, MIN_TOUCH_TARGET, SELECTED_BUTTON_COLOR};
+use crate::bee::{Emote, Emoting, PlayerInputs};
+use crate::controls::{Action, InputDevice, PlayerActions, Roster};
+use crate::game::AppState;
+
+/// Distance of the wheel's entries from its centre
+const WHEEL_RADIUS: f32 = 170.0;
+
+/// Room each entry has, wide enough for the longest quick-chat line
+const WHEEL_BUTTON_WIDTH: f32 = 150.0;
+
+/// Height of a bubble above the bee it belongs to
+const BUBBLE_HEIGHT: f32 = 34.0;
+
+/// Which local player has the emote wheel open, if anyone
+#[derive(Resource, Debug, Default)]
+pub struct EmoteWheel {
+    pub player: Option<usize>,
+}
+
+#[derive(Component)]
+pub struct EmoteWheelRoot;
+
+#[derive(Component, Debug, Clone, Copy, PartialEq, Eq)]
+pub struct EmoteButton(pub Emote);
+
+/// Opens the wheel for whoever plays with the pointer
+#[derive(Component)]
+pub struct EmoteWheelToggle;
+
+/// Text over a bee showing its `Emoting`
+#[derive(Component)]
+pub struct EmoteBubble;
+
+pub fn setup_emote_wheel(mut commands: Commands, state: Res<State<AppState>>) {
+    let scope = StateScoped(*state.get());
+    let size = WHEEL_RADIUS * 2.0 + WHEEL_BUTTON_WIDTH;
+
+    commands
+        .spawn((
+            scope.clone(),
+            Node {
+                position_type: PositionType::Absolute,
+                width: Val::Percent(100.0),
+                height: Val::Percent(100.0),
+                justify_content: JustifyContent::Center,
+                align_items: AlignItems::Center,
+                ..default()
+            },
+        ))
+        .with_children(|parent| {
+            parent
+                .spawn((
+                    EmoteWheelRoot,
+                    Node {
+                        display: Display::None,
+                        width: Val::Px(size),
+                        height: Val::Px(size),
+                        ..default()
+                    },
+                ))
+                .with_children(|wheel| {
+                    for (index, emote) in Emote::WHEEL.into_iter().enumerate() {
+                        let angle =
+                            std::f32::consts::TAU * index as f32 / Emote::WHEEL.len() as f32;
+                        let offset = Vec2::new(angle.sin(), -angle.cos()) * WHEEL_RADIUS;
+                        wheel
+                            .spawn(Node {
+                                position_type: PositionType::Absolute,
+                                left: Val::Px(size / 2.0 + offset.x - WHEEL_BUTTON_WIDTH / 2.0),
+                                top: Val::Px(size / 2.0 + offset.y - MIN_TOUCH_TARGET / 2.0),
+                                width: Val::Px(WHEEL_BUTTON_WIDTH),
+                                justify_content: JustifyContent::Center,
+                                ..default()
+                            })
+                            .with_children(|slot| {
+                                spawn_button(slot, emote.text(), EmoteButton(emote));
+                            });
+                    }
+                });
+        });
+
+    commands
+        .spawn((
+            scope,
+            Node {
+                position_type: PositionType::Absolute,
+                width: Val::Percent(100.0),
+                bottom: Val::Px(20.0),
+                justify_content: JustifyContent::Center,
+                ..default()
+            },
+        ))
+        .with_children(|parent| {
+            spawn_button(parent, "Emote", EmoteWheelToggle);
+        });
+}
+
+pub fn close_emote_wheel(mut wheel: ResMut<EmoteWheel>) {
+    wheel.player = None;
+}
+
+/// The emote action opens the wheel; pressing it again says whatever the
+/// player is pointing at with their movement keys or stick. While the wheel
+/// is open, pointing doesn't steer the bee.
+pub fn handle_emote_action(
+    actions: Res<PlayerActions>,
+    mut wheel: ResMut<EmoteWheel>,
+    mut inputs: ResMut<PlayerInputs>,
+) {
+    for (player, actions) in actions.players.iter().enumerate() {
+        if !actions.just_pressed(Action::Emote) {
+            continue;
+        }
+        match wheel.player {
+            Some(open) if open == player => {
+                inputs.player_mut(player).emote = Emote::on_wheel(actions.move_axis());
+                wheel.player = None;
+            }
+            // Someone else is picking
+            Some(_) => {}
+            None => wheel.player = Some(player),
+        }
+    }
+
+    if let Some(player) = wheel.player {
+        inputs.player_mut(player).direction = Vec2::ZERO;
+    }
+}
+
+pub fn handle_emote_buttons(
+    wheel_buttons: Query<(&EmoteButton, Ref<Interaction>)>,
+    toggles: Query<Ref<Interaction>, With<EmoteWheelToggle>>,
+    roster: Res<Roster>,
+    mut wheel: ResMut<EmoteWheel>,
+    mut inputs: ResMut<PlayerInputs>,
+) {
+    let pressed = |interaction: &Ref<Interaction>| {
+        interaction.is_changed() && **interaction == Interaction::Pressed
+    };
+
+    if toggles.iter().any(|interaction| pressed(&interaction)) {
+        wheel.player = match wheel.player {
+            Some(_) => None,
+            None => roster.player_of(InputDevice::Pointer),
+        };
+    }
+
+    let Some(player) = wheel.player else {
+        return;
+    };
+    for (button, interaction) in &wheel_buttons {
+        if pressed(&interaction) {
+            inputs.player_mut(player).emote = Some(button.0);
+            wheel.player = None;
+        }
+    }
+}
+
+/// Show the wheel while it's open, lighting up the entry being pointed at
+pub fn update_emote_wheel(
+    wheel: Res<EmoteWheel>,
+    actions: Res<PlayerActions>,
+    mut roots: Query<&mut Node, With<EmoteWheelRoot>>,
+    mut buttons: Query<(&EmoteButton, &mut BackgroundColor)>,
+) {
+    for mut node in &mut roots {
+        node.display = if wheel.player.is_some() {
+            Display::Flex
+        } else {
+            Display::None
+        };
+    }
+
+    let pointed = wheel
+        .player
+        .and_then(|player| actions.players.get(player))
+        .and_then(|actions| Emote::on_wheel(actions.move_axis()));
+    for (button, mut color) in &mut buttons {
+        color.0 = if pointed == Some(button.0) {
+            SELECTED_BUTTON_COLOR
+        } else {
+            BUTTON_COLOR
+        };
+    }
+}
+
+/// Keep a bubble over each bee or companion that is emoting
+pub fn update_emote_bubbles(
+    mut commands: Commands,
+    emoting: Query<(Entity, &Emoting), Changed<Emoting>>,
+    mut stopped: RemovedComponents<Emoting>,
+    bubbles: Query<(Entity, &Parent), With<EmoteBubble>>,
+) {
+    let mut stale: Vec<Entity> = stopped.read().collect();
+    stale.extend(emoting.iter().map(|(entity, _)| entity));
+    for (bubble, parent) in &bubbles {
+        if stale.contains(&parent.get()) {
+            commands.entity(bubble).despawn_recursive();
+        }
+    }
+
+    for (entity, emoting) in &emoting {
+        let bubble = commands
+            .spawn((
+                EmoteBubble,
+                Text2d::new(emoting.emote.text()),
+                TextFont {
+                    font_size: 18.0,
+                    ..default()
+                },
+                TextColor(Color::WHITE),
+                Transform::from_xyz(0.0, BUBBLE_HEIGHT, 10.0),
+            ))
+            .id();
+        commands.entity(entity).add_child(bubble);
+    }
+}
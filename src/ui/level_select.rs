@@ -0,0 +1,224 @@
+use bevy::prelude::*;
+
+use super::{spawn_button, BUTTON_COLOR, DISABLED_BUTTON_COLOR};
+use crate::bee::PlayerSlot;
+use crate::controls::{Action, DeviceActions, Roster};
+use crate::game::{AppState, Team};
+use crate::level::{Campaign, CampaignHandle, CampaignProgress, LoadLevelEvent};
+
+/// Root of the garden list
+#[derive(Component)]
+pub struct LevelSelectScreen;
+
+/// Returns to the title screen
+#[derive(Component)]
+pub struct LevelSelectBack;
+
+/// Lists who has joined for local co-op
+#[derive(Component)]
+pub struct CoopStatus;
+
+/// Sends everyone but the single solo player home
+#[derive(Component)]
+pub struct CoopReset;
+
+/// A garden in the list
+#[derive(Component)]
+pub struct LevelButton {
+    pub path: String,
+    pub unlocked: bool,
+}
+
+fn describe_roster(roster: &Roster) -> String {
+    if roster.devices.is_empty() {
+        return "Solo. For co-op, press Wiggle on each keyboard, mouse or gamepad that wants to play"
+            .to_string();
+    }
+    let players: Vec<String> = roster
+        .devices
+        .iter()
+        .enumerate()
+        .map(|(index, device)| format!("{} {}", PlayerSlot(index).label(), device))
+        .collect();
+    format!("Co-op: {}", players.join(", "))
+}
+
+pub fn setup_level_select(
+    mut commands: Commands,
+    campaign: Option<Res<CampaignHandle>>,
+    campaigns: Res<Assets<Campaign>>,
+    progress: Res<CampaignProgress>,
+    roster: Res<Roster>,
+) {
+    let campaign = campaign.and_then(|handle| campaigns.get(&handle.0));
+    spawn_level_select(&mut commands, campaign, &progress, &roster);
+}
+
+/// Rebuild the list once the campaign finishes loading (or is hot-reloaded)
+/// while the screen is open
+pub fn refresh_level_select(
+    mut commands: Commands,
+    mut events: EventReader<AssetEvent<Campaign>>,
+    campaign: Option<Res<CampaignHandle>>,
+    campaigns: Res<Assets<Campaign>>,
+    progress: Res<CampaignProgress>,
+    roster: Res<Roster>,
+    screens: Query<Entity, With<LevelSelectScreen>>,
+) {
+    let Some(handle) = campaign else {
+        return;
+    };
+    let reloaded = events.read().fold(false, |reloaded, event| {
+        reloaded || event.is_loaded_with_dependencies(&handle.0) || event.is_modified(&handle.0)
+    });
+    if !reloaded {
+        return;
+    }
+
+    for screen in &screens {
+        commands.entity(screen).despawn_recursive();
+    }
+    spawn_level_select(&mut commands, campaigns.get(&handle.0), &progress, &roster);
+}
+
+fn spawn_level_select(
+    commands: &mut Commands,
+    campaign: Option<&Campaign>,
+    progress: &CampaignProgress,
+    roster: &Roster,
+) {
+    commands
+        .spawn((
+            LevelSelectScreen,
+            StateScoped(AppState::LevelSelect),
+            Node {
+                position_type: PositionType::Absolute,
+                width: Val::Percent(100.0),
+                height: Val::Percent(100.0),
+                flex_direction: FlexDirection::Column,
+                justify_content: JustifyContent::Center,
+                align_items: AlignItems::Center,
+                row_gap: Val::Px(12.0),
+                ..default()
+            },
+            BackgroundColor(Color::srgba(0.0, 0.0, 0.0, 0.85)),
+        ))
+        .with_children(|parent| {
+            parent.spawn((
+                Text::new("Choose a garden"),
+                TextFont {
+                    font_size: 40.0,
+                    ..default()
+                },
+                TextColor(Color::WHITE),
+            ));
+
+            let Some(campaign) = campaign else {
+                parent.spawn(Text::new("Loading gardens..."));
+                spawn_button(parent, "Back", LevelSelectBack);
+                return;
+            };
+
+            for (index, entry) in campaign.levels.iter().enumerate() {
+                let unlocked = campaign.is_unlocked(index, progress);
+                let status = if progress.completed.contains(&entry.id) {
+                    " - done"
+                } else if !unlocked {
+                    " - locked"
+                } else {
+                    ""
+                };
+                spawn_button(
+                    parent,
+                    format!("{}. {}{}", index + 1, entry.name, status),
+                    LevelButton {
+                        path: entry.path.clone(),
+                        unlocked,
+                    },
+                );
+            }
+
+            parent
+                .spawn(Node {
+                    column_gap: Val::Px(12.0),
+                    align_items: AlignItems::Center,
+                    ..default()
+                })
+                .with_children(|row| {
+                    row.spawn((
+                        CoopStatus,
+                        Text::new(describe_roster(roster)),
+                        TextFont {
+                            font_size: 16.0,
+                            ..default()
+                        },
+                    ));
+                    spawn_button(row, "Solo", CoopReset);
+                });
+
+            spawn_button(parent, "Back", LevelSelectBack);
+        });
+}
+
+/// Let each device join as the next co-op player by pressing Wiggle
+pub fn join_coop_players(
+    devices: Res<DeviceActions>,
+    reset: Query<Ref<Interaction>, With<CoopReset>>,
+    mut roster: ResMut<Roster>,
+    mut status: Query<&mut Text, With<CoopStatus>>,
+) {
+    if reset
+        .iter()
+        .any(|interaction| interaction.is_changed() && *interaction == Interaction::Pressed)
+    {
+        roster.devices.clear();
+    }
+
+    for (device, actions) in &devices.devices {
+        if actions.just_pressed(Action::Wiggle) {
+            if let Some(player) = roster.join(*device) {
+                info!("{} joined with {}", PlayerSlot(player).label(), device);
+            }
+        }
+    }
+
+    if roster.is_changed() {
+        for mut text in &mut status {
+            **text = describe_roster(&roster);
+        }
+    }
+}
+
+/// Start an unlocked garden when its button is pressed
+pub fn handle_level_buttons(
+    mut buttons: Query<(&LevelButton, Ref<Interaction>, &mut BackgroundColor)>,
+    back: Query<Ref<Interaction>, With<LevelSelectBack>>,
+    roster: Res<Roster>,
+    mut team: ResMut<Team>,
+    mut next_state: ResMut<NextState<AppState>>,
+    mut load_level: EventWriter<LoadLevelEvent>,
+) {
+    if back
+        .iter()
+        .any(|interaction| interaction.is_changed() && *interaction == Interaction::Pressed)
+    {
+        next_state.set(AppState::MainMenu);
+        return;
+    }
+
+    for (button, interaction, mut color) in &mut buttons {
+        if !button.unlocked {
+            *color = BackgroundColor(DISABLED_BUTTON_COLOR);
+            continue;
+        }
+        *color = BackgroundColor(BUTTON_COLOR);
+
+        if interaction.is_changed() && *interaction == Interaction::Pressed {
+            load_level.send(LoadLevelEvent {
+                path: button.path.clone(),
+            });
+            team.players = roster.players();
+            next_state.set(AppState::InGame);
+        }
+    }
+}
@@ -0,0 +1,108 @@
+use bevy::prelude::*;
+
+use super::spawn_button;
+use crate::game::AppState;
+
+/// Title screen buttons
+#[derive(Component, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MainMenuAction {
+    Play,
+    /// Join the garden server
+    Online,
+    Records,
+    Settings,
+    Credits,
+}
+
+/// Credits text, toggled from the title screen
+#[derive(Component)]
+pub struct CreditsText;
+
+/// Full-screen column used by the menu screens
+pub(super) fn menu_root() -> (Node, BackgroundColor) {
+    (
+        Node {
+            position_type: PositionType::Absolute,
+            width: Val::Percent(100.0),
+            height: Val::Percent(100.0),
+            flex_direction: FlexDirection::Column,
+            justify_content: JustifyContent::Center,
+            align_items: AlignItems::Center,
+            row_gap: Val::Px(12.0),
+            ..default()
+        },
+        BackgroundColor(Color::srgba(0.0, 0.0, 0.0, 0.85)),
+    )
+}
+
+/// Shown while the config and campaign assets load
+pub fn setup_boot_screen(mut commands: Commands) {
+    commands
+        .spawn((StateScoped(AppState::Boot), menu_root()))
+        .with_children(|parent| {
+            parent.spawn(Text::new("Loading..."));
+        });
+}
+
+pub fn setup_main_menu(mut commands: Commands) {
+    commands
+        .spawn((StateScoped(AppState::MainMenu), menu_root()))
+        .with_children(|parent| {
+            parent.spawn((
+                Text::new("Allerbees"),
+                TextFont {
+                    font_size: 64.0,
+                    ..default()
+                },
+                TextColor(Color::srgb(1.0, 0.85, 0.3)),
+            ));
+
+            spawn_button(parent, "Play", MainMenuAction::Play);
+            spawn_button(parent, "Online", MainMenuAction::Online);
+            spawn_button(parent, "Records", MainMenuAction::Records);
+            spawn_button(parent, "Settings", MainMenuAction::Settings);
+            spawn_button(parent, "Credits", MainMenuAction::Credits);
+
+            parent.spawn((
+                CreditsText,
+                Text::new(format!(
+                    "Allerbees v{}\n{}",
+                    env!("CARGO_PKG_VERSION"),
+                    env!("CARGO_PKG_DESCRIPTION")
+                )),
+                TextFont {
+                    font_size: 16.0,
+                    ..default()
+                },
+                TextLayout::new_with_justify(JustifyText::Center),
+                Visibility::Hidden,
+            ));
+        });
+}
+
+pub fn handle_main_menu(
+    buttons: Query<(&MainMenuAction, Ref<Interaction>)>,
+    mut credits: Query<&mut Visibility, With<CreditsText>>,
+    mut next_state: ResMut<NextState<AppState>>,
+) {
+    for (action, interaction) in &buttons {
+        if !interaction.is_changed() || *interaction != Interaction::Pressed {
+            continue;
+        }
+
+        match action {
+            MainMenuAction::Play => next_state.set(AppState::LevelSelect),
+            MainMenuAction::Online => next_state.set(AppState::Online),
+            MainMenuAction::Records => next_state.set(AppState::Records),
+            MainMenuAction::Settings => next_state.set(AppState::Settings),
+            MainMenuAction::Credits => {
+                for mut visibility in &mut credits {
+                    *visibility = match *visibility {
+                        Visibility::Hidden => Visibility::Inherited,
+                        _ => Visibility::Hidden,
+                    };
+                }
+            }
+        }
+    }
+}
@@ -1,31 +1,111 @@
 use bevy::prelude::*;
 
-use crate::bee::{AllergyMeter, Bee, CollectedPollen};
+use crate::bee::{AllergyMeter, Bee, CollectedPollen, PlayerSlot};
+use crate::game::{AppState, Team};
+
+/// Row holding a panel per player; rebuilt when the team size changes
+#[derive(Component)]
+pub struct PlayerHuds;
 
 #[derive(Component)]
 pub struct AllergyMeterBar;
 
 #[derive(Component)]
-pub struct AllergyMeterFill;
+pub struct AllergyMeterFill {
+    pub player: usize,
+}
 
 #[derive(Component)]
-pub struct PollenCounter;
+pub struct PollenCounter {
+    pub player: usize,
+}
 
 #[derive(Component)]
 pub struct DangerVignette;
 
 // Minimum touch target size (Apple HIG recommends 44x44)
-const MIN_TOUCH_TARGET: f32 = 44.0;
+pub const MIN_TOUCH_TARGET: f32 = 44.0;
+
+fn player_of(slot: Option<&PlayerSlot>) -> usize {
+    slot.map_or(0, |slot| slot.0)
+}
 
 pub fn setup_ui(mut commands: Commands) {
+    commands.spawn((
+        PlayerHuds,
+        StateScoped(AppState::InGame),
+        Node {
+            position_type: PositionType::Absolute,
+            left: Val::Px(20.0),
+            top: Val::Px(20.0),
+            column_gap: Val::Px(24.0),
+            ..default()
+        },
+    ));
+
+    // Danger vignette (screen border overlay)
+    commands.spawn((
+        DangerVignette,
+        StateScoped(AppState::InGame),
+        Node {
+            position_type: PositionType::Absolute,
+            left: Val::Px(0.0),
+            top: Val::Px(0.0),
+            width: Val::Percent(100.0),
+            height: Val::Percent(100.0),
+            border: UiRect::all(Val::Px(20.0)),
+            ..default()
+        },
+        BorderColor(Color::srgba(0.9, 0.1, 0.1, 0.0)),
+    ));
+}
+
+/// Give every player a panel with their allergy meter and pollen count,
+/// labelled in their bee's colour when playing co-op
+pub fn spawn_player_huds(
+    mut commands: Commands,
+    rows: Query<(Entity, Ref<PlayerHuds>)>,
+    team: Res<Team>,
+) {
+    for (entity, row) in &rows {
+        if !row.is_added() && !team.is_changed() {
+            continue;
+        }
+
+        commands
+            .entity(entity)
+            .despawn_descendants()
+            .with_children(|row| {
+                for player in 0..team.players {
+                    row.spawn(Node {
+                        flex_direction: FlexDirection::Column,
+                        row_gap: Val::Px(4.0),
+                        ..default()
+                    })
+                    .with_children(|panel| spawn_player_panel(panel, player, team.players > 1));
+                }
+            });
+    }
+}
+
+fn spawn_player_panel(panel: &mut ChildBuilder, player: usize, labelled: bool) {
+    if labelled {
+        let slot = PlayerSlot(player);
+        panel.spawn((
+            Text::new(slot.label()),
+            TextFont {
+                font_size: 18.0,
+                ..default()
+            },
+            TextColor(slot.color()),
+        ));
+    }
+
     // Allergy meter background - sized for visibility and touch
-    commands
+    panel
         .spawn((
             AllergyMeterBar,
             Node {
-                position_type: PositionType::Absolute,
-                left: Val::Px(20.0),
-                top: Val::Px(20.0),
                 width: Val::Px(200.0),
                 height: Val::Px(24.0), // Slightly larger for visibility
                 min_height: Val::Px(MIN_TOUCH_TARGET), // Touch target padding
@@ -36,7 +116,7 @@ pub fn setup_ui(mut commands: Commands) {
         .with_children(|parent| {
             // Allergy meter fill
             parent.spawn((
-                AllergyMeterFill,
+                AllergyMeterFill { player },
                 Node {
                     width: Val::Percent(0.0),
                     height: Val::Percent(100.0),
@@ -47,47 +127,29 @@ pub fn setup_ui(mut commands: Commands) {
         });
 
     // Pollen counter
-    commands.spawn((
-        PollenCounter,
+    panel.spawn((
+        PollenCounter { player },
         Text::new("Pollen: 0"),
         TextFont {
             font_size: 24.0,
             ..default()
         },
         TextColor(Color::srgb(1.0, 0.9, 0.2)),
-        Node {
-            position_type: PositionType::Absolute,
-            right: Val::Px(20.0),
-            top: Val::Px(20.0),
-            ..default()
-        },
-    ));
-
-    // Danger vignette (screen border overlay)
-    commands.spawn((
-        DangerVignette,
-        Node {
-            position_type: PositionType::Absolute,
-            left: Val::Px(0.0),
-            top: Val::Px(0.0),
-            width: Val::Percent(100.0),
-            height: Val::Percent(100.0),
-            border: UiRect::all(Val::Px(20.0)),
-            ..default()
-        },
-        BorderColor(Color::srgba(0.9, 0.1, 0.1, 0.0)),
     ));
 }
 
 pub fn update_allergy_meter_display(
-    bees: Query<&AllergyMeter, With<Bee>>,
-    mut fills: Query<(&mut Node, &mut BackgroundColor), With<AllergyMeterFill>>,
+    bees: Query<(&AllergyMeter, Option<&PlayerSlot>), With<Bee>>,
+    mut fills: Query<(&AllergyMeterFill, &mut Node, &mut BackgroundColor)>,
 ) {
-    let Some(meter) = bees.iter().next() else {
-        return;
-    };
+    for (fill, mut node, mut color) in &mut fills {
+        let Some((meter, _)) = bees
+            .iter()
+            .find(|(_, slot)| player_of(*slot) == fill.player)
+        else {
+            continue;
+        };
 
-    for (mut node, mut color) in &mut fills {
         let percentage = meter.percentage();
         node.width = Val::Percent(percentage * 100.0);
 
@@ -103,48 +165,49 @@ pub fn update_allergy_meter_display(
     }
 }
 
-/// System to tint bee red at high allergy levels
-pub fn update_bee_allergy_tint(mut bees: Query<(&AllergyMeter, &mut Sprite), With<Bee>>) {
-    for (meter, mut sprite) in &mut bees {
+/// System to tint bees red at high allergy levels
+pub fn update_bee_allergy_tint(
+    mut bees: Query<(&AllergyMeter, Option<&PlayerSlot>, &mut Sprite), With<Bee>>,
+) {
+    for (meter, slot, mut sprite) in &mut bees {
         let percentage = meter.percentage();
+        let base = PlayerSlot(player_of(slot)).color();
 
         // Start tinting red above 50% allergy
         if percentage > 0.5 {
             let tint_amount = (percentage - 0.5) * 2.0; // 0.0 to 1.0
-                                                        // Blend from yellow (1.0, 0.9, 0.2) toward red (1.0, 0.4, 0.2)
-            let g = 0.9 - tint_amount * 0.5;
-            sprite.color = Color::srgb(1.0, g, 0.2);
+                                                        // Blend from the player's colour toward red (1.0, 0.4, 0.2)
+            sprite.color = base.mix(&Color::srgb(1.0, 0.4, 0.2), tint_amount);
         } else {
-            // Normal yellow color
-            sprite.color = Color::srgb(1.0, 0.9, 0.2);
+            // Normal colour
+            sprite.color = base;
         }
     }
 }
 
 pub fn update_pollen_counter(
-    bees: Query<&CollectedPollen, With<Bee>>,
-    mut counter: Query<&mut Text, With<PollenCounter>>,
+    bees: Query<(&CollectedPollen, Option<&PlayerSlot>), With<Bee>>,
+    mut counters: Query<(&PollenCounter, &mut Text)>,
 ) {
-    let Some(collected) = bees.iter().next() else {
-        return;
-    };
-
-    for mut text in &mut counter {
-        **text = format!("Pollen: {}", collected.count);
+    for (counter, mut text) in &mut counters {
+        if let Some((collected, _)) = bees
+            .iter()
+            .find(|(_, slot)| player_of(*slot) == counter.player)
+        {
+            **text = format!("Pollen: {}", collected.count);
+        }
     }
 }
 
-/// System to update danger vignette opacity based on allergy level
+/// System to update danger vignette opacity based on the most allergic bee
 pub fn update_danger_vignette(
     bees: Query<&AllergyMeter, With<Bee>>,
     mut vignettes: Query<&mut BorderColor, With<DangerVignette>>,
 ) {
-    let Some(meter) = bees.iter().next() else {
+    let Some(percentage) = bees.iter().map(AllergyMeter::percentage).reduce(f32::max) else {
         return;
     };
 
-    let percentage = meter.percentage();
-
     for mut border_color in &mut vignettes {
         // Start showing vignette above 60%, fully visible at 100%
         let alpha = if percentage > 0.6 {
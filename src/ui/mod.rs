@@ -1,24 +1,131 @@
+mod config_status;
+mod controls;
+mod emotes;
+mod level_select;
+mod main_menu;
 mod meters;
+mod online;
 mod overlay;
+mod pause;
+mod records;
+mod settings;
+mod toast;
+mod touch;
+mod widgets;
 
+pub use config_status::*;
+pub use controls::*;
+pub use emotes::*;
+pub use level_select::*;
+pub use main_menu::*;
 pub use meters::*;
+pub use online::*;
 pub use overlay::*;
+pub use pause::*;
+pub use records::*;
+pub use settings::*;
+pub use toast::*;
+pub use touch::*;
+pub use widgets::*;
 
 use bevy::prelude::*;
 
+use crate::bee::handle_direction_input;
+use crate::game::{AppState, GameState, PauseState};
+use crate::save::record_run;
+
 pub struct UiPlugin;
 
 impl Plugin for UiPlugin {
     fn build(&self, app: &mut App) {
-        app.add_systems(Startup, (setup_ui, setup_overlay))
+        app.init_resource::<Rebinding>()
+            .init_resource::<RoomCodeInput>()
+            .init_resource::<EmoteWheel>()
+            .add_systems(Startup, (setup_config_error_text, setup_toast_stack))
+            .add_systems(OnEnter(AppState::Boot), setup_boot_screen)
+            .add_systems(OnEnter(AppState::MainMenu), setup_main_menu)
+            .add_systems(OnEnter(AppState::LevelSelect), setup_level_select)
+            .add_systems(OnEnter(AppState::Settings), setup_settings_menu)
+            .add_systems(OnEnter(AppState::Controls), setup_controls_menu)
+            .add_systems(OnEnter(AppState::Records), setup_records_screen)
+            .add_systems(
+                OnEnter(AppState::Online),
+                (setup_online_hud, setup_emote_wheel),
+            )
+            .add_systems(OnExit(AppState::Online), close_emote_wheel)
+            .add_systems(OnExit(AppState::InGame), close_emote_wheel)
+            .add_systems(
+                OnEnter(AppState::InGame),
+                (
+                    setup_ui,
+                    setup_pause_button,
+                    setup_touch_controls,
+                    setup_emote_wheel,
+                ),
+            )
+            .add_systems(OnEnter(GameState::Won), setup_overlay.after(record_run))
+            .add_systems(OnEnter(GameState::Lost), setup_overlay)
+            .add_systems(
+                OnEnter(PauseState::Paused),
+                (setup_pause_menu, close_emote_wheel),
+            )
             .add_systems(
                 Update,
                 (
-                    update_allergy_meter_display,
-                    update_pollen_counter,
-                    update_overlay_visibility,
-                    update_bee_allergy_tint,
-                    update_danger_vignette,
+                    handle_main_menu.run_if(in_state(AppState::MainMenu)),
+                    (
+                        refresh_level_select,
+                        join_coop_players,
+                        handle_level_buttons,
+                    )
+                        .run_if(in_state(AppState::LevelSelect)),
+                    handle_settings_menu.run_if(in_state(AppState::Settings)),
+                    (capture_rebinding, handle_controls_menu)
+                        .chain()
+                        .run_if(in_state(AppState::Controls)),
+                    (
+                        refresh_records_screen,
+                        update_records_list,
+                        handle_records_back,
+                    )
+                        .chain()
+                        .run_if(in_state(AppState::Records)),
+                    (
+                        type_room_code,
+                        update_online_status,
+                        update_lobby_rows,
+                        handle_online_buttons,
+                    )
+                        .run_if(in_state(AppState::Online)),
+                    update_difficulty_buttons,
+                    update_config_error_text,
+                    (spawn_achievement_toasts, expire_toasts),
+                    (
+                        spawn_player_huds,
+                        update_allergy_meter_display,
+                        update_pollen_counter,
+                        handle_overlay_actions,
+                        update_bee_allergy_tint,
+                        update_danger_vignette,
+                        update_pause_button_visibility,
+                        update_wiggle_button,
+                        update_joystick_display,
+                        draw_wiggle_cooldown_ring,
+                    )
+                        .run_if(in_state(AppState::InGame)),
+                    (
+                        handle_pause_button,
+                        handle_wiggle_button,
+                        pause_on_focus_loss,
+                    )
+                        .run_if(in_state(PauseState::Running)),
+                    handle_pause_menu.run_if(in_state(PauseState::Paused)),
+                    (
+                        handle_emote_action.after(handle_direction_input),
+                        handle_emote_buttons,
+                    )
+                        .run_if(in_state(PauseState::Running).or(in_state(AppState::Online))),
+                    (update_emote_wheel, update_emote_bubbles),
                 ),
             );
     }
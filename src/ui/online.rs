@@ -0,0 +1,308 @@
+use bevy::input::keyboard::{Key, KeyboardInput};
+use bevy::input::ButtonState;
+use bevy::prelude::*;
+
+use super::spawn_button;
+use crate::bee::{PlayerSlot, Role};
+use crate::game::{AppState, GameConfig, GameState};
+use crate::launch;
+use crate::net::{
+    normalize_room_code, ClientMessage, ConnectionStatus, LinkSimulator, OnlineSession, RoomState,
+    SendToServer, ServerUrl, SnapshotBuffer, ROOM_CODE_LENGTH,
+};
+
+/// Connection state, this player's bee and the team's progress
+#[derive(Component)]
+pub struct OnlineStatusText;
+
+#[derive(Component, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OnlineAction {
+    Leave,
+    CreateRoom,
+    JoinRoom,
+    ChooseRole(Role),
+    ToggleReady,
+}
+
+/// Rows of lobby buttons, each shown while it applies
+#[derive(Component, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LobbyRow {
+    /// Creating or joining a room
+    Entry,
+    /// Picking a role and readying up in a room
+    Room,
+}
+
+/// Room code typed so far, starting from the `room` launch option (e.g.
+/// `?room=ABCD` on the web)
+#[derive(Resource, Debug, Clone, PartialEq)]
+pub struct RoomCodeInput(pub String);
+
+impl Default for RoomCodeInput {
+    fn default() -> Self {
+        let code = launch::option("room").map_or_else(String::new, |code| {
+            normalize_room_code(&code)
+                .chars()
+                .take(ROOM_CODE_LENGTH)
+                .collect()
+        });
+        Self(code)
+    }
+}
+
+pub fn setup_online_hud(mut commands: Commands) {
+    commands.spawn((
+        OnlineStatusText,
+        StateScoped(AppState::Online),
+        Text::new("Connecting..."),
+        TextFont {
+            font_size: 20.0,
+            ..default()
+        },
+        Node {
+            position_type: PositionType::Absolute,
+            left: Val::Px(20.0),
+            top: Val::Px(20.0),
+            ..default()
+        },
+    ));
+
+    commands
+        .spawn((
+            StateScoped(AppState::Online),
+            Node {
+                position_type: PositionType::Absolute,
+                right: Val::Px(20.0),
+                bottom: Val::Px(20.0),
+                ..default()
+            },
+        ))
+        .with_children(|parent| {
+            spawn_button(parent, "Leave", OnlineAction::Leave);
+        });
+
+    commands
+        .spawn((
+            StateScoped(AppState::Online),
+            Node {
+                position_type: PositionType::Absolute,
+                left: Val::Px(20.0),
+                bottom: Val::Px(20.0),
+                flex_direction: FlexDirection::Column,
+                row_gap: Val::Px(10.0),
+                ..default()
+            },
+        ))
+        .with_children(|parent| {
+            parent
+                .spawn(lobby_row(LobbyRow::Entry))
+                .with_children(|row| {
+                    spawn_button(row, "Create room", OnlineAction::CreateRoom);
+                    spawn_button(row, "Join room", OnlineAction::JoinRoom);
+                });
+            parent
+                .spawn(lobby_row(LobbyRow::Room))
+                .with_children(|row| {
+                    for role in Role::ALL {
+                        spawn_button(row, role.label(), OnlineAction::ChooseRole(role));
+                    }
+                    spawn_button(row, "Ready", OnlineAction::ToggleReady);
+                });
+        });
+}
+
+fn lobby_row(row: LobbyRow) -> impl Bundle {
+    (
+        row,
+        Node {
+            display: Display::None,
+            column_gap: Val::Px(10.0),
+            ..default()
+        },
+    )
+}
+
+fn describe_room(room: &RoomState) -> Vec<String> {
+    let mut lines = vec![format!(
+        "Room {}  {}/{} bees, AI companions fill the rest",
+        room.code,
+        room.members.len(),
+        room.cap
+    )];
+    for member in &room.members {
+        lines.push(format!(
+            "{} - {} - {}{}",
+            PlayerSlot(member.player).label(),
+            member.role.label(),
+            if member.ready { "ready" } else { "not ready" },
+            if member.player == room.you {
+                " (you)"
+            } else {
+                ""
+            }
+        ));
+    }
+    lines.push("Pick a role, then press Ready. The round starts when everyone is.".to_string());
+    lines
+}
+
+/// `lines`, then why the last lobby request was turned down
+fn with_notice(mut lines: Vec<String>, session: &OnlineSession) -> String {
+    lines.extend(session.notice.clone());
+    lines.join("\n")
+}
+
+fn describe_session(
+    session: &OnlineSession,
+    url: &ServerUrl,
+    code: &RoomCodeInput,
+    buffer: &SnapshotBuffer,
+    link: &LinkSimulator,
+    config: &GameConfig,
+) -> String {
+    let player = match &session.status {
+        ConnectionStatus::Connecting => return format!("Connecting to {}...", url.0),
+        ConnectionStatus::Disconnected(reason) => return format!("Disconnected: {reason}"),
+        ConnectionStatus::Lobby { cap } => {
+            let lines = vec![
+                format!("Lobby: create a room, or type a code and join one (up to {cap} bees)"),
+                format!("Code: {}_", code.0),
+            ];
+            return with_notice(lines, session);
+        }
+        ConnectionStatus::InRoom(room) => return with_notice(describe_room(room), session),
+        ConnectionStatus::Playing { player } => *player,
+    };
+    let Some(snapshot) = buffer.latest() else {
+        return "Waiting for the garden...".to_string();
+    };
+
+    let mut lines = Vec::new();
+    if let Some(bee) = snapshot.bee(player) {
+        lines.push(format!(
+            "You are {}  Pollen: {}  Allergy: {:.0}%",
+            PlayerSlot(player).label(),
+            bee.pollen,
+            bee.allergy / bee.allergy_max * 100.0
+        ));
+    }
+    let team_pollen: u32 = snapshot.bees.iter().map(|bee| bee.pollen).sum();
+    lines.push(format!(
+        "{} bees  Team pollen: {}/{}",
+        snapshot.bees.len(),
+        team_pollen,
+        config.win_lose.win_pollen
+    ));
+    lines.push(format!("Garden harvest: {}", snapshot.harvested));
+    match snapshot.state {
+        GameState::Playing | GameState::Restarting => {}
+        GameState::Won => lines.push("Garden won! Next round soon...".to_string()),
+        GameState::Lost => lines.push("Garden lost. Next round soon...".to_string()),
+    }
+
+    let conditions = link.conditions();
+    if !conditions.is_perfect() {
+        lines.push(format!(
+            "Simulated link: {} ms +{} ms jitter, {:.0}% loss",
+            conditions.latency.as_millis(),
+            conditions.jitter.as_millis(),
+            conditions.loss * 100.0
+        ));
+    }
+    lines.join("\n")
+}
+
+#[allow(clippy::too_many_arguments)]
+pub fn update_online_status(
+    session: Res<OnlineSession>,
+    url: Res<ServerUrl>,
+    code: Res<RoomCodeInput>,
+    buffer: Res<SnapshotBuffer>,
+    link: Res<LinkSimulator>,
+    config: Res<GameConfig>,
+    mut texts: Query<&mut Text, With<OnlineStatusText>>,
+) {
+    if !session.is_changed() && !buffer.is_changed() && !code.is_changed() {
+        return;
+    }
+    let status = describe_session(&session, &url, &code, &buffer, &link, &config);
+    for mut text in &mut texts {
+        **text = status.clone();
+    }
+}
+
+/// Show the lobby rows that apply to where the player is
+pub fn update_lobby_rows(session: Res<OnlineSession>, mut rows: Query<(&LobbyRow, &mut Node)>) {
+    if !session.is_changed() {
+        return;
+    }
+    for (row, mut node) in &mut rows {
+        let shown = matches!(
+            (row, &session.status),
+            (LobbyRow::Entry, ConnectionStatus::Lobby { .. })
+                | (LobbyRow::Room, ConnectionStatus::InRoom(_))
+        );
+        node.display = if shown { Display::Flex } else { Display::None };
+    }
+}
+
+/// Type a room code while in the lobby
+pub fn type_room_code(
+    mut keys: EventReader<KeyboardInput>,
+    session: Res<OnlineSession>,
+    mut code: ResMut<RoomCodeInput>,
+) {
+    for key in keys.read() {
+        if key.state != ButtonState::Pressed
+            || !matches!(session.status, ConnectionStatus::Lobby { .. })
+        {
+            continue;
+        }
+        match &key.logical_key {
+            Key::Backspace => {
+                code.0.pop();
+            }
+            Key::Character(typed) if code.0.len() < ROOM_CODE_LENGTH => {
+                let typed = normalize_room_code(typed);
+                if typed.chars().all(|c| c.is_ascii_alphanumeric()) {
+                    code.0.push_str(&typed);
+                    code.0.truncate(ROOM_CODE_LENGTH);
+                }
+            }
+            _ => {}
+        }
+    }
+}
+
+pub fn handle_online_buttons(
+    buttons: Query<(&OnlineAction, Ref<Interaction>)>,
+    session: Res<OnlineSession>,
+    code: Res<RoomCodeInput>,
+    mut requests: EventWriter<SendToServer>,
+    mut next_state: ResMut<NextState<AppState>>,
+) {
+    for (action, interaction) in &buttons {
+        if !interaction.is_changed() || *interaction != Interaction::Pressed {
+            continue;
+        }
+        let request = match action {
+            OnlineAction::Leave => {
+                next_state.set(AppState::MainMenu);
+                continue;
+            }
+            OnlineAction::CreateRoom => ClientMessage::CreateRoom,
+            OnlineAction::JoinRoom => ClientMessage::JoinRoom {
+                code: code.0.clone(),
+            },
+            OnlineAction::ChooseRole(role) => ClientMessage::ChooseRole { role: *role },
+            OnlineAction::ToggleReady => {
+                let ConnectionStatus::InRoom(room) = &session.status else {
+                    continue;
+                };
+                let ready = room.member(room.you).is_some_and(|member| member.ready);
+                ClientMessage::SetReady { ready: !ready }
+            }
+        };
+        requests.send(SendToServer(request));
+    }
+}
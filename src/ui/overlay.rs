@@ -1,6 +1,10 @@
 use bevy::prelude::*;
 
-use crate::game::{GameState, SessionTimer};
+use super::{spawn_button, spawn_leaderboard, BUTTON_COLOR, SELECTED_BUTTON_COLOR};
+use crate::game::{AppState, Difficulty, GameRng, GameState, SessionTimer};
+use crate::level::{ActiveLevel, Campaign, CampaignHandle, LoadLevelEvent};
+use crate::replay::{load_last_replay, PlayReplayEvent};
+use crate::save::{LastRun, Leaderboards};
 
 #[derive(Component)]
 pub struct GameOverlay;
@@ -8,65 +12,194 @@ pub struct GameOverlay;
 #[derive(Component)]
 pub struct OverlayText;
 
-pub fn setup_overlay(mut commands: Commands) {
+/// Button on the end screen that picks the difficulty for the next round
+#[derive(Component)]
+pub struct DifficultyButton(pub Difficulty);
+
+/// Campaign navigation buttons on the end screen
+#[derive(Component, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OverlayAction {
+    Gardens,
+    NextGarden,
+    WatchReplay,
+}
+
+/// Spawn the end screen when a round is won or lost. It is scoped to that
+/// state, so it disappears as soon as the next round starts.
+pub fn setup_overlay(
+    mut commands: Commands,
+    state: Res<State<GameState>>,
+    timer: Res<SessionTimer>,
+    difficulty: Res<Difficulty>,
+    rng: Res<GameRng>,
+    records: Res<Leaderboards>,
+    last_run: Option<Res<LastRun>>,
+) {
+    let state = *state.get();
+    let message = overlay_message(state, &timer, *difficulty, rng.seed());
+
     commands
         .spawn((
             GameOverlay,
+            StateScoped(state),
             Node {
                 position_type: PositionType::Absolute,
                 width: Val::Percent(100.0),
                 height: Val::Percent(100.0),
+                flex_direction: FlexDirection::Column,
                 justify_content: JustifyContent::Center,
                 align_items: AlignItems::Center,
+                row_gap: Val::Px(24.0),
                 ..default()
             },
             BackgroundColor(Color::srgba(0.0, 0.0, 0.0, 0.7)),
-            Visibility::Hidden,
         ))
         .with_children(|parent| {
             parent.spawn((
                 OverlayText,
-                Text::new(""),
+                Text::new(message),
                 TextFont {
                     font_size: 48.0,
                     ..default()
                 },
                 TextColor(Color::WHITE),
+                TextLayout::new_with_justify(JustifyText::Center),
             ));
+
+            // Top runs on this garden, with the one just won highlighted
+            if let (GameState::Won, Some(last_run)) = (state, &last_run) {
+                parent
+                    .spawn(Node {
+                        flex_direction: FlexDirection::Column,
+                        align_items: AlignItems::Start,
+                        ..default()
+                    })
+                    .with_children(|list| {
+                        let board =
+                            records.get(&last_run.level_id, last_run.difficulty, last_run.co_op);
+                        spawn_leaderboard(list, board, last_run.rank, 3);
+                    });
+            }
+
+            // Difficulty picker
+            parent
+                .spawn(Node {
+                    column_gap: Val::Px(12.0),
+                    ..default()
+                })
+                .with_children(|row| {
+                    for (i, difficulty) in Difficulty::ALL.into_iter().enumerate() {
+                        spawn_button(
+                            row,
+                            format!("{} {}", i + 1, difficulty.label()),
+                            DifficultyButton(difficulty),
+                        );
+                    }
+                });
+
+            // Campaign navigation
+            parent
+                .spawn(Node {
+                    column_gap: Val::Px(12.0),
+                    ..default()
+                })
+                .with_children(|row| {
+                    spawn_button(row, "Gardens", OverlayAction::Gardens);
+                    spawn_button(row, "Next garden", OverlayAction::NextGarden);
+                    spawn_button(row, "Watch replay", OverlayAction::WatchReplay);
+                });
         });
 }
 
-pub fn update_overlay_visibility(
-    state: Res<State<GameState>>,
-    timer: Res<SessionTimer>,
-    mut overlay: Query<&mut Visibility, With<GameOverlay>>,
-    mut text: Query<&mut Text, With<OverlayText>>,
-) {
-    let Ok(mut visibility) = overlay.get_single_mut() else {
-        return;
+/// End-of-round headline for the state the overlay was spawned for
+fn overlay_message(
+    state: GameState,
+    timer: &SessionTimer,
+    difficulty: Difficulty,
+    seed: u64,
+) -> String {
+    let headline = match state {
+        GameState::Won => "You Win!",
+        _ => "Game Over!",
     };
+    format!(
+        "{headline}\n\nTime: {}  ({})\nSeed: {seed}\n\nClick to restart",
+        timer.formatted(),
+        difficulty.label(),
+    )
+}
+
+/// Pick a difficulty from the end screen and highlight the current choice
+pub fn update_difficulty_buttons(
+    mut buttons: Query<(&DifficultyButton, &Interaction, &mut BackgroundColor)>,
+    mut difficulty: ResMut<Difficulty>,
+) {
+    for (button, interaction, _) in &buttons {
+        if *interaction == Interaction::Pressed && *difficulty != button.0 {
+            *difficulty = button.0;
+        }
+    }
 
-    let Ok(mut text) = text.get_single_mut() else {
-        return;
+    for (button, _, mut color) in &mut buttons {
+        *color = BackgroundColor(if button.0 == *difficulty {
+            SELECTED_BUTTON_COLOR
+        } else {
+            BUTTON_COLOR
+        });
+    }
+}
+
+/// Open the garden list, go straight to the next garden after a win, or
+/// watch the round that just ended
+#[allow(clippy::too_many_arguments)]
+pub fn handle_overlay_actions(
+    mut buttons: Query<(&OverlayAction, Ref<Interaction>, &mut Node)>,
+    state: Res<State<GameState>>,
+    active: Option<Res<ActiveLevel>>,
+    campaign: Option<Res<CampaignHandle>>,
+    campaigns: Res<Assets<Campaign>>,
+    mut next_state: ResMut<NextState<GameState>>,
+    mut next_app_state: ResMut<NextState<AppState>>,
+    mut load_level: EventWriter<LoadLevelEvent>,
+    mut play_replay: EventWriter<PlayReplayEvent>,
+) {
+    let next_garden = match (state.get(), active, campaign) {
+        (GameState::Won, Some(active), Some(campaign)) => campaigns
+            .get(&campaign.0)
+            .and_then(|campaign| campaign.next_after(&active.id))
+            .cloned(),
+        _ => None,
     };
 
-    match state.get() {
-        GameState::Playing => {
-            *visibility = Visibility::Hidden;
+    for (action, interaction, mut node) in &mut buttons {
+        if *action == OverlayAction::NextGarden {
+            node.display = if next_garden.is_some() {
+                Display::Flex
+            } else {
+                Display::None
+            };
         }
-        GameState::Won => {
-            *visibility = Visibility::Visible;
-            **text = format!(
-                "You Win!\n\nTime: {}\n\nClick to restart",
-                timer.formatted()
-            );
+
+        if !interaction.is_changed() || *interaction != Interaction::Pressed {
+            continue;
         }
-        GameState::Lost => {
-            *visibility = Visibility::Visible;
-            **text = format!(
-                "Game Over!\n\nTime: {}\n\nClick to restart",
-                timer.formatted()
-            );
+
+        match action {
+            OverlayAction::Gardens => next_app_state.set(AppState::LevelSelect),
+            OverlayAction::NextGarden => {
+                if let Some(next) = &next_garden {
+                    load_level.send(LoadLevelEvent {
+                        path: next.path.clone(),
+                    });
+                    next_state.set(GameState::Playing);
+                }
+            }
+            OverlayAction::WatchReplay => match load_last_replay() {
+                Some(replay) => {
+                    play_replay.send(PlayReplayEvent(replay));
+                }
+                None => warn!("No replay has been saved yet"),
+            },
         }
     }
 }
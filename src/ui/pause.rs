@@ -0,0 +1,131 @@
+use bevy::prelude::*;
+use bevy::window::{WindowFocused, WindowOccluded};
+
+use super::spawn_button;
+use crate::game::{restart_round, AppState, GameState, PauseState};
+
+/// Corner holder for the on-screen pause button, shown while a round runs
+#[derive(Component)]
+pub struct PauseHud;
+
+#[derive(Component)]
+pub struct PauseButton;
+
+/// Root of the pause menu
+#[derive(Component)]
+pub struct PauseMenu;
+
+#[derive(Component, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PauseMenuAction {
+    Resume,
+    Restart,
+    Quit,
+}
+
+pub fn setup_pause_button(mut commands: Commands) {
+    commands
+        .spawn((
+            PauseHud,
+            StateScoped(AppState::InGame),
+            Node {
+                position_type: PositionType::Absolute,
+                right: Val::Px(20.0),
+                bottom: Val::Px(20.0),
+                ..default()
+            },
+            Visibility::Hidden,
+        ))
+        .with_children(|parent| {
+            spawn_button(parent, "Pause", PauseButton);
+        });
+}
+
+pub fn update_pause_button_visibility(
+    state: Option<Res<State<PauseState>>>,
+    mut huds: Query<&mut Visibility, With<PauseHud>>,
+) {
+    let running = state.is_some_and(|state| *state.get() == PauseState::Running);
+    for mut visibility in &mut huds {
+        *visibility = if running {
+            Visibility::Visible
+        } else {
+            Visibility::Hidden
+        };
+    }
+}
+
+pub fn handle_pause_button(
+    buttons: Query<Ref<Interaction>, With<PauseButton>>,
+    mut next_state: ResMut<NextState<PauseState>>,
+) {
+    for interaction in &buttons {
+        if interaction.is_changed() && *interaction == Interaction::Pressed {
+            next_state.set(PauseState::Paused);
+        }
+    }
+}
+
+/// Pause when the window loses focus or, on the web, the tab is hidden, so
+/// allergies don't build up while the player is away
+pub fn pause_on_focus_loss(
+    mut focus_events: EventReader<WindowFocused>,
+    mut occlusion_events: EventReader<WindowOccluded>,
+    mut next_state: ResMut<NextState<PauseState>>,
+) {
+    let lost_focus = focus_events.read().any(|event| !event.focused);
+    let hidden = occlusion_events.read().any(|event| event.occluded);
+    if lost_focus || hidden {
+        next_state.set(PauseState::Paused);
+    }
+}
+
+pub fn setup_pause_menu(mut commands: Commands) {
+    commands
+        .spawn((
+            PauseMenu,
+            StateScoped(PauseState::Paused),
+            Node {
+                position_type: PositionType::Absolute,
+                width: Val::Percent(100.0),
+                height: Val::Percent(100.0),
+                flex_direction: FlexDirection::Column,
+                justify_content: JustifyContent::Center,
+                align_items: AlignItems::Center,
+                row_gap: Val::Px(12.0),
+                ..default()
+            },
+            BackgroundColor(Color::srgba(0.0, 0.0, 0.0, 0.7)),
+        ))
+        .with_children(|parent| {
+            parent.spawn((
+                Text::new("Paused"),
+                TextFont {
+                    font_size: 48.0,
+                    ..default()
+                },
+                TextColor(Color::WHITE),
+            ));
+            spawn_button(parent, "Resume", PauseMenuAction::Resume);
+            spawn_button(parent, "Restart", PauseMenuAction::Restart);
+            spawn_button(parent, "Quit to menu", PauseMenuAction::Quit);
+        });
+}
+
+pub fn handle_pause_menu(
+    buttons: Query<(&PauseMenuAction, Ref<Interaction>)>,
+    mut next_pause: ResMut<NextState<PauseState>>,
+    mut next_round: ResMut<NextState<GameState>>,
+    mut next_app: ResMut<NextState<AppState>>,
+) {
+    for (action, interaction) in &buttons {
+        if !interaction.is_changed() || *interaction != Interaction::Pressed {
+            continue;
+        }
+
+        match action {
+            PauseMenuAction::Resume => next_pause.set(PauseState::Running),
+            PauseMenuAction::Restart => restart_round(&mut next_round),
+            PauseMenuAction::Quit => next_app.set(AppState::MainMenu),
+        }
+    }
+}
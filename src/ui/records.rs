@@ -0,0 +1,277 @@
+use bevy::prelude::*;
+
+use super::{menu_root, spawn_button, BUTTON_COLOR, SELECTED_BUTTON_COLOR};
+use crate::game::{format_time, AppState, Difficulty};
+use crate::level::{Campaign, CampaignHandle};
+use crate::save::{Leaderboards, RunRecord, LEADERBOARD_SIZE};
+
+/// Root of the records screen
+#[derive(Component)]
+pub struct RecordsScreen;
+
+/// Returns from the records screen to the title screen
+#[derive(Component)]
+pub struct RecordsBack;
+
+/// Picks which table is shown. The difficulty buttons here, unlike
+/// `DifficultyButton`, leave the difficulty the game is played at alone.
+#[derive(Component, Debug, Clone, Copy, PartialEq)]
+pub enum RecordsFilter {
+    /// A garden, by campaign index
+    Garden(usize),
+    Difficulty(Difficulty),
+    /// Solo (false) or co-op (true) runs
+    CoOp(bool),
+}
+
+/// Holds the table of the chosen garden, difficulty and solo or co-op;
+/// rebuilt when any of them changes
+#[derive(Component)]
+pub struct RecordsList {
+    pub level: usize,
+    pub difficulty: Difficulty,
+    pub co_op: bool,
+}
+
+impl RecordsList {
+    fn apply(&mut self, filter: RecordsFilter) {
+        match filter {
+            RecordsFilter::Garden(level) => self.level = level,
+            RecordsFilter::Difficulty(difficulty) => self.difficulty = difficulty,
+            RecordsFilter::CoOp(co_op) => self.co_op = co_op,
+        }
+    }
+
+    fn shows(&self, filter: RecordsFilter) -> bool {
+        match filter {
+            RecordsFilter::Garden(level) => self.level == level,
+            RecordsFilter::Difficulty(difficulty) => self.difficulty == difficulty,
+            RecordsFilter::CoOp(co_op) => self.co_op == co_op,
+        }
+    }
+}
+
+fn describe_run(rank: usize, record: &RunRecord) -> String {
+    let unknown = || "-".to_string();
+    format!(
+        "{:>2}. {}   pollen {}   sneezes {}   seed {}",
+        rank + 1,
+        format_time(record.seconds),
+        record
+            .pollen
+            .map_or_else(unknown, |pollen| pollen.to_string()),
+        record
+            .sneezes
+            .map_or_else(unknown, |sneezes| sneezes.to_string()),
+        record.seed.map_or_else(unknown, |seed| seed.to_string()),
+    )
+}
+
+/// Spawn up to `limit` rows of `board`, plus the `highlight`ed run if it
+/// ranked below them, marking it as a new record
+pub fn spawn_leaderboard(
+    parent: &mut ChildBuilder,
+    board: &[RunRecord],
+    highlight: Option<usize>,
+    limit: usize,
+) {
+    if board.is_empty() {
+        parent.spawn((
+            Text::new("No wins yet"),
+            TextFont {
+                font_size: 16.0,
+                ..default()
+            },
+        ));
+        return;
+    }
+
+    for (rank, record) in board.iter().enumerate() {
+        let highlighted = highlight == Some(rank);
+        if rank >= limit && !highlighted {
+            continue;
+        }
+
+        let mut line = describe_run(rank, record);
+        if highlighted {
+            line.push_str("   new record!");
+        }
+        parent.spawn((
+            Text::new(line),
+            TextFont {
+                font_size: 16.0,
+                ..default()
+            },
+            TextColor(if highlighted {
+                SELECTED_BUTTON_COLOR
+            } else {
+                Color::WHITE
+            }),
+        ));
+    }
+}
+
+pub fn setup_records_screen(
+    mut commands: Commands,
+    campaign: Option<Res<CampaignHandle>>,
+    campaigns: Res<Assets<Campaign>>,
+    difficulty: Res<Difficulty>,
+) {
+    let campaign = campaign.and_then(|handle| campaigns.get(&handle.0));
+    let shown = RecordsList {
+        level: 0,
+        difficulty: *difficulty,
+        co_op: false,
+    };
+    spawn_records_screen(&mut commands, campaign, shown);
+}
+
+/// Rebuild the screen once the campaign finishes loading (or is
+/// hot-reloaded) while it is open
+pub fn refresh_records_screen(
+    mut commands: Commands,
+    mut events: EventReader<AssetEvent<Campaign>>,
+    campaign: Option<Res<CampaignHandle>>,
+    campaigns: Res<Assets<Campaign>>,
+    screens: Query<Entity, With<RecordsScreen>>,
+    lists: Query<&RecordsList>,
+    difficulty: Res<Difficulty>,
+) {
+    let Some(handle) = campaign else {
+        return;
+    };
+    let reloaded = events.read().fold(false, |reloaded, event| {
+        reloaded || event.is_loaded_with_dependencies(&handle.0) || event.is_modified(&handle.0)
+    });
+    if !reloaded {
+        return;
+    }
+
+    // Keep showing the difficulty and party that were picked
+    let shown = RecordsList {
+        level: 0,
+        difficulty: lists
+            .iter()
+            .next()
+            .map_or(*difficulty, |list| list.difficulty),
+        co_op: lists.iter().any(|list| list.co_op),
+    };
+    for screen in &screens {
+        commands.entity(screen).despawn_recursive();
+    }
+    spawn_records_screen(&mut commands, campaigns.get(&handle.0), shown);
+}
+
+fn spawn_records_screen(commands: &mut Commands, campaign: Option<&Campaign>, shown: RecordsList) {
+    commands
+        .spawn((RecordsScreen, StateScoped(AppState::Records), menu_root()))
+        .with_children(|parent| {
+            parent.spawn((
+                Text::new("Records"),
+                TextFont {
+                    font_size: 40.0,
+                    ..default()
+                },
+            ));
+
+            parent
+                .spawn(Node {
+                    column_gap: Val::Px(12.0),
+                    ..default()
+                })
+                .with_children(|row| {
+                    for (index, entry) in campaign.iter().flat_map(|c| &c.levels).enumerate() {
+                        spawn_button(row, entry.name.clone(), RecordsFilter::Garden(index));
+                    }
+                });
+
+            parent
+                .spawn(Node {
+                    column_gap: Val::Px(12.0),
+                    ..default()
+                })
+                .with_children(|row| {
+                    for difficulty in Difficulty::ALL {
+                        spawn_button(
+                            row,
+                            difficulty.label(),
+                            RecordsFilter::Difficulty(difficulty),
+                        );
+                    }
+                    spawn_button(row, "Solo", RecordsFilter::CoOp(false));
+                    spawn_button(row, "Co-op", RecordsFilter::CoOp(true));
+                });
+
+            parent.spawn((
+                shown,
+                Node {
+                    flex_direction: FlexDirection::Column,
+                    align_items: AlignItems::Start,
+                    row_gap: Val::Px(4.0),
+                    ..default()
+                },
+            ));
+
+            spawn_button(parent, "Back", RecordsBack);
+        });
+}
+
+/// Show the top runs of the chosen garden, difficulty and party
+pub fn update_records_list(
+    mut commands: Commands,
+    mut lists: Query<(Entity, &mut RecordsList)>,
+    mut filters: Query<(&RecordsFilter, Ref<Interaction>, &mut BackgroundColor)>,
+    records: Res<Leaderboards>,
+    campaign: Option<Res<CampaignHandle>>,
+    campaigns: Res<Assets<Campaign>>,
+) {
+    let Some(campaign) = campaign.and_then(|handle| campaigns.get(&handle.0)) else {
+        return;
+    };
+
+    for (_, mut list) in &mut lists {
+        for (filter, interaction, _) in &filters {
+            if interaction.is_changed() && *interaction == Interaction::Pressed {
+                list.apply(*filter);
+            }
+        }
+    }
+
+    let Some((_, shown)) = lists.iter().next() else {
+        return;
+    };
+    for (filter, _, mut color) in &mut filters {
+        *color = BackgroundColor(if shown.shows(*filter) {
+            SELECTED_BUTTON_COLOR
+        } else {
+            BUTTON_COLOR
+        });
+    }
+
+    for (entity, list) in &mut lists {
+        if !list.is_changed() {
+            continue;
+        }
+        let Some(entry) = campaign.levels.get(list.level) else {
+            continue;
+        };
+
+        let board = records.get(&entry.id, list.difficulty, list.co_op);
+        commands
+            .entity(entity)
+            .despawn_descendants()
+            .with_children(|parent| spawn_leaderboard(parent, board, None, LEADERBOARD_SIZE));
+    }
+}
+
+pub fn handle_records_back(
+    back: Query<Ref<Interaction>, With<RecordsBack>>,
+    mut next_state: ResMut<NextState<AppState>>,
+) {
+    if back
+        .iter()
+        .any(|interaction| interaction.is_changed() && *interaction == Interaction::Pressed)
+    {
+        next_state.set(AppState::MainMenu);
+    }
+}
@@ -0,0 +1,150 @@
+use bevy::prelude::*;
+
+use super::{menu_root, spawn_button, DifficultyButton};
+use crate::game::{AppState, Difficulty};
+use crate::save::Settings;
+
+/// Buttons on the settings screen
+#[derive(Component, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SettingsAction {
+    VolumeDown,
+    VolumeUp,
+    ReduceMotion,
+    UiScale,
+    VirtualJoystick,
+    Controls,
+    Back,
+}
+
+/// Text showing the current value of one setting
+#[derive(Component, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SettingValue {
+    Volume,
+    ReduceMotion,
+    UiScale,
+    VirtualJoystick,
+}
+
+impl SettingValue {
+    fn describe(&self, settings: &Settings) -> String {
+        match self {
+            SettingValue::Volume => format!("Volume: {:.0}%", settings.volume * 100.0),
+            SettingValue::ReduceMotion => format!(
+                "Reduce motion: {}",
+                if settings.reduce_motion { "on" } else { "off" }
+            ),
+            SettingValue::UiScale => format!("UI scale: {:.0}%", settings.ui_scale * 100.0),
+            SettingValue::VirtualJoystick => format!(
+                "Touch joystick: {}",
+                if settings.virtual_joystick {
+                    "on"
+                } else {
+                    "off"
+                }
+            ),
+        }
+    }
+}
+
+pub fn setup_settings_menu(mut commands: Commands, settings: Res<Settings>) {
+    commands
+        .spawn((StateScoped(AppState::Settings), menu_root()))
+        .with_children(|parent| {
+            parent.spawn((
+                Text::new("Settings"),
+                TextFont {
+                    font_size: 40.0,
+                    ..default()
+                },
+            ));
+
+            parent.spawn(Text::new("Difficulty"));
+            parent
+                .spawn(Node {
+                    column_gap: Val::Px(12.0),
+                    ..default()
+                })
+                .with_children(|row| {
+                    for difficulty in Difficulty::ALL {
+                        spawn_button(row, difficulty.label(), DifficultyButton(difficulty));
+                    }
+                });
+
+            let rows: [(SettingValue, &[(&str, SettingsAction)]); 4] = [
+                (
+                    SettingValue::Volume,
+                    &[
+                        ("-", SettingsAction::VolumeDown),
+                        ("+", SettingsAction::VolumeUp),
+                    ],
+                ),
+                (
+                    SettingValue::ReduceMotion,
+                    &[("Toggle", SettingsAction::ReduceMotion)],
+                ),
+                (
+                    SettingValue::UiScale,
+                    &[("Change", SettingsAction::UiScale)],
+                ),
+                (
+                    SettingValue::VirtualJoystick,
+                    &[("Toggle", SettingsAction::VirtualJoystick)],
+                ),
+            ];
+            for (value, buttons) in rows {
+                parent
+                    .spawn(Node {
+                        column_gap: Val::Px(12.0),
+                        align_items: AlignItems::Center,
+                        ..default()
+                    })
+                    .with_children(|row| {
+                        row.spawn((
+                            value,
+                            Text::new(value.describe(&settings)),
+                            Node {
+                                min_width: Val::Px(200.0),
+                                ..default()
+                            },
+                        ));
+                        for (label, action) in buttons {
+                            spawn_button(row, *label, *action);
+                        }
+                    });
+            }
+
+            spawn_button(parent, "Controls", SettingsAction::Controls);
+            spawn_button(parent, "Back", SettingsAction::Back);
+        });
+}
+
+pub fn handle_settings_menu(
+    buttons: Query<(&SettingsAction, Ref<Interaction>)>,
+    mut values: Query<(&SettingValue, &mut Text)>,
+    mut settings: ResMut<Settings>,
+    mut next_state: ResMut<NextState<AppState>>,
+) {
+    for (action, interaction) in &buttons {
+        if !interaction.is_changed() || *interaction != Interaction::Pressed {
+            continue;
+        }
+
+        match action {
+            SettingsAction::VolumeDown => settings.adjust_volume(-0.1),
+            SettingsAction::VolumeUp => settings.adjust_volume(0.1),
+            SettingsAction::ReduceMotion => settings.reduce_motion = !settings.reduce_motion,
+            SettingsAction::UiScale => settings.cycle_ui_scale(),
+            SettingsAction::VirtualJoystick => {
+                settings.virtual_joystick = !settings.virtual_joystick
+            }
+            SettingsAction::Controls => next_state.set(AppState::Controls),
+            SettingsAction::Back => next_state.set(AppState::MainMenu),
+        }
+    }
+
+    if settings.is_changed() {
+        for (value, mut text) in &mut values {
+            **text = value.describe(&settings);
+        }
+    }
+}
@@ -0,0 +1,79 @@
+use bevy::prelude::*;
+
+use crate::achievements::AchievementUnlocked;
+
+/// How long a toast stays on screen
+const TOAST_SECONDS: f32 = 3.0;
+
+/// Column at the top of the screen that toasts stack in
+#[derive(Component)]
+pub struct ToastStack;
+
+#[derive(Component)]
+pub struct Toast {
+    pub timer: Timer,
+}
+
+pub fn setup_toast_stack(mut commands: Commands) {
+    commands.spawn((
+        ToastStack,
+        Node {
+            position_type: PositionType::Absolute,
+            width: Val::Percent(100.0),
+            top: Val::Px(60.0),
+            flex_direction: FlexDirection::Column,
+            align_items: AlignItems::Center,
+            row_gap: Val::Px(8.0),
+            ..default()
+        },
+        // Above menus and the end screen
+        GlobalZIndex(10),
+    ));
+}
+
+pub fn spawn_achievement_toasts(
+    mut commands: Commands,
+    mut events: EventReader<AchievementUnlocked>,
+    stacks: Query<Entity, With<ToastStack>>,
+) {
+    let Ok(stack) = stacks.get_single() else {
+        return;
+    };
+
+    for event in events.read() {
+        commands.entity(stack).with_children(|parent| {
+            parent.spawn((
+                Toast {
+                    timer: Timer::from_seconds(TOAST_SECONDS, TimerMode::Once),
+                },
+                Text::new(format!(
+                    "Achievement unlocked: {}\n{}",
+                    event.name, event.description
+                )),
+                TextFont {
+                    font_size: 18.0,
+                    ..default()
+                },
+                TextLayout::new_with_justify(JustifyText::Center),
+                Node {
+                    padding: UiRect::all(Val::Px(10.0)),
+                    ..default()
+                },
+                BackgroundColor(Color::srgba(0.1, 0.1, 0.1, 0.9)),
+            ));
+        });
+    }
+}
+
+/// Count toasts down in real time, so they still go away while paused
+pub fn expire_toasts(
+    mut commands: Commands,
+    mut toasts: Query<(Entity, &mut Toast)>,
+    time: Res<Time<Real>>,
+) {
+    for (entity, mut toast) in &mut toasts {
+        if toast.timer.tick(time.delta()).finished() {
+            commands.entity(entity).despawn_recursive();
+        }
+    }
+}
@@ -0,0 +1,192 @@
+use bevy::prelude::*;
+
+use super::{BUTTON_COLOR, DISABLED_BUTTON_COLOR, MIN_TOUCH_TARGET};
+use crate::ai::PlayerBee;
+use crate::bee::{PlayerInputs, PlayerSlot, WiggleCooldown};
+use crate::controls::{InputDevice, Roster, TouchGestures, VirtualJoystick, JOYSTICK_RADIUS};
+use crate::game::{AppState, PauseState};
+
+/// Radius of the cooldown ring drawn around the player bee
+const COOLDOWN_RING_RADIUS: f32 = 28.0;
+
+/// Holder for the on-screen wiggle button, shown once the player has
+/// touched the screen
+#[derive(Component)]
+pub struct WiggleHud;
+
+#[derive(Component)]
+pub struct WiggleButton;
+
+#[derive(Component)]
+pub struct JoystickBase;
+
+#[derive(Component)]
+pub struct JoystickKnob;
+
+pub fn setup_touch_controls(mut commands: Commands) {
+    let size = MIN_TOUCH_TARGET * 2.0;
+
+    // Sits above the pause button, under the right thumb
+    commands
+        .spawn((
+            WiggleHud,
+            StateScoped(AppState::InGame),
+            Node {
+                position_type: PositionType::Absolute,
+                right: Val::Px(20.0),
+                bottom: Val::Px(32.0 + MIN_TOUCH_TARGET),
+                ..default()
+            },
+            Visibility::Hidden,
+        ))
+        .with_children(|parent| {
+            parent
+                .spawn((
+                    WiggleButton,
+                    Button,
+                    Node {
+                        width: Val::Px(size),
+                        height: Val::Px(size),
+                        justify_content: JustifyContent::Center,
+                        align_items: AlignItems::Center,
+                        ..default()
+                    },
+                    BorderRadius::MAX,
+                    BackgroundColor(BUTTON_COLOR),
+                ))
+                .with_children(|button| {
+                    button.spawn((
+                        Text::new("Wiggle"),
+                        TextFont {
+                            font_size: 18.0,
+                            ..default()
+                        },
+                        TextColor(Color::WHITE),
+                    ));
+                });
+        });
+
+    // Drawn where the joystick touch started; never takes pointer input itself
+    commands
+        .spawn((
+            JoystickBase,
+            StateScoped(AppState::InGame),
+            Node {
+                position_type: PositionType::Absolute,
+                width: Val::Px(JOYSTICK_RADIUS * 2.0),
+                height: Val::Px(JOYSTICK_RADIUS * 2.0),
+                ..default()
+            },
+            BorderRadius::MAX,
+            BackgroundColor(Color::srgba(1.0, 1.0, 1.0, 0.1)),
+            Visibility::Hidden,
+        ))
+        .with_children(|parent| {
+            parent.spawn((
+                JoystickKnob,
+                Node {
+                    position_type: PositionType::Absolute,
+                    width: Val::Px(MIN_TOUCH_TARGET),
+                    height: Val::Px(MIN_TOUCH_TARGET),
+                    ..default()
+                },
+                BorderRadius::MAX,
+                BackgroundColor(Color::srgba(1.0, 1.0, 1.0, 0.35)),
+            ));
+        });
+}
+
+/// Show the wiggle button while a round runs on a touch screen, dimmed
+/// while the touch player's wiggle is cooling down
+pub fn update_wiggle_button(
+    state: Option<Res<State<PauseState>>>,
+    gestures: Res<TouchGestures>,
+    roster: Res<Roster>,
+    players: Query<(&PlayerSlot, &WiggleCooldown), With<PlayerBee>>,
+    mut huds: Query<&mut Visibility, With<WiggleHud>>,
+    mut buttons: Query<&mut BackgroundColor, With<WiggleButton>>,
+) {
+    let running = state.is_some_and(|state| *state.get() == PauseState::Running);
+    for mut visibility in &mut huds {
+        let owned = roster.player_of(InputDevice::Pointer).is_some();
+        *visibility = if running && owned && gestures.seen_touch {
+            Visibility::Visible
+        } else {
+            Visibility::Hidden
+        };
+    }
+
+    let player = roster.player_of(InputDevice::Pointer);
+    let cooling = players
+        .iter()
+        .any(|(slot, cooldown)| Some(slot.0) == player && !cooldown.is_ready());
+    for mut color in &mut buttons {
+        *color = BackgroundColor(if cooling {
+            DISABLED_BUTTON_COLOR
+        } else {
+            BUTTON_COLOR
+        });
+    }
+}
+
+/// The button belongs to whoever plays with the touch screen
+pub fn handle_wiggle_button(
+    buttons: Query<Ref<Interaction>, With<WiggleButton>>,
+    roster: Res<Roster>,
+    mut inputs: ResMut<PlayerInputs>,
+) {
+    let Some(player) = roster.player_of(InputDevice::Pointer) else {
+        return;
+    };
+    for interaction in &buttons {
+        if interaction.is_changed() && *interaction == Interaction::Pressed {
+            inputs.player_mut(player).wiggle = true;
+        }
+    }
+}
+
+/// Ring around the player bee that empties as `WiggleCooldown` runs down
+pub fn draw_wiggle_cooldown_ring(
+    mut gizmos: Gizmos,
+    players: Query<(&GlobalTransform, &WiggleCooldown), With<PlayerBee>>,
+) {
+    for (transform, cooldown) in &players {
+        let remaining = cooldown.remaining_fraction();
+        if remaining <= 0.0 {
+            continue;
+        }
+        let center = Isometry2d::from_translation(transform.translation().truncate());
+        gizmos.arc_2d(
+            center,
+            remaining * std::f32::consts::TAU,
+            COOLDOWN_RING_RADIUS,
+            Color::srgba(1.0, 0.9, 0.3, 0.8),
+        );
+    }
+}
+
+/// Follow the virtual joystick's touch with its base and knob
+#[allow(clippy::type_complexity)]
+pub fn update_joystick_display(
+    joystick: Res<VirtualJoystick>,
+    ui_scale: Res<UiScale>,
+    mut bases: Query<(&mut Node, &mut Visibility), (With<JoystickBase>, Without<JoystickKnob>)>,
+    mut knobs: Query<&mut Node, With<JoystickKnob>>,
+) {
+    for (mut node, mut visibility) in &mut bases {
+        if joystick.touch.is_none() {
+            *visibility = Visibility::Hidden;
+            continue;
+        }
+        // Touches are in logical pixels, UI sizes get multiplied by `UiScale`
+        *visibility = Visibility::Visible;
+        node.left = Val::Px(joystick.origin.x / ui_scale.0 - JOYSTICK_RADIUS);
+        node.top = Val::Px(joystick.origin.y / ui_scale.0 - JOYSTICK_RADIUS);
+    }
+
+    let knob = joystick.axis() * JOYSTICK_RADIUS;
+    for mut node in &mut knobs {
+        node.left = Val::Px(JOYSTICK_RADIUS + knob.x - MIN_TOUCH_TARGET / 2.0);
+        node.top = Val::Px(JOYSTICK_RADIUS - knob.y - MIN_TOUCH_TARGET / 2.0);
+    }
+}
@@ -0,0 +1,40 @@
+use bevy::prelude::*;
+
+use super::MIN_TOUCH_TARGET;
+
+pub const BUTTON_COLOR: Color = Color::srgba(1.0, 1.0, 1.0, 0.15);
+pub const SELECTED_BUTTON_COLOR: Color = Color::srgb(0.9, 0.6, 0.2);
+pub const DISABLED_BUTTON_COLOR: Color = Color::srgba(0.3, 0.3, 0.3, 0.5);
+
+/// Spawn a touch-friendly text button carrying `marker`
+pub fn spawn_button(
+    parent: &mut ChildBuilder,
+    label: impl Into<String>,
+    marker: impl Bundle,
+) -> Entity {
+    parent
+        .spawn((
+            marker,
+            Button,
+            Node {
+                min_width: Val::Px(120.0),
+                min_height: Val::Px(MIN_TOUCH_TARGET),
+                padding: UiRect::horizontal(Val::Px(12.0)),
+                justify_content: JustifyContent::Center,
+                align_items: AlignItems::Center,
+                ..default()
+            },
+            BackgroundColor(BUTTON_COLOR),
+        ))
+        .with_children(|button| {
+            button.spawn((
+                Text::new(label),
+                TextFont {
+                    font_size: 20.0,
+                    ..default()
+                },
+                TextColor(Color::WHITE),
+            ));
+        })
+        .id()
+}
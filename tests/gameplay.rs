@@ -0,0 +1,459 @@
+//! Whole-game behaviour, driven through the headless harness
+
+use allerbees::headless::HeadlessGame;
+use allerbees::prelude::*;
+use std::time::Duration;
+
+use bevy::prelude::*;
+use bevy::time::TimeUpdateStrategy;
+
+const MEADOW: &str = concat!(
+    env!("CARGO_MANIFEST_DIR"),
+    "/assets/levels/meadow.level.ron"
+);
+
+/// A garden with a single flower whose head sits still at `head`
+fn garden(player_spawn: Vec2, head: Vec2) -> LevelDefinition {
+    let stem_offset = Vec2::new(0.0, 120.0);
+    LevelDefinition {
+        id: "test".to_string(),
+        name: "Test Garden".to_string(),
+        play_area: Vec2::new(700.0, 500.0),
+        player_spawn,
+        companions: Vec::new(),
+        flowers: vec![FlowerDef {
+            position: head - stem_offset,
+            stem_height: 200.0,
+            caches: Vec::new(),
+            heads: vec![HeadDef {
+                pattern: HeadPattern::Sway {
+                    amplitude: 0.0,
+                    speed: 0.0,
+                },
+                offset: Vec2::ZERO,
+                size: 45.0,
+                color: (1.0, 0.4, 0.6),
+                pollen_interval: 60.0,
+            }],
+        }],
+        rules: RuleOverrides::default(),
+    }
+}
+
+fn wiggle() -> PlayerInput {
+    PlayerInput {
+        wiggle: true,
+        ..default()
+    }
+}
+
+#[test]
+fn sneezing_three_times_loses_the_round() {
+    let mut game = HeadlessGame::default();
+    game.load_level(&garden(Vec2::new(-200.0, 0.0), Vec2::new(300.0, 0.0)));
+    let player = game.player();
+
+    for _ in 0..3 {
+        assert_eq!(game.state(), GameState::Playing);
+        game.world_mut()
+            .get_mut::<AllergyMeter>(player)
+            .unwrap()
+            .value = 90.0;
+        // Long enough for the stagger to wear off
+        game.step(40);
+    }
+
+    assert_eq!(game.world().get::<SneezeCount>(player).unwrap().count, 3);
+    assert_eq!(game.state(), GameState::Lost);
+}
+
+#[test]
+fn wiggle_raises_rizz_of_nearby_heads() {
+    let mut game = HeadlessGame::default();
+    game.load_level(&garden(Vec2::new(0.0, 40.0), Vec2::ZERO));
+
+    game.step_with_input(wiggle());
+    // The rizz lands when the wiggle finishes
+    game.step(40);
+
+    let world = game.world_mut();
+    let rizz = world.query::<&FlowerHead>().single(world).rizz;
+    assert!(rizz > 0.0, "rizz was {rizz}");
+}
+
+#[test]
+fn wiggle_respects_cooldown() {
+    let mut game = HeadlessGame::default();
+    game.load_level(&garden(Vec2::new(0.0, 40.0), Vec2::ZERO));
+    let player = game.player();
+
+    game.step_with_input(wiggle());
+    game.step(40);
+    assert!(game.world().get::<WiggleCooldown>(player).is_some());
+
+    game.step_with_input(wiggle());
+    assert!(game.world().get::<Wiggling>(player).is_none());
+}
+
+#[test]
+fn bees_stay_inside_the_play_area() {
+    let mut game = HeadlessGame::default();
+    game.load_level(&garden(Vec2::new(300.0, 0.0), Vec2::new(-300.0, 0.0)));
+    let player = game.player();
+
+    game.step_with_input(PlayerInput {
+        move_target: Some(Vec2::new(1000.0, 0.0)),
+        ..default()
+    });
+    game.step(120);
+
+    let position = game.world().get::<Transform>(player).unwrap().translation;
+    assert_eq!(position.x, 350.0);
+}
+
+/// Walk the first player around the meadow, wiggling once on the way
+fn play_meadow_script(game: &mut HeadlessGame) {
+    let waypoints = [
+        Vec2::new(0.0, 60.0),
+        Vec2::new(150.0, 120.0),
+        Vec2::new(-100.0, 150.0),
+    ];
+    for (i, target) in waypoints.into_iter().enumerate() {
+        game.step_with_input(PlayerInput {
+            move_target: Some(target),
+            wiggle: i == 1,
+            ..default()
+        });
+        game.step(120);
+    }
+}
+
+/// Play the meadow with a fixed seed and script, returning where things ended up
+fn scripted_run(seed: u64) -> (Vec3, u32, f32) {
+    let mut game = HeadlessGame::default();
+    game.set_seed(seed);
+    game.load_level_file(MEADOW).unwrap();
+    play_meadow_script(&mut game);
+
+    let player = game.player();
+    let world = game.world();
+    (
+        world.get::<Transform>(player).unwrap().translation,
+        world.get::<CollectedPollen>(player).unwrap().count,
+        world.get::<AllergyMeter>(player).unwrap().value,
+    )
+}
+
+#[test]
+fn same_seed_and_input_replay_identically() {
+    assert_eq!(scripted_run(11), scripted_run(11));
+}
+
+/// Everything a round start has to put back
+#[derive(Debug, PartialEq)]
+struct GardenState {
+    /// Position, pollen, allergy and sneezes of the player
+    bee: (Vec3, u32, f32, u32),
+    /// Position and rizz of each head, in level order
+    heads: Vec<(Vec3, f32)>,
+    /// Whether each stem cache is ready, in level order
+    caches: Vec<bool>,
+    /// Pollen lying on the ground
+    pollen: Vec<Vec3>,
+}
+
+fn garden_state(game: &mut HeadlessGame) -> GardenState {
+    let player = game.player();
+    let world = game.world_mut();
+    let bee = (
+        world.get::<Transform>(player).unwrap().translation,
+        world.get::<CollectedPollen>(player).unwrap().count,
+        world.get::<AllergyMeter>(player).unwrap().value,
+        world.get::<SneezeCount>(player).unwrap().count,
+    );
+
+    let mut heads: Vec<_> = world
+        .query::<(&LevelIndex, &Transform, &FlowerHead)>()
+        .iter(world)
+        .map(|(index, transform, head)| (index.0, (transform.translation, head.rizz)))
+        .collect();
+    heads.sort_by_key(|(index, _)| *index);
+
+    let mut caches: Vec<_> = world
+        .query::<(&LevelIndex, &CacheSpawnPoint)>()
+        .iter(world)
+        .map(|(index, cache)| (index.0, cache.is_active))
+        .collect();
+    caches.sort_by_key(|(index, _)| *index);
+
+    let mut pollen: Vec<Vec3> = world
+        .query_filtered::<&Transform, With<Pollen>>()
+        .iter(world)
+        .map(|transform| transform.translation)
+        .collect();
+    pollen.sort_by(|a, b| a.x.total_cmp(&b.x).then(a.y.total_cmp(&b.y)));
+
+    GardenState {
+        bee,
+        heads: heads.into_iter().map(|(_, head)| head).collect(),
+        caches: caches.into_iter().map(|(_, cache)| cache).collect(),
+        pollen,
+    }
+}
+
+#[test]
+fn restarting_a_round_matches_a_fresh_start() {
+    // Sneezes can't end the round early, so the whole script plays out
+    let mut config = GameConfig::default();
+    config.win_lose.no_fail = true;
+
+    let mut fresh = HeadlessGame::new(config.clone());
+    fresh.set_seed(5);
+    fresh.load_level_file(MEADOW).unwrap();
+    play_meadow_script(&mut fresh);
+
+    let mut restarted = HeadlessGame::new(config);
+    restarted.set_seed(5);
+    restarted.load_level_file(MEADOW).unwrap();
+    play_meadow_script(&mut restarted);
+    restarted.load_level_file(MEADOW).unwrap();
+    play_meadow_script(&mut restarted);
+
+    assert_eq!(garden_state(&mut restarted), garden_state(&mut fresh));
+}
+
+/// Input for one tick of a script that walks, wiggles and steers around
+/// the meadow
+fn scripted_input(tick: Res<SimulationTick>, mut inputs: ResMut<PlayerInputs>) {
+    let input = PlayerInput {
+        move_target: match tick.0 {
+            0 => Some(Vec2::new(0.0, 60.0)),
+            120 => Some(Vec2::new(150.0, 120.0)),
+            _ => None,
+        },
+        wiggle: tick.0 == 130,
+        direction: if (240..300).contains(&tick.0) {
+            Vec2::new(-1.0, 0.5)
+        } else {
+            Vec2::ZERO
+        },
+        ..default()
+    };
+    *inputs = PlayerInputs {
+        players: vec![input],
+    };
+}
+
+/// Player position, pollen and allergy, then each head's position and rizz
+type RoundState = (Vec3, u32, f32, Vec<(Vec3, f32)>);
+
+/// What the round looks like at `CHECKPOINT`
+#[derive(Resource, Default)]
+struct Checkpoint(Option<RoundState>);
+
+const CHECKPOINT: u32 = 360;
+
+fn take_checkpoint(
+    tick: Res<SimulationTick>,
+    players: Query<(&Transform, &CollectedPollen, &AllergyMeter), With<PlayerBee>>,
+    heads: Query<(&Transform, &FlowerHead)>,
+    mut checkpoint: ResMut<Checkpoint>,
+) {
+    if tick.0 != CHECKPOINT {
+        return;
+    }
+    let (transform, pollen, allergy) = players.single();
+    let heads = heads
+        .iter()
+        .map(|(transform, head)| (transform.translation, head.rizz))
+        .collect();
+    checkpoint.0 = Some((transform.translation, pollen.count, allergy.value, heads));
+}
+
+/// Play the meadow script with `frame` of real time passing per update
+fn run_at_frame_length(frame: Duration) -> RoundState {
+    // Sneezes can't end the round early, so the whole script plays out
+    let mut config = GameConfig::default();
+    config.win_lose.no_fail = true;
+    let mut game = HeadlessGame::new(config);
+    game.set_seed(3);
+    game.load_level_file(MEADOW).unwrap();
+    game.app_mut()
+        .init_resource::<Checkpoint>()
+        .insert_resource(TimeUpdateStrategy::ManualDuration(frame))
+        .add_systems(FixedUpdate, scripted_input.before(apply_player_input))
+        .add_systems(FixedPostUpdate, take_checkpoint);
+
+    while game.tick() <= CHECKPOINT {
+        assert_eq!(game.state(), GameState::Playing, "round ended early");
+        game.app_mut().update();
+    }
+    game.world_mut()
+        .resource_mut::<Checkpoint>()
+        .0
+        .take()
+        .unwrap()
+}
+
+#[test]
+fn frame_rate_does_not_change_the_round() {
+    let slow = run_at_frame_length(Duration::from_secs_f64(1.0 / 30.0));
+    let fast = run_at_frame_length(Duration::from_secs_f64(1.0 / 144.0));
+    assert_eq!(slow, fast);
+}
+
+#[test]
+fn pausing_freezes_the_round() {
+    let mut game = HeadlessGame::default();
+    game.load_level(&garden(Vec2::new(-200.0, 0.0), Vec2::new(300.0, 0.0)));
+    game.step(30);
+
+    game.world_mut()
+        .resource_mut::<NextState<PauseState>>()
+        .set(PauseState::Paused);
+    game.step(1);
+    let tick = game.tick();
+    let elapsed = game.world().resource::<SessionTimer>().elapsed;
+
+    game.step(120);
+    assert_eq!(game.tick(), tick);
+    assert_eq!(game.world().resource::<SessionTimer>().elapsed, elapsed);
+
+    game.world_mut()
+        .resource_mut::<NextState<PauseState>>()
+        .set(PauseState::Running);
+    game.step(10);
+    assert!(game.tick() > tick);
+}
+
+#[test]
+fn co_op_bees_each_follow_their_own_input() {
+    let mut game = HeadlessGame::default();
+    game.set_players(2);
+    game.load_level(&garden(Vec2::new(-200.0, 0.0), Vec2::new(300.0, 0.0)));
+    let players = game.players();
+    assert_eq!(players.len(), 2);
+
+    game.step_with_inputs(vec![
+        PlayerInput::default(),
+        PlayerInput {
+            move_target: Some(Vec2::new(-200.0, -150.0)),
+            ..default()
+        },
+    ]);
+    game.step(60);
+
+    let position = |game: &HeadlessGame, bee| {
+        game.world()
+            .get::<Transform>(bee)
+            .unwrap()
+            .translation
+            .truncate()
+    };
+    let first = position(&game, players[0]);
+    let second = position(&game, players[1]);
+    assert!(first.y > -30.0, "first bee moved to {first}");
+    assert!(second.y < -100.0, "second bee stayed at {second}");
+}
+
+#[test]
+fn co_op_team_pools_pollen_to_win() {
+    let mut game = HeadlessGame::default();
+    game.set_players(2);
+    game.load_level(&garden(Vec2::new(-200.0, 0.0), Vec2::new(300.0, 0.0)));
+    let target = game.world().resource::<GameConfig>().win_lose.win_pollen;
+
+    for bee in game.players() {
+        game.world_mut()
+            .get_mut::<CollectedPollen>(bee)
+            .unwrap()
+            .count = target / 2 + 1;
+    }
+    // The win is seen during one tick and the round ends on the next
+    game.step(2);
+
+    assert_eq!(game.state(), GameState::Won);
+}
+
+#[test]
+fn emotes_show_over_the_bee_for_a_while() {
+    let mut game = HeadlessGame::default();
+    game.load_level(&garden(Vec2::new(-200.0, 0.0), Vec2::new(300.0, 0.0)));
+    let player = game.player();
+
+    game.step_with_input(PlayerInput {
+        emote: Some(Emote::Wave),
+        ..default()
+    });
+    let emoting = game.world().get::<Emoting>(player).unwrap();
+    assert_eq!(emoting.emote, Emote::Wave);
+
+    game.step((EMOTE_SECONDS * 60.0) as u32 + 1);
+    assert!(game.world().get::<Emoting>(player).is_none());
+}
+
+#[test]
+fn the_healer_says_when_it_is_coming_to_help() {
+    let mut level = garden(Vec2::new(-200.0, 0.0), Vec2::new(300.0, 0.0));
+    level.companions.push(CompanionSpawn {
+        kind: CompanionKind::Healer,
+        position: Vec2::new(0.0, 200.0),
+    });
+    let mut game = HeadlessGame::default();
+    game.load_level(&level);
+
+    let healer = {
+        let world = game.world_mut();
+        world
+            .query_filtered::<Entity, With<AiHealer>>()
+            .single(world)
+    };
+    game.step(1);
+    assert!(game.world().get::<Emoting>(healer).is_none());
+
+    let player = game.player();
+    game.world_mut()
+        .get_mut::<AllergyMeter>(player)
+        .unwrap()
+        .value = 70.0;
+    game.step(1);
+    let emoting = game.world().get::<Emoting>(healer).unwrap();
+    assert_eq!(emoting.emote, Emote::ComingToHelp);
+}
+
+#[test]
+fn a_healer_player_heals_their_teammates() {
+    // Lobby rooms leave out the healer companion when a player is the healer
+    let level = fill_companions(
+        &garden(Vec2::new(-200.0, 0.0), Vec2::new(300.0, 0.0)),
+        &[Role::Gatherer, Role::Healer],
+        2,
+    );
+    assert!(level.companions.is_empty());
+    let mut game = HeadlessGame::default();
+    game.set_players(2);
+    game.load_level(&level);
+    let players = game.players();
+    game.world_mut().get_mut::<Bee>(players[1]).unwrap().role = Role::Healer;
+    for (bee, y) in players.iter().zip([0.0, 20.0]) {
+        game.world_mut()
+            .get_mut::<Transform>(*bee)
+            .unwrap()
+            .translation = Vec3::new(-200.0, y, 1.0);
+    }
+    for bee in &players {
+        game.world_mut()
+            .get_mut::<AllergyMeter>(*bee)
+            .unwrap()
+            .value = 70.0;
+    }
+
+    game.step(60);
+    let allergy = |game: &HeadlessGame, bee| game.world().get::<AllergyMeter>(bee).unwrap().value;
+    let healed = allergy(&game, players[0]);
+    let healer = allergy(&game, players[1]);
+    assert!(
+        healed < healer - 10.0,
+        "healed to {healed}, healer at {healer}"
+    );
+}
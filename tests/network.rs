@@ -0,0 +1,492 @@
+//! Online play against a garden server running on this machine
+
+use std::net::SocketAddr;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{mpsc, Arc};
+use std::thread::JoinHandle;
+use std::time::{Duration, Instant};
+
+use allerbees::headless::HeadlessGame;
+use allerbees::net::{
+    encode, ClientMessage, Connection, GardenServer, Intent, LobbyServer, Prediction, RoomState,
+    ServerMessage, Snapshot, PROTOCOL_VERSION,
+};
+use allerbees::prelude::*;
+use bevy::prelude::*;
+
+const MEADOW: &str = concat!(
+    env!("CARGO_MANIFEST_DIR"),
+    "/assets/levels/meadow.level.ron"
+);
+
+/// Longest any test waits on the server
+const TIMEOUT: Duration = Duration::from_secs(5);
+
+/// A garden or lobby server on a free local port, stopped when dropped
+struct LocalServer {
+    address: SocketAddr,
+    stop: Arc<AtomicBool>,
+    thread: Option<JoinHandle<()>>,
+}
+
+impl LocalServer {
+    fn start() -> Self {
+        Self::spawn(|level, sender, running| {
+            let mut server =
+                GardenServer::bind("127.0.0.1:0", GameConfig::default(), level).unwrap();
+            sender.send(server.local_addr()).unwrap();
+            server.run(running);
+        })
+    }
+
+    /// A lobby whose rooms take `cap` bees
+    fn start_lobby(cap: usize) -> Self {
+        Self::spawn(move |level, sender, running| {
+            let mut lobby =
+                LobbyServer::bind("127.0.0.1:0", GameConfig::default(), level, cap).unwrap();
+            sender.send(lobby.local_addr()).unwrap();
+            lobby.run(running);
+        })
+    }
+
+    fn spawn(
+        serve: impl FnOnce(LevelDefinition, mpsc::Sender<SocketAddr>, &AtomicBool) + Send + 'static,
+    ) -> Self {
+        let level = LevelDefinition::from_ron(&std::fs::read(MEADOW).unwrap()).unwrap();
+        let stop = Arc::new(AtomicBool::new(false));
+        let (sender, receiver) = mpsc::channel();
+
+        // The game isn't `Send`, so it's built on the thread that runs it
+        let running = stop.clone();
+        let thread = std::thread::spawn(move || serve(level, sender, &running));
+
+        Self {
+            address: receiver.recv_timeout(TIMEOUT).unwrap(),
+            stop,
+            thread: Some(thread),
+        }
+    }
+
+    fn url(&self) -> String {
+        format!("ws://{}", self.address)
+    }
+}
+
+impl Drop for LocalServer {
+    fn drop(&mut self) {
+        self.stop.store(true, Ordering::Relaxed);
+        if let Some(thread) = self.thread.take() {
+            let _ = thread.join();
+        }
+    }
+}
+
+/// Read from `connection` until a message passes `check`, returning what it
+/// returned
+fn wait_for<T>(
+    connection: &mut Connection,
+    mut check: impl FnMut(&ServerMessage) -> Option<T>,
+) -> T {
+    let start = Instant::now();
+    while start.elapsed() < TIMEOUT {
+        let mut messages = Vec::new();
+        connection.receive(&mut messages).unwrap();
+        if let Some(found) = messages.iter().find_map(&mut check) {
+            return found;
+        }
+        std::thread::sleep(Duration::from_millis(5));
+    }
+    panic!("server didn't send the expected message in time");
+}
+
+/// Run `server` on this thread until `done` says so
+fn serve_until(server: &mut GardenServer, done: impl Fn(&GardenServer) -> bool) {
+    let start = Instant::now();
+    while !done(server) {
+        assert!(start.elapsed() < TIMEOUT, "server didn't get there in time");
+        server.update();
+        std::thread::sleep(Duration::from_millis(5));
+    }
+}
+
+fn join(server: &LocalServer) -> (Connection, usize) {
+    let mut connection = Connection::connect(&server.url()).unwrap();
+    let player = wait_for(&mut connection, |message| match message {
+        ServerMessage::Welcome { player, .. } => Some(*player),
+        _ => None,
+    });
+    (connection, player)
+}
+
+fn next_snapshot(connection: &mut Connection, check: impl Fn(&Snapshot) -> bool) -> Snapshot {
+    wait_for(connection, |message| match message {
+        ServerMessage::Snapshot(snapshot) if check(snapshot) => Some(snapshot.clone()),
+        _ => None,
+    })
+}
+
+fn hold(connection: &mut Connection, direction: Vec2) {
+    connection
+        .send(&ClientMessage::Intent(Intent {
+            direction,
+            ..default()
+        }))
+        .unwrap();
+}
+
+#[test]
+fn two_clients_steer_their_own_bees() {
+    let server = LocalServer::start();
+    let (mut first, first_player) = join(&server);
+    let (mut second, second_player) = join(&server);
+    assert_ne!(first_player, second_player);
+
+    let start = next_snapshot(&mut first, |snapshot| snapshot.bees.len() == 2);
+    let first_start = start.bee(first_player).unwrap().position;
+    let second_start = start.bee(second_player).unwrap().position;
+
+    hold(&mut first, Vec2::X);
+    hold(&mut second, Vec2::NEG_X);
+
+    // Both clients see the same garden: each bee went its own way
+    let check = |snapshot: &Snapshot| {
+        let moved = |player, start: Vec2| {
+            snapshot
+                .bee(player)
+                .map_or(0.0, |bee| bee.position.x - start.x)
+        };
+        moved(first_player, first_start) > 30.0 && moved(second_player, second_start) < -30.0
+    };
+    next_snapshot(&mut first, check);
+    next_snapshot(&mut second, check);
+}
+
+#[test]
+fn a_client_cannot_send_its_bee_to_nowhere() {
+    let server = LocalServer::start();
+    let (mut socket, _) = tungstenite::connect(server.url()).unwrap();
+    let mut send = |text: &str| socket.send(tungstenite::Message::text(text)).unwrap();
+    send(&encode(&ClientMessage::Hello {
+        version: PROTOCOL_VERSION,
+    }));
+
+    // Too big for an f32, so these arrive as infinities
+    let overflowing =
+        r#"{"Intent":{"sequence":1,"direction":[1e39,-1e39],"move_target":[1e39,0.0]}}"#;
+    let ClientMessage::Intent(intent) = allerbees::net::decode(overflowing).unwrap() else {
+        panic!("expected an intent");
+    };
+    assert!(!intent.direction.is_finite());
+    send(overflowing);
+    send(&encode(&ClientMessage::Intent(Intent {
+        sequence: 2,
+        direction: Vec2::X,
+        ..default()
+    })));
+
+    let start = Instant::now();
+    let mut first = None;
+    loop {
+        assert!(start.elapsed() < TIMEOUT, "the bee never moved");
+        let message = socket.read().unwrap();
+        let Ok(ServerMessage::Snapshot(snapshot)) =
+            allerbees::net::decode(message.to_text().unwrap())
+        else {
+            continue;
+        };
+        let Some(bee) = snapshot.bees.first() else {
+            continue;
+        };
+        assert!(bee.position.is_finite(), "bee went to {}", bee.position);
+        let first = *first.get_or_insert(bee.position);
+        if bee.ack == 2 && bee.position.x - first.x > 30.0 {
+            break;
+        }
+    }
+}
+
+#[test]
+fn emotes_reach_the_other_players() {
+    let server = LocalServer::start();
+    let (mut first, first_player) = join(&server);
+    let (mut second, _) = join(&server);
+
+    first
+        .send(&ClientMessage::Intent(Intent {
+            emote: Some(Emote::Heart),
+            ..default()
+        }))
+        .unwrap();
+    next_snapshot(&mut second, |snapshot| {
+        snapshot
+            .bee(first_player)
+            .is_some_and(|bee| bee.emote == Some(Emote::Heart))
+    });
+}
+
+#[test]
+fn leaving_takes_the_bee_out_of_the_garden() {
+    let server = LocalServer::start();
+    let (mut staying, _) = join(&server);
+    let (leaving, leaving_player) = join(&server);
+    next_snapshot(&mut staying, |snapshot| snapshot.bees.len() == 2);
+
+    drop(leaving);
+    let snapshot = next_snapshot(&mut staying, |snapshot| snapshot.bees.len() == 1);
+    assert!(snapshot.bee(leaving_player).is_none());
+
+    // The slot is free for the next bee
+    let (_, rejoined_player) = join(&server);
+    assert_eq!(rejoined_player, leaving_player);
+}
+
+#[test]
+fn the_predicted_bee_ends_up_where_the_server_puts_it() {
+    // No sneezes: the client leaves those to the server
+    let mut config = GameConfig::default();
+    config.allergy.proximity_multiplier = 0.0;
+    let level = LevelDefinition::from_ron(&std::fs::read(MEADOW).unwrap()).unwrap();
+    let mut server = HeadlessGame::new(config);
+    server.load_level(&level);
+    let config = server.world().resource::<GameConfig>().clone();
+    let timestep = server.timestep();
+
+    let mut prediction = Prediction::default();
+    prediction.set_play_area(PlayArea(level.play_area));
+    let start = capture_snapshot(&mut server, 0);
+    prediction.reconcile(0, start.bee(0).unwrap(), &config, timestep);
+
+    // Fly right into the edge of the garden, let go and coast to a stop, then
+    // wiggle. The server applies each intent on its own tick, as a garden
+    // does, and the client checks itself against every snapshot.
+    for tick in 1..=300 {
+        let input = PlayerInput {
+            direction: if tick <= 240 { Vec2::X } else { Vec2::ZERO },
+            wiggle: tick == 270,
+            ..default()
+        };
+        let intent = prediction.apply(&input, &config, timestep);
+        server.step_with_input(PlayerInput::from(&intent));
+        if tick == 260 {
+            let edge = level.play_area.x / 2.0;
+            assert_eq!(prediction.bee().unwrap().position.x, edge);
+        }
+
+        if tick % SNAPSHOT_INTERVAL == 0 {
+            let snapshot = capture_snapshot(&mut server, tick);
+            let mut state = snapshot.bee(0).unwrap().clone();
+            state.ack = intent.sequence;
+            prediction.reconcile(tick, &state, &config, timestep);
+            assert!(
+                prediction.error().length() < 1e-3,
+                "tick {tick}: predicted {:?}, server had {}",
+                prediction.bee().map(|bee| bee.position),
+                state.position
+            );
+        }
+    }
+
+    let bee = server.player();
+    let actual = server.world().get::<Transform>(bee).unwrap().translation;
+    let predicted = prediction.bee().unwrap().position;
+    assert!(actual.truncate().distance(predicted) < 1e-3);
+}
+
+/// Rizz of every flower head, in level order
+fn head_rizz(server: &mut GardenServer) -> Vec<f32> {
+    let world = server.game().world_mut();
+    let mut heads: Vec<(usize, f32)> = world
+        .query::<(&LevelIndex, &FlowerHead)>()
+        .iter(world)
+        .map(|(index, head)| (index.0, head.rizz))
+        .collect();
+    heads.sort_by_key(|(index, _)| *index);
+    heads.into_iter().map(|(_, rizz)| rizz).collect()
+}
+
+#[test]
+fn a_persistent_garden_picks_up_where_it_was_left() {
+    let level = LevelDefinition::from_ron(&std::fs::read(MEADOW).unwrap()).unwrap();
+    let path = std::env::temp_dir().join(format!("allerbees-world-{}.ron", std::process::id()));
+    let _ = std::fs::remove_file(&path);
+
+    let mut server =
+        GardenServer::bind("127.0.0.1:0", GameConfig::default(), level.clone()).unwrap();
+    server.persist_to(&path).unwrap();
+    {
+        let world = server.game().world_mut();
+        for mut head in world.query::<&mut FlowerHead>().iter_mut(world) {
+            head.rizz = 42.0;
+        }
+        let mut caches = world.query::<&mut CacheSpawnPoint>();
+        let mut cache = caches.iter_mut(world).next().unwrap();
+        cache.is_active = false;
+        cache.respawn_timer.reset();
+    }
+
+    // A bee leaves carrying pollen
+    let url = format!("ws://{}", server.local_addr());
+    let (leave, told_to_leave) = mpsc::channel();
+    let client = std::thread::spawn(move || {
+        let mut connection = Connection::connect(&url).unwrap();
+        wait_for(&mut connection, |message| match message {
+            ServerMessage::Welcome { .. } => Some(()),
+            _ => None,
+        });
+        told_to_leave.recv_timeout(TIMEOUT).unwrap();
+    });
+    serve_until(&mut server, |server| server.players() == 1);
+    {
+        let world = server.game().world_mut();
+        let mut bees = world.query_filtered::<&mut CollectedPollen, With<PlayerBee>>();
+        bees.single_mut(world).count = 7;
+    }
+    leave.send(()).unwrap();
+    serve_until(&mut server, |server| server.players() == 0);
+    client.join().unwrap();
+    assert_eq!(server.harvested(), 7);
+    let left_rizz = head_rizz(&mut server);
+    assert!(left_rizz.iter().all(|rizz| *rizz > 0.0));
+    drop(server);
+
+    // A restarted server has the same garden
+    let mut server = GardenServer::bind("127.0.0.1:0", GameConfig::default(), level).unwrap();
+    server.persist_to(&path).unwrap();
+    assert_eq!(head_rizz(&mut server), left_rizz);
+    let world = server.game().world_mut();
+    let resting = world
+        .query::<&CacheSpawnPoint>()
+        .iter(world)
+        .filter(|cache| !cache.is_active)
+        .count();
+    assert_eq!(resting, 1);
+    assert_eq!(server.harvested(), 7);
+
+    std::fs::remove_file(&path).unwrap();
+}
+
+#[test]
+fn stopping_the_server_saves_the_garden() {
+    let level = LevelDefinition::from_ron(&std::fs::read(MEADOW).unwrap()).unwrap();
+    let path = std::env::temp_dir().join(format!("allerbees-stop-{}.ron", std::process::id()));
+    let _ = std::fs::remove_file(&path);
+
+    let mut server =
+        GardenServer::bind("127.0.0.1:0", GameConfig::default(), level.clone()).unwrap();
+    server.persist_to(&path).unwrap();
+    {
+        let world = server.game().world_mut();
+        for mut head in world.query::<&mut FlowerHead>().iter_mut(world) {
+            head.rizz = 42.0;
+        }
+    }
+
+    // As the server binary's Ctrl-C handler does
+    let stop = AtomicBool::new(true);
+    server.run(&stop);
+
+    let saved = WorldState::load(&path, &level).unwrap().unwrap();
+    assert!(saved.rizz.iter().all(|rizz| *rizz == 42.0));
+    std::fs::remove_file(&path).unwrap();
+}
+
+#[test]
+fn clients_on_another_protocol_are_turned_away() {
+    let server = LocalServer::start();
+    let (mut socket, _) = tungstenite::connect(server.url()).unwrap();
+    socket
+        .send(tungstenite::Message::text(encode(&ClientMessage::Hello {
+            version: PROTOCOL_VERSION + 1,
+        })))
+        .unwrap();
+
+    let reply = socket.read().unwrap();
+    let message: ServerMessage = allerbees::net::decode(reply.to_text().unwrap()).unwrap();
+    assert!(matches!(message, ServerMessage::Rejected { .. }));
+}
+
+#[test]
+fn a_silent_connection_does_not_hold_up_the_garden() {
+    let server = LocalServer::start();
+    // Connects, but never starts its handshake
+    let _silent = std::net::TcpStream::connect(server.address).unwrap();
+
+    let start = Instant::now();
+    let (mut connection, _) = join(&server);
+    next_snapshot(&mut connection, |_| true);
+    assert!(start.elapsed() < Duration::from_millis(500));
+}
+
+#[test]
+fn secure_servers_are_refused_up_front() {
+    assert!(Connection::connect("wss://example.com").is_err());
+}
+
+fn room_update(connection: &mut Connection, check: impl Fn(&RoomState) -> bool) -> RoomState {
+    wait_for(connection, |message| match message {
+        ServerMessage::Room(room) if check(room) => Some(room.clone()),
+        _ => None,
+    })
+}
+
+#[test]
+fn players_meet_in_a_lobby_room_and_start_together() {
+    let lobby = LocalServer::start_lobby(3);
+    let mut host = Connection::connect(&lobby.url()).unwrap();
+    let cap = wait_for(&mut host, |message| match message {
+        ServerMessage::Lobby { cap } => Some(*cap),
+        _ => None,
+    });
+    assert_eq!(cap, 3);
+    host.send(&ClientMessage::CreateRoom).unwrap();
+    let code = room_update(&mut host, |_| true).code;
+
+    let mut guest = Connection::connect(&lobby.url()).unwrap();
+    guest
+        .send(&ClientMessage::JoinRoom {
+            code: "ZZZZ".to_string(),
+        })
+        .unwrap();
+    wait_for(&mut guest, |message| {
+        matches!(message, ServerMessage::Denied { .. }).then_some(())
+    });
+
+    // Codes can be typed in lower case
+    guest
+        .send(&ClientMessage::JoinRoom {
+            code: code.to_lowercase(),
+        })
+        .unwrap();
+    let room = room_update(&mut guest, |room| room.members.len() == 2);
+    let guest_player = room.you;
+    guest
+        .send(&ClientMessage::ChooseRole { role: Role::Healer })
+        .unwrap();
+    guest
+        .send(&ClientMessage::SetReady { ready: true })
+        .unwrap();
+
+    // Nothing starts until everyone is ready
+    room_update(&mut host, |room| {
+        room.member(guest_player).is_some_and(|member| member.ready)
+    });
+    host.send(&ClientMessage::SetReady { ready: true }).unwrap();
+
+    let welcome = |message: &ServerMessage| match message {
+        ServerMessage::Welcome { player, level } => Some((*player, level.clone())),
+        _ => None,
+    };
+    let (host_player, level) = wait_for(&mut host, welcome);
+    let (player, _) = wait_for(&mut guest, welcome);
+    assert_eq!(player, guest_player);
+    assert_ne!(host_player, guest_player);
+
+    // The empty slot went to a companion, a diva since a player heals
+    let kinds: Vec<_> = level.companions.iter().map(|c| c.kind).collect();
+    assert_eq!(kinds, [CompanionKind::Diva]);
+
+    let snapshot = next_snapshot(&mut host, |snapshot| snapshot.bees.len() == 2);
+    assert_eq!(snapshot.bee(guest_player).unwrap().role, Role::Healer);
+    assert_eq!(snapshot.bee(host_player).unwrap().role, Role::Gatherer);
+    assert_eq!(snapshot.companions.len(), 1);
+}